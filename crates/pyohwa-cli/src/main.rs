@@ -2,6 +2,7 @@ use std::path::PathBuf;
 use std::process;
 
 use clap::{Parser, Subcommand};
+use pyohwa_core::build::pipeline::BuildOptions;
 
 #[derive(Parser)]
 #[command(name = "pyohwa", version, about = "Rust + Elm static site generator")]
@@ -22,6 +23,15 @@ enum Command {
         /// Project root directory (default: current directory)
         #[arg(short, long, default_value = ".")]
         root: PathBuf,
+        /// Worker threads for page processing (default: `[build] jobs`, or all cores)
+        #[arg(short, long)]
+        jobs: Option<usize>,
+        /// Fail on broken internal links instead of warning
+        #[arg(long, default_value = "false")]
+        strict: bool,
+        /// Skip pages with content errors, build the rest, then report the errors and fail
+        #[arg(long, default_value = "false")]
+        keep_going: bool,
     },
     /// Start dev server with live reload
     Dev {
@@ -34,6 +44,9 @@ enum Command {
         /// Open browser automatically
         #[arg(long, default_value = "false")]
         open: bool,
+        /// Worker threads for page processing (default: `[build] jobs`, or all cores)
+        #[arg(short, long)]
+        jobs: Option<usize>,
     },
 }
 
@@ -42,8 +55,33 @@ fn main() {
 
     let result = match cli.command {
         Command::Init { name } => run_init(&name),
-        Command::Build { root } => run_build(&root),
-        Command::Dev { root, port, open } => run_dev(&root, port, open),
+        Command::Build {
+            root,
+            jobs,
+            strict,
+            keep_going,
+        } => run_build(
+            &root,
+            BuildOptions {
+                jobs,
+                strict,
+                keep_going,
+            },
+        ),
+        Command::Dev {
+            root,
+            port,
+            open,
+            jobs,
+        } => run_dev(
+            &root,
+            port,
+            open,
+            BuildOptions {
+                jobs,
+                ..Default::default()
+            },
+        ),
     };
 
     if let Err(e) = result {
@@ -97,20 +135,41 @@ description = ""
     Ok(())
 }
 
-fn run_build(root: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+fn run_build(root: &PathBuf, options: BuildOptions) -> Result<(), Box<dyn std::error::Error>> {
     let project_root = if root == &PathBuf::from(".") {
         std::env::current_dir()?
     } else {
         std::fs::canonicalize(root)?
     };
 
-    pyohwa_core::build::pipeline::build(&project_root)?;
+    let report = pyohwa_core::build::pipeline::build_with_options(&project_root, &options)?;
+    for warning in &report.warnings {
+        eprintln!("warning: {warning}");
+    }
+    for link in &report.broken_links {
+        eprintln!("warning: {link}");
+    }
+
+    let diagnostics = &report.diagnostics;
+    if !diagnostics.is_empty() {
+        eprintln!("{diagnostics}\n");
+        return Err(format!(
+            "build finished with {} page(s) skipped",
+            diagnostics.file_count()
+        )
+        .into());
+    }
 
     println!("Build complete.");
     Ok(())
 }
 
-fn run_dev(root: &PathBuf, port: u16, open: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn run_dev(
+    root: &PathBuf,
+    port: u16,
+    open: bool,
+    build_options: BuildOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
     let project_root = if root == &PathBuf::from(".") {
         std::env::current_dir()?
     } else {
@@ -121,6 +180,7 @@ fn run_dev(root: &PathBuf, port: u16, open: bool) -> Result<(), Box<dyn std::err
         port,
         project_root,
         open,
+        build_options,
     };
 
     let rt = tokio::runtime::Runtime::new()?;
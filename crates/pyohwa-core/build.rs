@@ -13,6 +13,7 @@ fn main() {
     println!("cargo:rerun-if-changed=../../themes/default");
     println!("cargo:rerun-if-changed=../../elm/src");
     println!("cargo:rerun-if-changed=../../elm/elm.json");
+    println!("cargo:rerun-if-changed=../../elm/dist/elm.min.js");
     println!("cargo:rerun-if-changed=pre-built");
 
     // --- Elm JS ---
@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::content::schema::SchemaViolation;
+use crate::error::ContentError;
+
+/// A problem with one content file. The page is skipped, but the build can go on
+/// to find the problems in other files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// Source file, relative to the project root once collected
+    pub path: PathBuf,
+    /// What went wrong, e.g. `invalid frontmatter`, used to count problems by kind
+    pub kind: &'static str,
+    pub message: String,
+    /// 1-based line and column, when the problem has a position
+    pub position: Option<(usize, usize)>,
+    /// The source line at `position`
+    pub snippet: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn from_content_error(err: ContentError) -> Self {
+        let (path, kind, message, position) = match err {
+            ContentError::EmptyContent { path } => {
+                (path, "empty content", "the file is empty".to_string(), None)
+            }
+            ContentError::MissingFrontmatter { path } => (
+                path,
+                "missing frontmatter",
+                "no `---` frontmatter block at the start of the file".to_string(),
+                Some((1, 1)),
+            ),
+            ContentError::InvalidFrontmatter {
+                path,
+                line,
+                column,
+                reason,
+            } => (path, "invalid frontmatter", reason, Some((line, column))),
+            ContentError::MissingTitle { path } => (
+                path,
+                "missing title",
+                "the frontmatter has no `title`".to_string(),
+                None,
+            ),
+            ContentError::Math {
+                path,
+                line,
+                column,
+                reason,
+            } => (path, "invalid math", reason, Some((line, column))),
+            ContentError::Include { path, reason } => (path, "invalid include", reason, None),
+            ContentError::IncludeCycle { path, chain } => (path, "include cycle", chain, None),
+        };
+        Self {
+            path,
+            kind,
+            message,
+            position,
+            snippet: None,
+        }
+    }
+
+    /// Make the path relative to `root` and pick the snippet out of the file's `source`.
+    fn locate(mut self, root: &Path, source: &str) -> Self {
+        if let Ok(relative) = self.path.strip_prefix(root) {
+            self.path = relative.to_path_buf();
+        }
+        self.snippet = self
+            .position
+            .and_then(|(line, _)| source.lines().nth(line.checked_sub(1)?))
+            .map(str::to_string);
+        self
+    }
+}
+
+impl From<SchemaViolation> for Diagnostic {
+    fn from(violation: SchemaViolation) -> Self {
+        Self {
+            path: violation.path,
+            kind: "schema violation",
+            message: violation.message,
+            position: Some((violation.line, violation.column)),
+            snippet: None,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some((line, column)) = self.position {
+            write!(f, ":{line}:{column}")?;
+        }
+        write!(f, ": {}: {}", self.kind, self.message)
+    }
+}
+
+/// Content problems collected across the whole build, in discovery order.
+///
+/// Displays as a report grouped by file, with the offending source line under
+/// each positioned problem and the counts by kind at the end:
+///
+/// ```text
+/// content/guide.md (2 errors)
+///   3:9: schema violation: 'status' must be one of beta, stable; got `alpha`
+///      3 | status: alpha
+///        |         ^
+///   invalid include: 'missing.md' not found
+///
+/// 2 errors in 1 file: 1 invalid include, 1 schema violation
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diagnostics {
+    items: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// Record the problems of one content file, whose text is `source`.
+    pub fn extend(
+        &mut self,
+        root: &Path,
+        source: &str,
+        diagnostics: impl IntoIterator<Item = Diagnostic>,
+    ) {
+        self.items.extend(
+            diagnostics
+                .into_iter()
+                .map(|diagnostic| diagnostic.locate(root, source)),
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.items.iter()
+    }
+
+    /// Number of distinct files with problems, i.e. pages skipped.
+    pub fn file_count(&self) -> usize {
+        let mut paths: Vec<_> = self.items.iter().map(|d| &d.path).collect();
+        paths.dedup();
+        paths.len()
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut files: Vec<(&Path, Vec<&Diagnostic>)> = Vec::new();
+        for diagnostic in &self.items {
+            match files.last_mut() {
+                Some((path, group)) if *path == diagnostic.path => group.push(diagnostic),
+                _ => files.push((&diagnostic.path, vec![diagnostic])),
+            }
+        }
+
+        for (path, group) in &files {
+            writeln!(f, "{} ({})", path.display(), plural(group.len(), "error"))?;
+            for diagnostic in group {
+                match diagnostic.position {
+                    Some((line, column)) => writeln!(
+                        f,
+                        "  {line}:{column}: {}: {}",
+                        diagnostic.kind, diagnostic.message
+                    )?,
+                    None => writeln!(f, "  {}: {}", diagnostic.kind, diagnostic.message)?,
+                }
+                if let (Some((line, column)), Some(snippet)) =
+                    (diagnostic.position, &diagnostic.snippet)
+                {
+                    let gutter = " ".repeat(line.to_string().len());
+                    writeln!(f, "    {line} | {snippet}")?;
+                    writeln!(
+                        f,
+                        "    {gutter} | {}^",
+                        " ".repeat(column.saturating_sub(1))
+                    )?;
+                }
+            }
+            writeln!(f)?;
+        }
+
+        let mut kinds: BTreeMap<&str, usize> = BTreeMap::new();
+        for diagnostic in &self.items {
+            *kinds.entry(diagnostic.kind).or_default() += 1;
+        }
+        let kinds: Vec<_> = kinds
+            .into_iter()
+            .map(|(kind, count)| plural(count, kind))
+            .collect();
+        write!(
+            f,
+            "{} in {}: {}",
+            plural(self.items.len(), "error"),
+            plural(files.len(), "file"),
+            kinds.join(", ")
+        )
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_groups_by_file_with_snippets_and_counts() {
+        let root = Path::new("/site");
+        let mut diagnostics = Diagnostics::default();
+        diagnostics.extend(
+            root,
+            "---\ntitle: A\nstatus: alpha\n---\n",
+            [
+                Diagnostic::from(SchemaViolation {
+                    path: root.join("content/a.md"),
+                    line: 3,
+                    column: 9,
+                    message: "'status' must be one of beta, stable; got `alpha`".to_string(),
+                }),
+                Diagnostic::from_content_error(ContentError::Include {
+                    path: root.join("content/a.md"),
+                    reason: "'missing.md' not found".to_string(),
+                }),
+            ],
+        );
+        diagnostics.extend(
+            root,
+            "",
+            [Diagnostic::from_content_error(ContentError::EmptyContent {
+                path: root.join("content/b.md"),
+            })],
+        );
+
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics.file_count(), 2);
+        assert_eq!(
+            diagnostics.to_string(),
+            "content/a.md (2 errors)
+  3:9: schema violation: 'status' must be one of beta, stable; got `alpha`
+    3 | status: alpha
+      |         ^
+  invalid include: 'missing.md' not found
+
+content/b.md (1 error)
+  empty content: the file is empty
+
+3 errors in 2 files: 1 empty content, 1 invalid include, 1 schema violation"
+        );
+    }
+
+    #[test]
+    fn diagnostic_displays_on_one_line() {
+        let diagnostic = Diagnostic::from_content_error(ContentError::InvalidFrontmatter {
+            path: PathBuf::from("content/a.md"),
+            line: 3,
+            column: 8,
+            reason: "'order' must be an integer, got a string `first`".to_string(),
+        });
+        assert_eq!(
+            diagnostic.to_string(),
+            "content/a.md:3:8: invalid frontmatter: 'order' must be an integer, got a string `first`"
+        );
+    }
+}
@@ -1,31 +1,100 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
 
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
 
+use crate::config::Config;
 use crate::content::page::RawContent;
+use crate::content::{frontmatter, include};
 use crate::error::BuildError;
-
-/// Maps file paths to their SHA-256 content hashes.
-pub type BuildManifest = HashMap<PathBuf, String>;
+use crate::markdown::highlight::{SYNTAXES_DIR, THEMES_DIR};
+use crate::markdown::parser;
+use crate::site::route::resolve_route;
 
 const MANIFEST_DIR: &str = ".pyohwa";
 const MANIFEST_FILE: &str = "manifest.json";
+const CONFIG_FILE: &str = "pyohwa.toml";
+
+/// Snapshot of every build input, keyed by paths relative to the project root.
+///
+/// Comparing two manifests tells which outputs are stale:
+/// - `config` / `theme` changes invalidate every page (`theme` covers the
+///   project's `themes/` and `syntaxes/` directories)
+/// - `nav` covers the inputs of the sidebar, nav and prev/next links,
+///   which every page embeds, and every page's heading ids, which anchor
+///   links on other pages are checked against
+/// - `pages` track each page and the files it depends on: static files in
+///   `files`, snippet and partial sources in `includes`
+/// - `files` also covers the non-Markdown files in the content directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BuildManifest {
+    pub config: String,
+    pub theme: String,
+    pub nav: String,
+    pub pages: BTreeMap<PathBuf, PageEntry>,
+    pub files: BTreeMap<PathBuf, String>,
+    pub includes: BTreeMap<PathBuf, String>,
+}
+
+/// Manifest record for a single content file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PageEntry {
+    /// SHA-256 of the raw markdown source
+    pub hash: String,
+    /// Output file path relative to the output dir
+    pub output: PathBuf,
+    /// Project-relative files the page references (static assets, snippets)
+    pub deps: Vec<PathBuf>,
+}
+
+/// What an incremental build has to redo, derived from two manifests.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RebuildPlan {
+    /// Config, theme or navigation inputs changed: re-render every page
+    pub full: bool,
+    /// Project-relative content files whose output must be re-rendered
+    pub pages: BTreeSet<PathBuf>,
+    /// Output files of deleted pages, relative to the output dir
+    pub removed_outputs: Vec<PathBuf>,
+    /// Static files were added, changed or removed
+    pub static_changed: bool,
+}
+
+impl RebuildPlan {
+    /// Returns true if nothing needs to be rebuilt.
+    pub fn is_empty(&self) -> bool {
+        !self.full
+            && self.pages.is_empty()
+            && self.removed_outputs.is_empty()
+            && !self.static_changed
+    }
+}
 
 /// Compute SHA-256 hash of content.
 pub fn hash_content(content: &str) -> String {
+    hash_bytes(content.as_bytes())
+}
+
+/// Compute SHA-256 hash of raw bytes.
+pub fn hash_bytes(bytes: &[u8]) -> String {
     let mut hasher = Sha256::new();
-    hasher.update(content.as_bytes());
+    hasher.update(bytes);
     format!("{:x}", hasher.finalize())
 }
 
 /// Load the build manifest from `.pyohwa/manifest.json`.
-/// Returns an empty manifest if the file doesn't exist.
+/// Returns an empty manifest if the file doesn't exist or uses an older format.
 pub fn load_manifest(project_root: &Path) -> BuildManifest {
     let path = project_root.join(MANIFEST_DIR).join(MANIFEST_FILE);
     match std::fs::read_to_string(&path) {
         Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
-        Err(_) => HashMap::new(),
+        Err(_) => BuildManifest::default(),
     }
 }
 
@@ -40,40 +109,284 @@ pub fn save_manifest(project_root: &Path, manifest: &BuildManifest) -> Result<()
     Ok(())
 }
 
-/// Compare raw contents against a previous manifest.
-/// Returns `(changed_paths, new_manifest)`.
-/// A file is considered changed if it's new or its hash differs.
-pub fn detect_changes(
+/// Build a manifest describing the current state of the project's build inputs.
+pub fn build_manifest(
+    project_root: &Path,
+    config: &Config,
     raw_contents: &[RawContent],
-    old_manifest: &BuildManifest,
-) -> (Vec<PathBuf>, BuildManifest) {
-    let mut new_manifest = BuildManifest::new();
-    let mut changed = Vec::new();
+) -> BuildManifest {
+    let content_dir = project_root.join(&config.build.content_dir);
+    let static_dir = project_root.join(&config.build.static_dir);
+
+    let config_raw = std::fs::read(project_root.join(CONFIG_FILE)).unwrap_or_default();
+    // Include targets come back canonicalized
+    let canonical_root = project_root
+        .canonicalize()
+        .unwrap_or_else(|_| project_root.to_path_buf());
+
+    let mut pages = BTreeMap::new();
+    let mut includes = BTreeMap::new();
+    let mut nav_inputs = String::new();
 
     for raw in raw_contents {
-        let hash = hash_content(&raw.raw);
-        let old_hash = old_manifest.get(&raw.path);
+        let relative = relative_to(project_root, &raw.path);
+        let route = resolve_route(&content_dir, &raw.path);
 
-        if old_hash != Some(&hash) {
-            changed.push(raw.path.clone());
+        // Only the fields feeding the sidebar, nav and prev/next links matter here,
+        // plus the heading ids that `page#id` links elsewhere resolve to.
+        // Unparseable files fold their whole source in, so fixing them triggers a full rebuild.
+        match frontmatter::parse_frontmatter(raw) {
+            Ok(parsed) => {
+                let fm = &parsed.frontmatter;
+                let badge = config
+                    .sidebar
+                    .badge
+                    .as_ref()
+                    .map(|key| fm.extra_values(key));
+                let body = include::expand_content(&parsed, project_root)
+                    .map_or_else(|_| parsed.body.clone(), |expanded| expanded.body);
+                let heading_ids = parser::heading_ids(&body, config.markdown.math);
+                nav_inputs.push_str(&format!(
+                    "{}\0{}\0{:?}\0{:?}\0{}\0{:?}\0{:?}\0{:?}\0{}\n",
+                    relative.display(),
+                    fm.title,
+                    fm.order,
+                    fm.layout,
+                    fm.draft,
+                    fm.prev,
+                    fm.next,
+                    badge,
+                    heading_ids.join(" ")
+                ));
+            }
+            Err(_) => {
+                nav_inputs.push_str(&format!("{}\0{}\n", relative.display(), raw.raw));
+            }
         }
 
-        new_manifest.insert(raw.path.clone(), hash);
+        let mut deps: Vec<PathBuf> = scan_static_refs(&raw.raw, &static_dir, &config.site.base_url)
+            .into_iter()
+            .map(|dep| relative_to(project_root, &dep))
+            .collect();
+
+        // A broken include fails the build itself, so nothing is recorded for it here
+        let included = include::expand(&raw.raw, &raw.path, project_root)
+            .map(|expanded| expanded.deps)
+            .unwrap_or_default();
+        for dep in included {
+            if let Ok(bytes) = std::fs::read(&dep) {
+                let dep = relative_to(&canonical_root, &dep);
+                includes.insert(dep.clone(), hash_bytes(&bytes));
+                deps.push(dep);
+            }
+        }
+
+        pages.insert(
+            relative,
+            PageEntry {
+                hash: hash_content(&raw.raw),
+                output: route.output,
+                deps,
+            },
+        );
     }
 
-    // Detect deleted files (in old but not in new)
-    for old_path in old_manifest.keys() {
-        if !new_manifest.contains_key(old_path) {
-            changed.push(old_path.clone());
+    let mut theme_files = hash_tree(project_root, &project_root.join(THEMES_DIR));
+    theme_files.extend(hash_tree(project_root, &project_root.join(SYNTAXES_DIR)));
+    if let Some(custom_css) = &config.theme.custom_css {
+        if let Ok(bytes) = std::fs::read(project_root.join(custom_css)) {
+            theme_files.insert(custom_css.clone(), hash_bytes(&bytes));
         }
     }
+    let theme_inputs: String = theme_files
+        .iter()
+        .map(|(path, hash)| format!("{}\0{hash}\n", path.display()))
+        .collect();
+
+    // Static files, and the non-Markdown files next to the pages, which are copied alike
+    let mut files = hash_tree(project_root, &static_dir);
+    files.extend(
+        hash_tree(project_root, &content_dir)
+            .into_iter()
+            .filter(|(path, _)| path.extension().and_then(|e| e.to_str()) != Some("md")),
+    );
 
-    (changed, new_manifest)
+    BuildManifest {
+        config: hash_bytes(&config_raw),
+        theme: hash_content(&theme_inputs),
+        nav: hash_content(&nav_inputs),
+        pages,
+        files,
+        includes,
+    }
+}
+
+/// Compare a previous manifest against the current one and decide what to rebuild.
+pub fn plan_rebuild(old: &BuildManifest, new: &BuildManifest) -> RebuildPlan {
+    let mut plan = RebuildPlan {
+        full: old.config != new.config || old.theme != new.theme || old.nav != new.nav,
+        ..RebuildPlan::default()
+    };
+
+    let changed_files: BTreeSet<&PathBuf> = new
+        .files
+        .iter()
+        .filter(|(path, hash)| old.files.get(*path) != Some(hash))
+        .map(|(path, _)| path)
+        .chain(
+            old.files
+                .keys()
+                .filter(|path| !new.files.contains_key(*path)),
+        )
+        .collect();
+
+    plan.static_changed = !changed_files.is_empty();
+
+    let changed_includes = new
+        .includes
+        .iter()
+        .filter(|(path, hash)| old.includes.get(*path) != Some(hash))
+        .map(|(path, _)| path)
+        .chain(
+            old.includes
+                .keys()
+                .filter(|path| !new.includes.contains_key(*path)),
+        );
+    let changed_files: BTreeSet<&PathBuf> =
+        changed_files.into_iter().chain(changed_includes).collect();
+
+    for (path, entry) in &new.pages {
+        let stale = match old.pages.get(path) {
+            Some(old_entry) => old_entry.hash != entry.hash,
+            None => true,
+        };
+        if stale || entry.deps.iter().any(|dep| changed_files.contains(dep)) {
+            plan.pages.insert(path.clone());
+        }
+    }
+
+    for (path, entry) in &old.pages {
+        if !new.pages.contains_key(path) {
+            plan.removed_outputs.push(entry.output.clone());
+        }
+    }
+
+    plan
+}
+
+static MARKDOWN_LINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\]\(\s*<?([^)\s>]+)"#).unwrap());
+static HTML_ATTR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?:src|href)\s*=\s*["']([^"']+)["']"#).unwrap());
+
+/// Find absolute links in markdown source that point at files in the static directory.
+///
+/// Both markdown links/images (`![alt](/logo.png)`) and raw HTML attributes
+/// (`<img src="/logo.png">`) are recognized. The configured base URL is stripped first.
+pub fn scan_static_refs(markdown: &str, static_dir: &Path, base_url: &str) -> Vec<PathBuf> {
+    let base = base_url.trim_end_matches('/');
+    let mut refs = BTreeSet::new();
+
+    let targets = MARKDOWN_LINK_RE
+        .captures_iter(markdown)
+        .chain(HTML_ATTR_RE.captures_iter(markdown))
+        .filter_map(|caps| caps.get(1).map(|m| m.as_str()));
+
+    for target in targets {
+        let target = target.split(['#', '?']).next().unwrap_or("");
+        let Some(path) = target
+            .strip_prefix(base)
+            .unwrap_or(target)
+            .strip_prefix('/')
+        else {
+            continue;
+        };
+        if path.is_empty() {
+            continue;
+        }
+        let candidate = static_dir.join(path);
+        if candidate.is_file() {
+            refs.insert(candidate);
+        }
+    }
+
+    refs.into_iter().collect()
+}
+
+/// Hash every file under `dir`, keyed by its path relative to `project_root`.
+fn hash_tree(project_root: &Path, dir: &Path) -> BTreeMap<PathBuf, String> {
+    let mut files = BTreeMap::new();
+    if !dir.exists() {
+        return files;
+    }
+
+    for entry in WalkDir::new(dir)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        if let Ok(bytes) = std::fs::read(entry.path()) {
+            files.insert(relative_to(project_root, entry.path()), hash_bytes(&bytes));
+        }
+    }
+
+    files
+}
+
+fn relative_to(root: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(root).unwrap_or(path).to_path_buf()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
+
+    fn page(hash: &str, deps: &[&str]) -> PageEntry {
+        PageEntry {
+            hash: hash.to_string(),
+            output: PathBuf::from("out/index.html"),
+            deps: deps.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    fn manifest(pages: &[(&str, PageEntry)]) -> BuildManifest {
+        BuildManifest {
+            config: "config".to_string(),
+            theme: "theme".to_string(),
+            nav: "nav".to_string(),
+            pages: pages
+                .iter()
+                .map(|(path, entry)| (PathBuf::from(path), entry.clone()))
+                .collect(),
+            files: BTreeMap::new(),
+            includes: BTreeMap::new(),
+        }
+    }
+
+    fn write_project(root: &Path) {
+        fs::create_dir_all(root.join("content/guide")).unwrap();
+        fs::create_dir_all(root.join("static/img")).unwrap();
+        fs::write(root.join("pyohwa.toml"), "[site]\ntitle = \"T\"\n").unwrap();
+        fs::write(root.join("static/img/logo.png"), "png").unwrap();
+        fs::write(
+            root.join("content/index.md"),
+            "---\ntitle: Home\n---\n![logo](/img/logo.png)\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("content/guide/intro.md"),
+            "---\ntitle: Intro\n---\n# Intro\n",
+        )
+        .unwrap();
+    }
+
+    fn current_manifest(root: &Path) -> BuildManifest {
+        let config = crate::config::load(root).unwrap();
+        let raw = crate::content::loader::discover(&root.join("content")).unwrap();
+        build_manifest(root, &config, &raw)
+    }
 
     #[test]
     fn hash_is_deterministic() {
@@ -91,71 +404,203 @@ mod tests {
     }
 
     #[test]
-    fn first_run_detects_all_as_changed() {
-        let raw = vec![
-            RawContent {
-                path: PathBuf::from("a.md"),
-                raw: "# A".to_string(),
-            },
-            RawContent {
-                path: PathBuf::from("b.md"),
-                raw: "# B".to_string(),
-            },
-        ];
-
-        let (changed, manifest) = detect_changes(&raw, &HashMap::new());
-        assert_eq!(changed.len(), 2);
-        assert_eq!(manifest.len(), 2);
+    fn first_run_is_a_full_rebuild() {
+        let new = manifest(&[("a.md", page("1", &[])), ("b.md", page("2", &[]))]);
+        let plan = plan_rebuild(&BuildManifest::default(), &new);
+        assert!(plan.full);
+        assert_eq!(plan.pages.len(), 2);
     }
 
     #[test]
     fn no_changes_detected_when_content_same() {
-        let raw = vec![RawContent {
-            path: PathBuf::from("a.md"),
-            raw: "# A".to_string(),
-        }];
+        let old = manifest(&[("a.md", page("1", &[]))]);
+        let plan = plan_rebuild(&old, &old.clone());
+        assert!(plan.is_empty());
+    }
 
-        let mut old = HashMap::new();
-        old.insert(PathBuf::from("a.md"), hash_content("# A"));
+    #[test]
+    fn detects_modified_page_only() {
+        let old = manifest(&[("a.md", page("1", &[])), ("b.md", page("2", &[]))]);
+        let new = manifest(&[("a.md", page("1b", &[])), ("b.md", page("2", &[]))]);
 
-        let (changed, _) = detect_changes(&raw, &old);
-        assert!(changed.is_empty());
+        let plan = plan_rebuild(&old, &new);
+        assert!(!plan.full);
+        assert_eq!(
+            plan.pages.into_iter().collect::<Vec<_>>(),
+            vec![PathBuf::from("a.md")]
+        );
     }
 
     #[test]
-    fn detects_modified_file() {
-        let raw = vec![RawContent {
-            path: PathBuf::from("a.md"),
-            raw: "# A modified".to_string(),
-        }];
+    fn config_change_forces_full_rebuild() {
+        let old = manifest(&[("a.md", page("1", &[]))]);
+        let mut new = old.clone();
+        new.config = "other".to_string();
+        assert!(plan_rebuild(&old, &new).full);
+    }
 
-        let mut old = HashMap::new();
-        old.insert(PathBuf::from("a.md"), hash_content("# A"));
+    #[test]
+    fn nav_change_forces_full_rebuild() {
+        let old = manifest(&[("a.md", page("1", &[]))]);
+        let mut new = old.clone();
+        new.nav = "other".to_string();
+        assert!(plan_rebuild(&old, &new).full);
+    }
 
-        let (changed, _) = detect_changes(&raw, &old);
-        assert_eq!(changed.len(), 1);
-        assert_eq!(changed[0], PathBuf::from("a.md"));
+    #[test]
+    fn static_change_invalidates_dependent_pages() {
+        let mut old = manifest(&[
+            ("a.md", page("1", &["static/logo.png"])),
+            ("b.md", page("2", &[])),
+        ]);
+        old.files
+            .insert(PathBuf::from("static/logo.png"), "x".to_string());
+        let mut new = old.clone();
+        new.files
+            .insert(PathBuf::from("static/logo.png"), "y".to_string());
+
+        let plan = plan_rebuild(&old, &new);
+        assert!(!plan.full);
+        assert!(plan.static_changed);
+        assert_eq!(
+            plan.pages.into_iter().collect::<Vec<_>>(),
+            vec![PathBuf::from("a.md")]
+        );
     }
 
     #[test]
-    fn detects_new_file() {
-        let raw = vec![
-            RawContent {
-                path: PathBuf::from("a.md"),
-                raw: "# A".to_string(),
-            },
-            RawContent {
-                path: PathBuf::from("b.md"),
-                raw: "# B".to_string(),
-            },
-        ];
+    fn deleted_page_reports_its_output() {
+        let old = manifest(&[("a.md", page("1", &[])), ("b.md", page("2", &[]))]);
+        let new = manifest(&[("a.md", page("1", &[]))]);
+        let plan = plan_rebuild(&old, &new);
+        assert_eq!(plan.removed_outputs, vec![PathBuf::from("out/index.html")]);
+    }
+
+    #[test]
+    fn scan_static_refs_finds_links_and_html() {
+        let tmp = tempfile::tempdir().unwrap();
+        let static_dir = tmp.path().join("static");
+        fs::create_dir_all(static_dir.join("img")).unwrap();
+        fs::write(static_dir.join("img/a.png"), "a").unwrap();
+        fs::write(static_dir.join("b.pdf"), "b").unwrap();
+
+        let md = "![a](/docs/img/a.png) <a href=\"/docs/b.pdf#page=2\">b</a> [x](/missing.png) [y](https://example.com)";
+        let refs = scan_static_refs(md, &static_dir, "/docs/");
+        assert_eq!(
+            refs,
+            vec![static_dir.join("b.pdf"), static_dir.join("img/a.png")]
+        );
+    }
+
+    #[test]
+    fn build_manifest_tracks_pages_and_static_deps() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        write_project(root);
+
+        let manifest = current_manifest(root);
+        assert_eq!(manifest.pages.len(), 2);
+        assert_eq!(
+            manifest.pages[&PathBuf::from("content/index.md")].deps,
+            vec![PathBuf::from("static/img/logo.png")]
+        );
+        assert_eq!(
+            manifest.pages[&PathBuf::from("content/guide/intro.md")].output,
+            PathBuf::from("guide/intro/index.html")
+        );
+        assert!(manifest
+            .files
+            .contains_key(&PathBuf::from("static/img/logo.png")));
+    }
+
+    #[test]
+    fn include_change_invalidates_only_including_pages() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        write_project(root);
+        fs::create_dir_all(root.join("snippets")).unwrap();
+        fs::write(root.join("snippets/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(
+            root.join("content/guide/intro.md"),
+            "---\ntitle: Intro\n---\n<<< @/snippets/main.rs\n",
+        )
+        .unwrap();
+        let before = current_manifest(root);
+        assert_eq!(
+            before.pages[&PathBuf::from("content/guide/intro.md")].deps,
+            vec![PathBuf::from("snippets/main.rs")]
+        );
+
+        fs::write(root.join("snippets/main.rs"), "fn main() { run() }\n").unwrap();
+        let after = current_manifest(root);
+        let plan = plan_rebuild(&before, &after);
+        assert!(!plan.full);
+        assert!(!plan.static_changed);
+        assert_eq!(
+            plan.pages.into_iter().collect::<Vec<_>>(),
+            vec![PathBuf::from("content/guide/intro.md")]
+        );
+    }
+
+    #[test]
+    fn body_edit_does_not_touch_nav_hash() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        write_project(root);
+        let before = current_manifest(root);
+
+        fs::write(
+            root.join("content/guide/intro.md"),
+            "---\ntitle: Intro\n---\n# Intro\n\nMore text.\n",
+        )
+        .unwrap();
+        let after = current_manifest(root);
+        assert_eq!(before.nav, after.nav);
+        assert!(!plan_rebuild(&before, &after).full);
+
+        fs::write(
+            root.join("content/guide/intro.md"),
+            "---\ntitle: Introduction\n---\n# Intro\n",
+        )
+        .unwrap();
+        let renamed = current_manifest(root);
+        assert!(plan_rebuild(&after, &renamed).full);
+    }
+
+    #[test]
+    fn heading_rename_forces_full_rebuild() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        write_project(root);
+        fs::write(
+            root.join("content/guide/intro.md"),
+            "---\ntitle: Intro\n---\n# Intro\n\n## Old name\n",
+        )
+        .unwrap();
+        let before = current_manifest(root);
+
+        // Another page may link to `/guide/intro#old-name`
+        fs::write(
+            root.join("content/guide/intro.md"),
+            "---\ntitle: Intro\n---\n# Intro\n\n## New name\n",
+        )
+        .unwrap();
+        let after = current_manifest(root);
+        assert_ne!(before.nav, after.nav);
+        assert!(plan_rebuild(&before, &after).full);
+    }
 
-        let mut old = HashMap::new();
-        old.insert(PathBuf::from("a.md"), hash_content("# A"));
+    #[test]
+    fn theme_file_change_forces_full_rebuild() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        write_project(root);
+        let before = current_manifest(root);
 
-        let (changed, _) = detect_changes(&raw, &old);
-        assert_eq!(changed.len(), 1);
-        assert_eq!(changed[0], PathBuf::from("b.md"));
+        fs::create_dir_all(root.join("themes/default")).unwrap();
+        fs::write(root.join("themes/default/theme.css"), "body {}").unwrap();
+        let after = current_manifest(root);
+        assert!(plan_rebuild(&before, &after).full);
     }
 
     #[test]
@@ -163,13 +608,29 @@ mod tests {
         let tmp = tempfile::tempdir().unwrap();
         let root = tmp.path();
 
-        let mut manifest = BuildManifest::new();
-        manifest.insert(PathBuf::from("a.md"), "abc123".to_string());
-        manifest.insert(PathBuf::from("b.md"), "def456".to_string());
+        let mut manifest = manifest(&[("a.md", page("abc123", &["static/x.png"]))]);
+        manifest
+            .files
+            .insert(PathBuf::from("static/x.png"), "def456".to_string());
 
         save_manifest(root, &manifest).unwrap();
         let loaded = load_manifest(root);
 
         assert_eq!(loaded, manifest);
     }
+
+    #[test]
+    fn legacy_manifest_loads_as_empty() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join(MANIFEST_DIR)).unwrap();
+        fs::write(
+            root.join(MANIFEST_DIR).join(MANIFEST_FILE),
+            r#"{"content/a.md": "abc"}"#,
+        )
+        .unwrap();
+
+        let loaded = load_manifest(root);
+        assert!(plan_rebuild(&loaded, &manifest(&[("a.md", page("1", &[]))])).full);
+    }
 }
@@ -1,3 +1,4 @@
+pub mod diagnostics;
 pub mod incremental;
 pub mod output;
 pub mod pipeline;
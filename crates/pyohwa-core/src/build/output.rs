@@ -1,16 +1,21 @@
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use pyohwa_search::shard::{IndexFile, SHARD_DIR, SHARD_PREFIXES};
 
 use crate::config::Config;
 use crate::content::page::Page;
 use crate::error::BuildError;
-use crate::render::embedded;
+use crate::render::template::SiteData;
+use crate::render::theme::Theme;
 use crate::site::route::Route;
 
-/// Write rendered HTML files and embedded assets to the output directory
+/// Write rendered HTML files and theme assets to the output directory
 /// without cleaning the directory first. Used for incremental dev builds.
 pub fn write_output_incremental(
     pages: &[(Route, String)],
+    theme: &Theme,
     output_dir: &Path,
 ) -> Result<(), BuildError> {
     fs::create_dir_all(output_dir)?;
@@ -23,20 +28,33 @@ pub fn write_output_incremental(
         fs::write(&output_path, html)?;
     }
 
-    let assets_dir = output_dir.join("assets");
-    fs::create_dir_all(&assets_dir)?;
-    fs::write(assets_dir.join("elm.min.js"), embedded::ELM_JS)?;
-    fs::write(assets_dir.join("theme.css"), embedded::THEME_CSS)?;
+    write_theme_assets(theme, output_dir)
+}
 
+/// Remove the output file of a deleted page, along with its directory if it is left empty.
+pub fn remove_output(output: &Path, output_dir: &Path) -> Result<(), BuildError> {
+    let output_path = output_dir.join(output);
+    if output_path.exists() {
+        fs::remove_file(&output_path)?;
+    }
+    if let Some(parent) = output_path.parent() {
+        if parent != output_dir && fs::read_dir(parent).is_ok_and(|mut d| d.next().is_none()) {
+            fs::remove_dir(parent)?;
+        }
+    }
     Ok(())
 }
 
-/// Write rendered HTML files and embedded assets to the output directory.
+/// Write rendered HTML files and theme assets to the output directory.
 ///
 /// 1. Clean and recreate the output directory
 /// 2. Write each HTML page at its route output path
-/// 3. Extract embedded assets (elm.min.js, theme.css) to dist/assets/
-pub fn write_output(pages: &[(Route, String)], output_dir: &Path) -> Result<(), BuildError> {
+/// 3. Write the theme's assets (elm.min.js, theme.css) to dist/assets/
+pub fn write_output(
+    pages: &[(Route, String)],
+    theme: &Theme,
+    output_dir: &Path,
+) -> Result<(), BuildError> {
     // Clean output directory
     if output_dir.exists() {
         fs::remove_dir_all(output_dir)?;
@@ -52,22 +70,105 @@ pub fn write_output(pages: &[(Route, String)], output_dir: &Path) -> Result<(),
         fs::write(&output_path, html)?;
     }
 
-    // Write embedded assets
+    // Write theme assets
+    write_theme_assets(theme, output_dir)
+}
+
+/// Write the theme's compiled app and stylesheet to `assets/`; the embedded
+/// default fills in for whichever the theme does not ship. The project's
+/// `custom_css` goes to its fingerprinted path.
+fn write_theme_assets(theme: &Theme, output_dir: &Path) -> Result<(), BuildError> {
+    let assets_dir = output_dir.join("assets");
+    fs::create_dir_all(&assets_dir)?;
+    fs::write(assets_dir.join("elm.min.js"), theme.js.as_bytes())?;
+    fs::write(assets_dir.join("theme.css"), theme.css.as_bytes())?;
+    remove_fingerprinted(&assets_dir, "custom.", ".css")?;
+    if let Some(custom) = &theme.custom_css {
+        fs::write(output_dir.join(&custom.path), &custom.css)?;
+    }
+    Ok(())
+}
+
+/// Remove the fingerprinted files of earlier builds, named `<prefix><hash><suffix>`
+/// with the 10 hex digits of a content hash. Other files with the same prefix and
+/// suffix, like a static `assets/custom.print.css`, are left alone.
+fn remove_fingerprinted(assets_dir: &Path, prefix: &str, suffix: &str) -> Result<(), BuildError> {
+    for entry in fs::read_dir(assets_dir)? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        let is_fingerprinted = name
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(suffix))
+            .is_some_and(|hash| hash.len() == 10 && hash.bytes().all(|b| b.is_ascii_hexdigit()));
+        if is_fingerprinted {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write the generated syntax-highlighting stylesheet to `assets/highlight.css`.
+pub fn write_highlight_css(css: &str, output_dir: &Path) -> Result<(), BuildError> {
     let assets_dir = output_dir.join("assets");
     fs::create_dir_all(&assets_dir)?;
-    fs::write(assets_dir.join("elm.min.js"), embedded::ELM_JS)?;
-    fs::write(assets_dir.join("theme.css"), embedded::THEME_CSS)?;
+    fs::write(assets_dir.join("highlight.css"), css)?;
+    Ok(())
+}
+
+/// Write the shared site data to its fingerprinted path under `assets/`,
+/// removing the files of earlier builds.
+pub fn write_site_data(site_data: &SiteData, output_dir: &Path) -> Result<(), BuildError> {
+    let assets_dir = output_dir.join("assets");
+    fs::create_dir_all(&assets_dir)?;
+    remove_fingerprinted(&assets_dir, "site.", ".json")?;
+    fs::write(output_dir.join(&site_data.path), &site_data.json)?;
+    Ok(())
+}
 
+/// Write the search index manifest and shards, each with gzip- and
+/// brotli-compressed `.gz` and `.br` siblings servers can send as is, and
+/// remove the shards of earlier builds. Only shard files are removed, so other
+/// files in the shard directory are left alone.
+pub fn write_search_index(files: &[IndexFile], output_dir: &Path) -> Result<(), BuildError> {
+    let shard_dir = output_dir.join(SHARD_DIR);
+    fs::create_dir_all(&shard_dir)?;
+    for file in files {
+        fs::write(output_dir.join(&file.path), &file.contents)?;
+        fs::write(output_dir.join(format!("{}.gz", file.path)), file.gzip()?)?;
+        fs::write(output_dir.join(format!("{}.br", file.path)), file.brotli()?)?;
+    }
+
+    let current: HashSet<PathBuf> = files.iter().map(|f| output_dir.join(&f.path)).collect();
+    for entry in fs::read_dir(&shard_dir)? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        if !SHARD_PREFIXES.iter().any(|prefix| name.starts_with(prefix)) {
+            continue;
+        }
+        let base = name
+            .strip_suffix(".gz")
+            .or_else(|| name.strip_suffix(".br"))
+            .unwrap_or(name);
+        if !current.contains(&shard_dir.join(base)) {
+            fs::remove_file(&path)?;
+        }
+    }
     Ok(())
 }
 
-/// Generate a sitemap.xml string from rendered pages.
-pub fn generate_sitemap(pages: &[(Route, String)], config: &Config) -> String {
+/// Generate a sitemap.xml string from page routes.
+pub fn generate_sitemap(routes: &[&Route], config: &Config) -> String {
     let base = normalize_sitemap_base(&config.site.base_url);
     let mut xml = String::from(
         "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
     );
-    for (route, _) in pages {
+    for route in routes {
         let loc = format!("{}{}", base, escape_xml(route.path()));
         xml.push_str(&format!("  <url>\n    <loc>{loc}</loc>\n  </url>\n"));
     }
@@ -77,14 +178,14 @@ pub fn generate_sitemap(pages: &[(Route, String)], config: &Config) -> String {
 
 /// Write sitemap.xml to the output directory if enabled.
 pub fn write_sitemap(
-    pages: &[(Route, String)],
+    routes: &[&Route],
     config: &Config,
     output_dir: &Path,
 ) -> Result<(), BuildError> {
     if !config.seo.sitemap {
         return Ok(());
     }
-    let sitemap = generate_sitemap(pages, config);
+    let sitemap = generate_sitemap(routes, config);
     fs::write(output_dir.join("sitemap.xml"), sitemap)?;
     Ok(())
 }
@@ -208,7 +309,7 @@ mod tests {
             ),
         ];
 
-        write_output(&pages, &tmp).unwrap();
+        write_output(&pages, &Theme::builtin(), &tmp).unwrap();
 
         assert!(tmp.join("index.html").exists());
         assert!(tmp.join("guide/intro/index.html").exists());
@@ -245,7 +346,7 @@ mod tests {
             "<html>New</html>".to_string(),
         )];
 
-        write_output(&pages, &tmp).unwrap();
+        write_output(&pages, &Theme::builtin(), &tmp).unwrap();
 
         assert!(tmp.join("index.html").exists());
         assert!(!tmp.join("stale.html").exists());
@@ -253,28 +354,52 @@ mod tests {
         let _ = fs::remove_dir_all(&tmp);
     }
 
+    #[test]
+    fn test_remove_fingerprinted_keeps_other_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let assets = tmp.path();
+        for name in [
+            "custom.0123456789.css",
+            "custom.print.css",
+            "custom.abcdef012345.css",
+            "site.abcdef0123.json",
+            "site.webmanifest.json",
+        ] {
+            fs::write(assets.join(name), "").unwrap();
+        }
+
+        remove_fingerprinted(assets, "custom.", ".css").unwrap();
+        remove_fingerprinted(assets, "site.", ".json").unwrap();
+
+        let mut left: Vec<_> = fs::read_dir(assets)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            [
+                "custom.abcdef012345.css",
+                "custom.print.css",
+                "site.webmanifest.json"
+            ]
+        );
+    }
+
     #[test]
     fn test_generate_sitemap() {
-        let pages = vec![
-            (
-                Route {
-                    path: "/".to_string(),
-                    source: PathBuf::from("index.md"),
-                    output: PathBuf::from("index.html"),
-                },
-                String::new(),
-            ),
-            (
-                Route {
-                    path: "/guide/intro".to_string(),
-                    source: PathBuf::from("guide/intro.md"),
-                    output: PathBuf::from("guide/intro/index.html"),
-                },
-                String::new(),
-            ),
-        ];
+        let index = Route {
+            path: "/".to_string(),
+            source: PathBuf::from("index.md"),
+            output: PathBuf::from("index.html"),
+        };
+        let intro = Route {
+            path: "/guide/intro".to_string(),
+            source: PathBuf::from("guide/intro.md"),
+            output: PathBuf::from("guide/intro/index.html"),
+        };
         let config = Config::default();
-        let xml = generate_sitemap(&pages, &config);
+        let xml = generate_sitemap(&[&index, &intro], &config);
         assert!(xml.contains("<urlset"));
         assert!(xml.contains("<loc>/</loc>"));
         assert!(xml.contains("<loc>/guide/intro</loc>"));
@@ -1,61 +1,122 @@
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 
+use pyohwa_search::indexer::IndexOptions;
+use rayon::prelude::*;
+
+use crate::build::diagnostics::{Diagnostic, Diagnostics};
 use crate::build::incremental;
-use crate::config::{self, Config};
-use crate::content::frontmatter;
+use crate::config::{self, Config, SearchConfig};
 use crate::content::loader;
-use crate::content::page::{Page, RenderedContent};
+use crate::content::page::{Page, RawContent, RenderedContent};
+use crate::content::{frontmatter, include, schema};
 use crate::error::BuildError;
 use crate::markdown::highlight;
 use crate::markdown::parser;
 use crate::render::assets;
 use crate::render::template;
+use crate::render::theme::Theme;
 use crate::site::graph::{self, SiteGraph};
+use crate::site::links::{self, BrokenLink};
 use crate::site::route::Route;
 
 /// Intermediate result from build_internal, holding all data needed for output.
 struct BuildResult {
     output_pages: Vec<(Route, String)>,
     site_graph: SiteGraph,
+    site_data: template::SiteData,
+    theme: Theme,
+    highlight_css: String,
+    broken_links: Vec<BrokenLink>,
+    diagnostics: Diagnostics,
+    warnings: Vec<String>,
     config: Config,
+    content_dir: PathBuf,
     output_dir: PathBuf,
     static_dir: PathBuf,
 }
 
+/// Options that override `pyohwa.toml` for a single build, typically set from CLI flags.
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    /// Worker threads for the per-page stages; `None` falls back to `[build] jobs`
+    pub jobs: Option<usize>,
+    /// Fail the build on broken internal links instead of reporting them as warnings
+    pub strict: bool,
+    /// Skip pages with content errors and build the rest instead of failing;
+    /// the errors come back in `BuildReport::diagnostics`
+    pub keep_going: bool,
+}
+
+/// Non-fatal findings of a successful build, for the caller to print.
+#[derive(Debug, Clone, Default)]
+pub struct BuildReport {
+    pub broken_links: Vec<BrokenLink>,
+    /// Content errors of the pages skipped under `keep_going`
+    pub diagnostics: Diagnostics,
+    /// Other warnings, such as deprecated settings
+    pub warnings: Vec<String>,
+}
+
 /// Execute the full build pipeline (production).
-pub fn build(project_root: &Path) -> Result<(), BuildError> {
-    let result = build_internal(project_root, None)?;
+pub fn build(project_root: &Path) -> Result<BuildReport, BuildError> {
+    build_with_options(project_root, &BuildOptions::default())
+}
 
-    crate::build::output::write_output(&result.output_pages, &result.output_dir)?;
+/// Execute the full build pipeline (production) with explicit options.
+pub fn build_with_options(
+    project_root: &Path,
+    options: &BuildOptions,
+) -> Result<BuildReport, BuildError> {
+    let result = build_internal(project_root, None, None, options)?;
 
-    if result.static_dir.exists() {
-        assets::copy_static_assets(&result.static_dir, &result.output_dir)?;
-    }
+    crate::build::output::write_output(&result.output_pages, &result.theme, &result.output_dir)?;
+    crate::build::output::write_highlight_css(&result.highlight_css, &result.output_dir)?;
+    crate::build::output::write_site_data(&result.site_data, &result.output_dir)?;
+
+    copy_static(&result)?;
 
     write_search_and_seo(&result)?;
 
-    Ok(())
+    Ok(result.report())
 }
 
 /// Execute the build pipeline with live reload JS injected.
 /// Used by the dev server for initial build.
-pub fn build_dev(project_root: &Path, ws_port: u16) -> Result<(), BuildError> {
-    let result = build_internal(project_root, Some(ws_port))?;
+pub fn build_dev(
+    project_root: &Path,
+    ws_port: u16,
+    options: &BuildOptions,
+) -> Result<BuildReport, BuildError> {
+    let result = build_internal(project_root, Some(ws_port), None, options)?;
 
-    crate::build::output::write_output(&result.output_pages, &result.output_dir)?;
+    crate::build::output::write_output(&result.output_pages, &result.theme, &result.output_dir)?;
+    crate::build::output::write_highlight_css(&result.highlight_css, &result.output_dir)?;
+    crate::build::output::write_site_data(&result.site_data, &result.output_dir)?;
 
-    if result.static_dir.exists() {
-        assets::copy_static_assets(&result.static_dir, &result.output_dir)?;
-    }
+    copy_static(&result)?;
 
     write_search_and_seo(&result)?;
 
-    Ok(())
+    let (raw_contents, _) = discover_pages(&result.content_dir, project_root)?;
+    let manifest = incremental::build_manifest(project_root, &result.config, &raw_contents);
+    incremental::save_manifest(project_root, &manifest)?;
+
+    Ok(result.report())
 }
 
-/// Incremental dev build: detect changes via manifest, rebuild if needed.
-/// Returns `true` if the site was rebuilt, `false` if no changes detected.
-pub fn build_dev_incremental(project_root: &Path, ws_port: u16) -> Result<bool, BuildError> {
+/// Incremental dev build: compare build inputs against the saved manifest and
+/// rebuild only what depends on the changed inputs.
+///
+/// Config, theme and navigation changes (titles, order, added or removed pages)
+/// re-render every page; a body or referenced static file change re-renders only
+/// the affected pages.
+/// Returns `None` if no changes were detected.
+pub fn build_dev_incremental(
+    project_root: &Path,
+    ws_port: u16,
+    options: &BuildOptions,
+) -> Result<Option<BuildReport>, BuildError> {
     let config = config::load(project_root)?;
     let content_dir = project_root.join(&config.build.content_dir);
 
@@ -63,34 +124,72 @@ pub fn build_dev_incremental(project_root: &Path, ws_port: u16) -> Result<bool,
         return Err(BuildError::ContentDirNotFound(content_dir));
     }
 
-    let raw_contents = loader::discover(&content_dir)?;
+    let (raw_contents, _) = discover_pages(&content_dir, project_root)?;
     let old_manifest = incremental::load_manifest(project_root);
-    let (changed, new_manifest) = incremental::detect_changes(&raw_contents, &old_manifest);
+    let new_manifest = incremental::build_manifest(project_root, &config, &raw_contents);
+    let plan = incremental::plan_rebuild(&old_manifest, &new_manifest);
 
-    if changed.is_empty() {
-        return Ok(false);
+    if plan.is_empty() {
+        return Ok(None);
     }
 
-    // Changes detected — full rebuild (site graph depends on all pages)
-    let result = build_internal(project_root, Some(ws_port))?;
+    let only = if plan.full { None } else { Some(&plan.pages) };
+    let result = build_internal(project_root, Some(ws_port), only, options)?;
+
+    crate::build::output::write_output_incremental(
+        &result.output_pages,
+        &result.theme,
+        &result.output_dir,
+    )?;
+    crate::build::output::write_highlight_css(&result.highlight_css, &result.output_dir)?;
+    crate::build::output::write_site_data(&result.site_data, &result.output_dir)?;
 
-    crate::build::output::write_output_incremental(&result.output_pages, &result.output_dir)?;
+    for output in &plan.removed_outputs {
+        crate::build::output::remove_output(output, &result.output_dir)?;
+    }
 
-    if result.static_dir.exists() {
-        assets::copy_static_assets(&result.static_dir, &result.output_dir)?;
+    if plan.full || plan.static_changed {
+        copy_static(&result)?;
     }
 
     write_search_and_seo(&result)?;
 
     incremental::save_manifest(project_root, &new_manifest)?;
 
-    Ok(true)
+    Ok(Some(result.report()))
 }
 
-/// Convert Page types to pyohwa_search::PageData for search indexing.
-fn pages_to_search_data(pages: &[Page]) -> Vec<pyohwa_search::PageData> {
+/// Copy the theme's static files, the files next to the pages in `content/`, then
+/// the project's static files, which win on conflicts.
+fn copy_static(result: &BuildResult) -> Result<(), BuildError> {
+    if let Some(theme_static) = result.theme.static_dir() {
+        assets::copy_static_assets(&theme_static, &result.output_dir)?;
+    }
+    assets::copy_content_assets(&result.content_dir, &result.output_dir)?;
+    if result.static_dir.exists() {
+        assets::copy_static_assets(&result.static_dir, &result.output_dir)?;
+    }
+    Ok(())
+}
+
+impl BuildResult {
+    fn report(&self) -> BuildReport {
+        BuildReport {
+            broken_links: self.broken_links.clone(),
+            diagnostics: self.diagnostics.clone(),
+            warnings: self.warnings.clone(),
+        }
+    }
+}
+
+/// Convert Page types to pyohwa_search::PageData for search indexing, with the
+/// custom frontmatter fields named in `[search] filters`. Pages with
+/// `search: false` or matching `[search] exclude` are left out.
+fn pages_to_search_data(pages: &[Page], search: &SearchConfig) -> Vec<pyohwa_search::PageData> {
     pages
         .iter()
+        .filter(|page| page.frontmatter.search != Some(false))
+        .filter(|page| !search.excludes(&page.route.source))
         .map(|page| pyohwa_search::PageData {
             url: page.route.path().to_string(),
             title: page.frontmatter.title.clone(),
@@ -99,23 +198,63 @@ fn pages_to_search_data(pages: &[Page]) -> Vec<pyohwa_search::PageData> {
             tags: page.frontmatter.tags.clone(),
             date: page.frontmatter.date.clone(),
             draft: page.frontmatter.draft,
+            fields: search
+                .filters
+                .iter()
+                .map(|key| (key.clone(), page.frontmatter.extra_values(key)))
+                .filter(|(_, values)| !values.is_empty())
+                .collect(),
+            toc: page
+                .toc
+                .iter()
+                .map(|item| pyohwa_search::TocEntry {
+                    id: item.id.clone(),
+                    text: item.text.clone(),
+                    level: item.level,
+                })
+                .collect(),
+            boost: page.frontmatter.search_boost.unwrap_or(1.0),
         })
         .collect()
 }
 
+/// The indexing options set by `[search]`, with the stop words of the site language.
+fn index_options(config: &Config) -> IndexOptions {
+    let search = &config.search;
+    IndexOptions {
+        max_content_length: search.max_content_length,
+        field_weights: [
+            search.weights.title,
+            search.weights.headings,
+            search.weights.tags,
+            search.weights.body,
+        ],
+        exclude_code: search.exclude_code,
+        stop_words: search
+            .stop_words_for(&config.site.language)
+            .iter()
+            .map(|word| pyohwa_search::tokenizer::normalize(word))
+            .collect(),
+    }
+}
+
 /// Write search index, sitemap, and atom feed after the main build.
 fn write_search_and_seo(result: &BuildResult) -> Result<(), BuildError> {
     // Search index
     if result.config.search.enabled {
-        let search_data = pages_to_search_data(&result.site_graph.pages);
-        let index = pyohwa_search::build_search_index(&search_data);
-        let json = pyohwa_search::serialize_search_index(&index)
-            .map_err(|e| BuildError::Search(e.to_string()))?;
-        std::fs::write(result.output_dir.join("search-index.json"), json)?;
+        let search_data = pages_to_search_data(&result.site_graph.pages, &result.config.search);
+        let index = pyohwa_search::build_search_index(&search_data, &index_options(&result.config));
+        let search_error = |e: pyohwa_search::error::SearchError| BuildError::Search(e.to_string());
+        let files = pyohwa_search::serialize_search_index(&index).map_err(search_error)?;
+        if let Some(limit) = result.config.search.max_index_size {
+            pyohwa_search::shard::check_size(&files, limit.0).map_err(search_error)?;
+        }
+        crate::build::output::write_search_index(&files, &result.output_dir)?;
     }
 
-    // Sitemap
-    crate::build::output::write_sitemap(&result.output_pages, &result.config, &result.output_dir)?;
+    // Sitemap (from the site graph: incremental builds only render part of the pages)
+    let routes: Vec<&Route> = result.site_graph.pages.iter().map(|p| &p.route).collect();
+    crate::build::output::write_sitemap(&routes, &result.config, &result.output_dir)?;
 
     // Atom feed
     crate::build::output::write_atom_feed(
@@ -128,6 +267,7 @@ fn write_search_and_seo(result: &BuildResult) -> Result<(), BuildError> {
 }
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
 
@@ -167,7 +307,7 @@ enabled = true
 
         std::fs::write(
             root.join("content/guide/installation.md"),
-            "---\ntitle: \"Installation\"\norder: 2\n---\n# Installation\n\n```bash\ncargo install pyohwa\n```\n",
+            "---\ntitle: \"Installation\"\norder: 2\n---\n# Installation\n\n```bash\ncargo install pyohwa\n```\n\n## From source\n\nClone it.\n",
         )
         .unwrap();
 
@@ -198,19 +338,32 @@ enabled = true
         );
 
         // Verify search index contains subdirectory paths
-        let search_json = std::fs::read_to_string(dist.join("search-index.json")).unwrap();
-        assert!(
-            search_json.contains("getting-started"),
-            "search index missing getting-started"
-        );
-        assert!(
-            search_json.contains("installation"),
-            "search index missing installation"
-        );
-        assert!(
-            search_json.contains("overview"),
-            "search index missing overview"
+        let entries = read_search_entries(&dist);
+        let urls: Vec<_> = entries.iter().map(|entry| &entry["url"]).collect();
+        for url in [
+            "/guide/getting-started",
+            "/guide/installation",
+            "/api/overview",
+        ] {
+            assert!(
+                urls.contains(&&serde_json::json!(url)),
+                "search index missing {url}"
+            );
+        }
+        assert!(dist.join("search-index.json.gz").exists());
+        let section = entries
+            .iter()
+            .find(|entry| entry["url"] == "/guide/installation#from-source")
+            .expect("search index missing the installation section");
+        assert_eq!(
+            section["breadcrumb"],
+            serde_json::json!(["Installation", "From source"])
         );
+        assert_eq!(section["content"], "Clone it.");
+
+        // Verify the syntax-highlighting stylesheet is emitted and linked
+        let highlight_css = std::fs::read_to_string(dist.join("assets/highlight.css")).unwrap();
+        assert!(highlight_css.contains("pre.highlight"));
 
         // Verify HTML contains __PYOHWA_DATA__
         let html = std::fs::read_to_string(dist.join("guide/getting-started/index.html")).unwrap();
@@ -219,12 +372,770 @@ enabled = true
             "HTML missing __PYOHWA_DATA__"
         );
     }
+
+    /// Read the search index entries from their page shards.
+    fn read_search_entries(dist: &Path) -> Vec<serde_json::Value> {
+        let read = |path: &str| -> serde_json::Value {
+            serde_json::from_str(&std::fs::read_to_string(dist.join(path)).unwrap()).unwrap()
+        };
+        read("search-index.json")["page_shards"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|path| read(path.as_str().unwrap()).as_array().unwrap().clone())
+            .collect()
+    }
+
+    /// Read every file under `dir` into a map keyed by relative path.
+    fn snapshot_dir(dir: &Path) -> std::collections::BTreeMap<PathBuf, Vec<u8>> {
+        walkdir::WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| {
+                let rel = e.path().strip_prefix(dir).unwrap().to_path_buf();
+                (rel, std::fs::read(e.path()).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parallel_build_matches_sequential() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::write(root.join("pyohwa.toml"), "[site]\ntitle = \"Test Site\"\n").unwrap();
+        for section in ["guide", "api", "reference"] {
+            std::fs::create_dir_all(root.join("content").join(section)).unwrap();
+            for i in 0..12 {
+                std::fs::write(
+                    root.join(format!("content/{section}/page-{i}.md")),
+                    format!(
+                        "---\ntitle: \"{section} {i}\"\norder: {i}\n---\n# Heading {i}\n\n```rust\nfn f{i}() {{}}\n```\n"
+                    ),
+                )
+                .unwrap();
+            }
+        }
+
+        build_with_options(
+            root,
+            &BuildOptions {
+                jobs: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let sequential = snapshot_dir(&root.join("dist"));
+
+        build_with_options(
+            root,
+            &BuildOptions {
+                jobs: Some(8),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let parallel = snapshot_dir(&root.join("dist"));
+
+        assert_eq!(sequential.len(), parallel.len());
+        assert!(sequential == parallel, "parallel output differs");
+    }
+
+    #[test]
+    fn test_search_config_shapes_the_index() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::write(
+            root.join("pyohwa.toml"),
+            "[search]\nexclude = [\"changelog/**\"]\nmax_content_length = 12\nexclude_code = true\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("content/changelog")).unwrap();
+        std::fs::write(
+            root.join("content/index.md"),
+            "---\ntitle: Home\nsearch_boost: 2\n---\nWelcome to the docs.\n\n```sh\ncargo install\n```\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("content/hidden.md"),
+            "---\ntitle: Hidden\nsearch: false\n---\nSecret\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("content/changelog/v1.md"),
+            "---\ntitle: v1\n---\nFirst\n",
+        )
+        .unwrap();
+
+        build(root).unwrap();
+
+        let dist = root.join("dist");
+        let entries = read_search_entries(&dist);
+        let urls: Vec<_> = entries.iter().map(|entry| &entry["url"]).collect();
+        assert_eq!(urls, ["/"]);
+        assert_eq!(entries[0]["content"], "Welcome to...");
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dist.join("search-index.json")).unwrap())
+                .unwrap();
+        assert_eq!(manifest["boosts"], serde_json::json!({ "0": 2.0 }));
+        assert!(dist.join("hidden/index.html").exists());
+    }
+
+    #[test]
+    fn test_search_index_over_budget_fails_build() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::write(
+            root.join("pyohwa.toml"),
+            "[search]\nmax_index_size = \"4KB\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("content")).unwrap();
+        std::fs::write(
+            root.join("content/index.md"),
+            format!("---\ntitle: Home\n---\n{}\n", "lorem ipsum ".repeat(20)),
+        )
+        .unwrap();
+        build(root).unwrap();
+
+        let words: Vec<_> = (0..200).map(|i| format!("word{i}")).collect();
+        std::fs::write(
+            root.join("content/index.md"),
+            format!("---\ntitle: Home\n---\n{}\n", words.join(" ")),
+        )
+        .unwrap();
+        let err = build(root).unwrap_err();
+        assert!(matches!(err, BuildError::Search(_)));
+        assert!(err.to_string().contains("max_index_size of 4096 bytes"));
+    }
+
+    #[test]
+    fn test_unknown_highlight_theme_fails_build() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::write(
+            root.join("pyohwa.toml"),
+            "[theme]\nhighlight_theme = \"no-such-theme\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("content")).unwrap();
+        std::fs::write(root.join("content/index.md"), "---\ntitle: Home\n---\n").unwrap();
+
+        let err = build(root).unwrap_err();
+        assert!(matches!(
+            err,
+            BuildError::Config(crate::error::ConfigError::UnknownHighlightTheme { .. })
+        ));
+        assert!(err.to_string().contains("InspiredGitHub"));
+    }
+
+    #[test]
+    fn test_deprecated_highlight_theme_warns_and_builds() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::write(
+            root.join("pyohwa.toml"),
+            "[theme]\nhighlight_theme = \"one-dark\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("content")).unwrap();
+        std::fs::write(root.join("content/index.md"), "---\ntitle: Home\n---\n").unwrap();
+
+        let report = build(root).unwrap();
+        assert_eq!(
+            report.warnings,
+            vec![
+                "theme.highlight_theme 'one-dark' is deprecated, using 'base16-ocean.dark' instead"
+            ]
+        );
+        let css = std::fs::read_to_string(root.join("dist/assets/highlight.css")).unwrap();
+        assert!(css.contains("light \"base16-ocean.dark\""));
+    }
+
+    #[test]
+    fn test_parallel_build_reports_all_content_errors_in_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::create_dir_all(root.join("content")).unwrap();
+        for i in 0..20 {
+            std::fs::write(
+                root.join(format!("content/page-{i:02}.md")),
+                format!("---\ntitle: \"Page {i}\"\n---\nBody\n"),
+            )
+            .unwrap();
+        }
+        std::fs::write(root.join("content/page-05.md"), "no frontmatter").unwrap();
+        std::fs::write(root.join("content/page-15.md"), "").unwrap();
+
+        let err = build_with_options(
+            root,
+            &BuildOptions {
+                jobs: Some(8),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        let BuildError::Diagnostics(diagnostics) = &err else {
+            panic!("unexpected error: {err}");
+        };
+        let paths: Vec<_> = diagnostics.iter().map(|d| d.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("content/page-05.md"),
+                PathBuf::from("content/page-15.md")
+            ]
+        );
+        assert!(err
+            .to_string()
+            .ends_with("2 errors in 2 files: 1 empty content, 1 missing frontmatter"));
+    }
+
+    #[test]
+    fn test_keep_going_builds_valid_pages() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::create_dir_all(root.join("content")).unwrap();
+        std::fs::write(root.join("content/index.md"), "---\ntitle: Home\n---\n").unwrap();
+        std::fs::write(
+            root.join("content/broken.md"),
+            "---\ntitle: Broken\norder: first\n---\n",
+        )
+        .unwrap();
+
+        let options = BuildOptions {
+            keep_going: true,
+            ..Default::default()
+        };
+        let report = build_with_options(root, &options).unwrap();
+        assert_eq!(report.diagnostics.len(), 1);
+        let diagnostic = report.diagnostics.iter().next().unwrap();
+        assert_eq!(diagnostic.path, PathBuf::from("content/broken.md"));
+        assert_eq!(diagnostic.position, Some((3, 8)));
+        assert_eq!(diagnostic.snippet.as_deref(), Some("order: first"));
+
+        assert!(root.join("dist/index.html").exists());
+        assert!(!root.join("dist/broken/index.html").exists());
+    }
+
+    #[test]
+    fn test_partials_in_content_are_not_pages() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        // The README's include example, with the partial next to the page
+        std::fs::create_dir_all(root.join("content/partials")).unwrap();
+        std::fs::write(
+            root.join("content/index.md"),
+            "---\ntitle: Home\n---\n# Home\n\n@include(./partials/install.md)\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("content/partials/install.md"),
+            "Run `cargo install pyohwa`.\n",
+        )
+        .unwrap();
+
+        std::fs::write(root.join("content/_notes.md"), "---\ntitle: Notes\n---\n").unwrap();
+
+        let report = build(root).unwrap();
+        let html = std::fs::read_to_string(root.join("dist/index.html")).unwrap();
+        assert!(html.contains("<code>cargo install pyohwa</code>"));
+        assert!(!root.join("dist/partials").exists());
+        assert_eq!(
+            report.warnings,
+            vec![
+                "content/partials/install.md is included by content/index.md, so it is not built as a page"
+            ]
+        );
+
+        // Only included files are partials; the rest stay pages whatever their name
+        assert!(root.join("dist/_notes/index.html").exists());
+    }
+
+    #[test]
+    fn test_page_size_does_not_grow_with_the_site() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        // 1000 pages in 20 sections; search is off to keep the build quick
+        std::fs::write(root.join("pyohwa.toml"), "[search]\nenabled = false\n").unwrap();
+        for section in 0..20 {
+            let dir = root.join(format!("content/section-{section:02}"));
+            std::fs::create_dir_all(&dir).unwrap();
+            for page in 0..50 {
+                std::fs::write(
+                    dir.join(format!("page-{page:02}.md")),
+                    format!("---\ntitle: Section {section} page {page}\n---\n# Page {page}\n"),
+                )
+                .unwrap();
+            }
+        }
+
+        build(root).unwrap();
+        let site_data = std::fs::read_dir(root.join("dist/assets"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .unwrap();
+        let site_data_size = std::fs::metadata(site_data).unwrap().len();
+
+        // Each page carries its own section of the sidebar, not the whole site's
+        let largest = walkdir::WalkDir::new(root.join("dist"))
+            .into_iter()
+            .map(|entry| entry.unwrap())
+            .filter(|entry| entry.file_name() == "index.html")
+            .map(|entry| entry.metadata().unwrap().len())
+            .max()
+            .unwrap();
+        assert!(
+            site_data_size > 50_000,
+            "site data is {site_data_size} bytes"
+        );
+        assert!(largest < 20_000, "a page is {largest} bytes");
+    }
+
+    #[test]
+    fn test_search_page_keeps_its_output_next_to_the_index() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::create_dir_all(root.join("content")).unwrap();
+        std::fs::write(
+            root.join("content/index.md"),
+            "---\ntitle: Home\n---\n# Home\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("content/search.md"),
+            "---\ntitle: Search\n---\nSearch tips.\n",
+        )
+        .unwrap();
+
+        build_dev(root, 3000, &BuildOptions::default()).unwrap();
+        let dist = root.join("dist");
+        assert!(dist.join("search/index.html").exists());
+
+        // A file in the shard directory that is not a shard is left alone
+        std::fs::write(dist.join("assets/search/notes.txt"), "keep").unwrap();
+        std::fs::write(
+            root.join("content/search.md"),
+            "---\ntitle: Search\n---\nSearch tips, revised.\n",
+        )
+        .unwrap();
+        build_dev_incremental(root, 3000, &BuildOptions::default())
+            .unwrap()
+            .unwrap();
+        assert!(dist.join("search/index.html").exists());
+        assert!(dist.join("assets/search/notes.txt").exists());
+
+        // Only the shards of the last build are left, each with its siblings
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dist.join("search-index.json")).unwrap())
+                .unwrap();
+        let mut shards: Vec<String> = manifest["term_shards"]
+            .as_object()
+            .unwrap()
+            .values()
+            .chain(manifest["page_shards"].as_array().unwrap())
+            .map(|path| path.as_str().unwrap().to_string())
+            .flat_map(|path| [format!("{path}.br"), format!("{path}.gz"), path])
+            .collect();
+        shards.push("assets/search/notes.txt".to_string());
+        shards.sort();
+        let mut files: Vec<String> = std::fs::read_dir(dist.join("assets/search"))
+            .unwrap()
+            .map(|entry| {
+                format!(
+                    "assets/search/{}",
+                    entry.unwrap().file_name().to_string_lossy()
+                )
+            })
+            .collect();
+        files.sort();
+        assert_eq!(files, shards);
+        assert!(dist.join("search-index.json.br").exists());
+    }
+
+    #[test]
+    fn test_broken_links_warn_or_fail_with_strict() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::create_dir_all(root.join("content/guide")).unwrap();
+        std::fs::write(
+            root.join("content/guide/intro.md"),
+            "---\ntitle: Intro\n---\n[setup](./setup.md#install) [gone](./gone.md) ![d](./diagram.png)\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("content/guide/setup.md"),
+            "---\ntitle: Setup\n---\n## Install\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("content/guide/diagram.png"), "png").unwrap();
+
+        let report = build(root).unwrap();
+        assert_eq!(report.broken_links.len(), 1);
+        assert_eq!(report.broken_links[0].target, "./gone.md");
+        let html = std::fs::read_to_string(root.join("dist/guide/intro/index.html")).unwrap();
+        assert!(html.contains(r#"href="/guide/setup#install""#));
+        // Files next to the page are copied and are not broken links
+        assert!(html.contains(r#"src="/guide/diagram.png""#));
+        assert!(root.join("dist/guide/diagram.png").exists());
+
+        let strict = BuildOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let err = build_with_options(root, &strict).unwrap_err();
+        assert!(matches!(err, BuildError::BrokenLinks(ref links) if links.len() == 1));
+        assert!(err
+            .to_string()
+            .contains("guide/intro.md: broken link `./gone.md`"));
+    }
+
+    #[test]
+    fn test_page_size_does_not_repeat_content_or_site_data() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::create_dir_all(root.join("content/guide")).unwrap();
+        for i in 0..200 {
+            std::fs::write(
+                root.join(format!("content/guide/page-{i:03}.md")),
+                format!("---\ntitle: \"Page {i}\"\norder: {i}\n---\nBody {i}\n"),
+            )
+            .unwrap();
+        }
+        let paragraph = "Large page paragraph with enough text to matter. ".repeat(20);
+        let body: String = (0..100)
+            .map(|i| format!("## Section {i}\n\n{paragraph}\n\n"))
+            .collect();
+        std::fs::write(
+            root.join("content/guide/large.md"),
+            format!("---\ntitle: Large\n---\nLARGE-PAGE-MARKER\n\n{body}"),
+        )
+        .unwrap();
+
+        build(root).unwrap();
+
+        let site_files: Vec<_> = std::fs::read_dir(root.join("dist/assets"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name.starts_with("site.") && name.ends_with(".json"))
+            .collect();
+        assert_eq!(site_files.len(), 1, "{site_files:?}");
+        let site_json =
+            std::fs::read_to_string(root.join("dist/assets").join(&site_files[0])).unwrap();
+        assert!(site_json.contains("/guide/page-199"));
+
+        let html = std::fs::read_to_string(root.join("dist/guide/large/index.html")).unwrap();
+        assert_eq!(html.matches("LARGE-PAGE-MARKER").count(), 1);
+        assert_eq!(html.matches(paragraph.trim_end()).count(), 100);
+        // The sidebar is only in the server-rendered markup
+        assert_eq!(html.matches("href=\"/guide/page-100\"").count(), 1);
+
+        let start = html.find("window.__PYOHWA_DATA__ = ").unwrap();
+        let end = start + html[start..].find("</script>").unwrap();
+        assert!(
+            end - start < 8 * 1024,
+            "inline payload is {} bytes",
+            end - start
+        );
+        assert!(html[start..end].contains(&format!("/assets/{}", site_files[0])));
+    }
+
+    #[test]
+    fn test_custom_layouts_need_a_template() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::create_dir_all(root.join("content")).unwrap();
+        std::fs::write(
+            root.join("content/changes.md"),
+            "---\ntitle: Changes\nlayout: changelog\n---\n- Fixed\n",
+        )
+        .unwrap();
+
+        let err = build(root).unwrap_err();
+        match &err {
+            BuildError::Render(crate::error::RenderError::LayoutNotFound { name, file, line }) => {
+                assert_eq!(name, "changelog");
+                assert!(file.ends_with("content/changes.md"));
+                assert_eq!(*line, 3);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+
+        let templates = root.join("themes/default/templates");
+        std::fs::create_dir_all(&templates).unwrap();
+        std::fs::write(
+            templates.join("changelog.html"),
+            "{% extends \"base.html\" %}{% block main %}<section class=\"changelog\">{{ page.content }}</section>{% endblock %}",
+        )
+        .unwrap();
+
+        build(root).unwrap();
+        let html = std::fs::read_to_string(root.join("dist/changes/index.html")).unwrap();
+        assert!(html.contains("<div class=\"pyohwa-layout\" data-layout=\"changelog\">"));
+        assert!(html.contains("<section class=\"changelog\"><ul>"));
+        assert!(html.contains("\"layout\":\"changelog\""));
+    }
+
+    #[test]
+    fn test_theme_package_overrides_embedded_pieces() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::write(
+            root.join("pyohwa.toml"),
+            "[theme]\nname = \"ocean\"\n\n[theme.settings]\naccent = \"teal\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("content")).unwrap();
+        std::fs::write(root.join("content/index.md"), "---\ntitle: Home\n---\nHi\n").unwrap();
+        std::fs::create_dir_all(root.join("static")).unwrap();
+        std::fs::write(root.join("static/favicon.ico"), "project").unwrap();
+
+        let theme = root.join("themes/ocean");
+        std::fs::create_dir_all(theme.join("dist")).unwrap();
+        std::fs::create_dir_all(theme.join("static")).unwrap();
+        std::fs::create_dir_all(theme.join("templates")).unwrap();
+        std::fs::write(
+            theme.join("theme.toml"),
+            "name = \"Ocean\"\n\n[settings.accent]\ndefault = \"blue\"\n",
+        )
+        .unwrap();
+        std::fs::write(theme.join("dist/theme.css"), ".ocean {}").unwrap();
+        std::fs::write(theme.join("static/favicon.ico"), "theme").unwrap();
+        std::fs::write(theme.join("static/wave.svg"), "<svg/>").unwrap();
+        std::fs::write(
+            theme.join("templates/base.html"),
+            "<body style=\"--accent: {{ theme.settings.accent }}\">{% block body %}{% endblock %}</body>",
+        )
+        .unwrap();
+
+        build(root).unwrap();
+
+        let dist = root.join("dist");
+        assert_eq!(
+            std::fs::read_to_string(dist.join("assets/theme.css")).unwrap(),
+            ".ocean {}"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dist.join("assets/elm.min.js")).unwrap(),
+            crate::render::embedded::ELM_JS
+        );
+        assert!(dist.join("wave.svg").exists());
+        assert_eq!(
+            std::fs::read_to_string(dist.join("favicon.ico")).unwrap(),
+            "project"
+        );
+        let html = std::fs::read_to_string(dist.join("index.html")).unwrap();
+        assert!(html.starts_with("<body style=\"--accent: teal\">"));
+    }
+
+    #[test]
+    fn test_frontmatter_schema_violations_are_reported_together() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::write(
+            root.join("pyohwa.toml"),
+            "[frontmatter.schema.status]\nrequired = true\nenum = [\"beta\", \"stable\"]\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("content")).unwrap();
+        std::fs::write(
+            root.join("content/a.md"),
+            "---\ntitle: A\nstatus: alpha\n---\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("content/b.md"), "---\ntitle: B\n---\n").unwrap();
+        std::fs::write(
+            root.join("content/c.md"),
+            "---\ntitle: C\nstatus: stable\n---\n",
+        )
+        .unwrap();
+
+        let err = build(root).unwrap_err();
+        let BuildError::Diagnostics(diagnostics) = &err else {
+            panic!("unexpected error: {err}");
+        };
+        let violations: Vec<_> = diagnostics.iter().collect();
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].path, PathBuf::from("content/a.md"));
+        assert_eq!(violations[0].position, Some((3, 9)));
+        assert_eq!(violations[1].path, PathBuf::from("content/b.md"));
+        assert_eq!(violations[1].message, "missing required field 'status'");
+        assert!(err
+            .to_string()
+            .ends_with("2 errors in 2 files: 2 schema violations"));
+
+        std::fs::write(
+            root.join("content/a.md"),
+            "---\ntitle: A\nstatus: beta\n---\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("content/b.md"),
+            "---\ntitle: B\nstatus: beta\n---\n",
+        )
+        .unwrap();
+        build(root).unwrap();
+    }
+
+    #[test]
+    fn test_custom_frontmatter_feeds_badges_and_search_filters() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::write(
+            root.join("pyohwa.toml"),
+            "[sidebar]\nbadge = \"status\"\n\n[search]\nfilters = [\"status\", \"owner\"]\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("content/guide")).unwrap();
+        std::fs::write(
+            root.join("content/guide/beta.md"),
+            "---\ntitle: Beta\nstatus: beta\nowner: [platform]\n---\nNew\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("content/guide/stable.md"),
+            "---\ntitle: Stable\n---\nOld\n",
+        )
+        .unwrap();
+
+        build(root).unwrap();
+
+        let dist = root.join("dist");
+        let html = std::fs::read_to_string(dist.join("guide/stable/index.html")).unwrap();
+        assert!(html.contains("Beta <span class=\"pyohwa-sidebar-badge\">beta</span></a>"));
+        assert!(html.contains(">Stable</a>"));
+
+        let fields: Vec<_> = read_search_entries(&dist)
+            .iter()
+            .map(|entry| entry.get("fields").cloned())
+            .collect();
+        assert!(fields.contains(&Some(serde_json::json!({
+            "status": ["beta"],
+            "owner": ["platform"],
+        }))));
+        assert!(fields.contains(&None));
+    }
+
+    #[test]
+    fn test_incremental_rebuild_tracks_dependencies() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        std::fs::write(root.join("pyohwa.toml"), "[site]\ntitle = \"Test Site\"\n").unwrap();
+        std::fs::create_dir_all(root.join("content/guide")).unwrap();
+        std::fs::create_dir_all(root.join("static")).unwrap();
+        std::fs::write(root.join("static/logo.svg"), "<svg/>").unwrap();
+        std::fs::write(
+            root.join("content/guide/intro.md"),
+            "---\ntitle: \"Intro\"\norder: 1\n---\n# Intro\n\n![logo](/logo.svg)\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("content/guide/setup.md"),
+            "---\ntitle: \"Setup\"\norder: 2\n---\n# Setup\n",
+        )
+        .unwrap();
+
+        build_dev(root, 3000, &BuildOptions::default()).unwrap();
+        let dist = root.join("dist");
+        let intro_out = dist.join("guide/intro/index.html");
+        let setup_out = dist.join("guide/setup/index.html");
+
+        // Nothing changed since the initial dev build
+        assert!(build_dev_incremental(root, 3000, &BuildOptions::default())
+            .unwrap()
+            .is_none());
+
+        // Body edit: only that page is re-rendered
+        std::fs::write(&intro_out, "stale").unwrap();
+        std::fs::write(&setup_out, "stale").unwrap();
+        std::fs::write(
+            root.join("content/guide/setup.md"),
+            "---\ntitle: \"Setup\"\norder: 2\n---\n# Setup\n\nUpdated body.\n",
+        )
+        .unwrap();
+        assert!(build_dev_incremental(root, 3000, &BuildOptions::default())
+            .unwrap()
+            .is_some());
+        assert_eq!(std::fs::read_to_string(&intro_out).unwrap(), "stale");
+        assert!(std::fs::read_to_string(&setup_out)
+            .unwrap()
+            .contains("Updated body."));
+        // The sitemap still lists pages that were not re-rendered
+        let sitemap = std::fs::read_to_string(dist.join("sitemap.xml")).unwrap();
+        assert!(sitemap.contains("/guide/intro"));
+
+        // Static asset change: the referencing page is re-rendered and the asset recopied
+        std::fs::write(&setup_out, "stale").unwrap();
+        std::fs::write(root.join("static/logo.svg"), "<svg></svg>").unwrap();
+        assert!(build_dev_incremental(root, 3000, &BuildOptions::default())
+            .unwrap()
+            .is_some());
+        assert_ne!(std::fs::read_to_string(&intro_out).unwrap(), "stale");
+        assert_eq!(std::fs::read_to_string(&setup_out).unwrap(), "stale");
+        assert_eq!(
+            std::fs::read_to_string(dist.join("logo.svg")).unwrap(),
+            "<svg></svg>"
+        );
+
+        // Config change: every page is re-rendered
+        std::fs::write(&intro_out, "stale").unwrap();
+        std::fs::write(root.join("pyohwa.toml"), "[site]\ntitle = \"Renamed\"\n").unwrap();
+        assert!(build_dev_incremental(root, 3000, &BuildOptions::default())
+            .unwrap()
+            .is_some());
+        assert!(std::fs::read_to_string(&intro_out)
+            .unwrap()
+            .contains("Renamed"));
+        assert!(std::fs::read_to_string(&setup_out)
+            .unwrap()
+            .contains("Renamed"));
+
+        // Deleted page: its output is removed
+        std::fs::remove_file(root.join("content/guide/setup.md")).unwrap();
+        assert!(build_dev_incremental(root, 3000, &BuildOptions::default())
+            .unwrap()
+            .is_some());
+        assert!(!setup_out.exists());
+    }
 }
 
 /// Internal: run stages 1–7, returning rendered pages and paths.
-fn build_internal(project_root: &Path, ws_port: Option<u16>) -> Result<BuildResult, BuildError> {
+///
+/// The pure per-page stages (2b–5 and 7) run on a work-stealing thread pool sized by
+/// `options.jobs` or `[build] jobs`. Results keep the discovery order, so output is
+/// identical to a sequential build. Content errors in stages 2b–5 are collected for
+/// every page and fail the build together, or skip their pages under `keep_going`;
+/// any other error fails the build with the first one in discovery order.
+///
+/// When `only` is given, stage 7 renders just those content files (paths relative
+/// to the project root); every page still goes through stages 3–6 because the
+/// site graph and search index need all of them.
+fn build_internal(
+    project_root: &Path,
+    ws_port: Option<u16>,
+    only: Option<&BTreeSet<PathBuf>>,
+    options: &BuildOptions,
+) -> Result<BuildResult, BuildError> {
     // Stage 1: Load config
-    let config = config::load(project_root)?;
+    let mut config = config::load(project_root)?;
 
     let content_dir = project_root.join(&config.build.content_dir);
     let output_dir = project_root.join(&config.build.output_dir);
@@ -234,51 +1145,229 @@ fn build_internal(project_root: &Path, ws_port: Option<u16>) -> Result<BuildResu
         return Err(BuildError::ContentDirNotFound(content_dir));
     }
 
-    // Stage 2: Discover content files (IO)
-    let raw_contents = loader::discover(&content_dir)?;
+    // Load syntaxes and themes once, and resolve highlight themes up front
+    // so a typo fails before any page work
+    let highlighter = highlight::Highlighter::load(project_root)?;
+    let mut warnings = Vec::new();
+    for (key, name) in [
+        ("highlight_theme", &mut config.theme.highlight_theme),
+        (
+            "highlight_theme_dark",
+            &mut config.theme.highlight_theme_dark,
+        ),
+    ] {
+        if let Some(replacement) =
+            highlight::deprecated_theme_replacement(&highlighter.themes, name)
+        {
+            warnings.push(format!(
+                "theme.{key} '{name}' is deprecated, using '{replacement}' instead"
+            ));
+            *name = replacement.to_string();
+        }
+    }
+    let highlight_css = highlight::generate_css(
+        &highlighter.themes,
+        &config.theme.highlight_theme,
+        &config.theme.highlight_theme_dark,
+    )?;
 
-    // Stage 3: Parse frontmatter (pure)
-    let parsed_contents: Vec<_> = raw_contents
-        .iter()
-        .map(|raw| frontmatter::parse_frontmatter(raw))
-        .collect::<Result<Vec<_>, _>>()?;
+    // Resolve the theme up front so a missing theme, bad setting or template
+    // syntax error fails before any page work
+    let theme = Theme::load(project_root, &config.theme)?;
 
-    // Stage 4: Markdown -> HTML (pure)
-    let rendered_contents: Vec<_> = parsed_contents
-        .iter()
-        .map(|parsed| parser::parse_markdown(parsed))
-        .collect::<Result<Vec<_>, _>>()?;
+    let markdown_ctx = parser::MarkdownContext {
+        highlighter: &highlighter,
+        language: &config.site.language,
+        math: config.markdown.math,
+    };
 
-    // Stage 5: Syntax highlighting (pure)
-    let highlighted_contents: Vec<RenderedContent> = rendered_contents
-        .iter()
-        .map(|rendered| highlight::apply_syntax_highlighting(rendered))
-        .collect::<Result<Vec<_>, _>>()?;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.jobs.unwrap_or(config.build.jobs))
+        .build()
+        .map_err(|e| BuildError::ThreadPool(e.to_string()))?;
+
+    // Stage 2: Discover content files, leaving out included partials (IO)
+    let (raw_contents, partial_warnings) = discover_pages(&content_dir, project_root)?;
+    warnings.extend(partial_warnings);
+
+    // Stages 2b-5, per page: schema check, frontmatter, includes, markdown
+    let processed = pool.install(|| {
+        collect_in_order(
+            raw_contents
+                .par_iter()
+                .map(|raw| process_page(raw, &config, project_root, &markdown_ctx)),
+        )
+    })?;
+
+    let mut diagnostics = Diagnostics::default();
+    let mut rendered_contents = Vec::with_capacity(processed.len());
+    for (raw, result) in raw_contents.iter().zip(processed) {
+        match result {
+            Ok(rendered) => rendered_contents.push(rendered),
+            Err(page_diagnostics) => diagnostics.extend(project_root, &raw.raw, page_diagnostics),
+        }
+    }
+    if !diagnostics.is_empty() && !options.keep_going {
+        return Err(BuildError::Diagnostics(diagnostics));
+    }
 
     // Stage 6: Build site graph (pure)
-    let site_graph =
-        graph::build_graph_with_content_dir(&highlighted_contents, &config, &content_dir);
+    let mut site_graph =
+        graph::build_graph_with_content_dir(&rendered_contents, &config, &content_dir);
+
+    // Stage 6b: Rewrite internal links through the route table and check them
+    let broken_links = links::resolve_links(
+        &mut site_graph,
+        &content_dir,
+        &static_dir,
+        &config.site.base_url,
+    );
+    if options.strict && !broken_links.is_empty() {
+        return Err(BuildError::BrokenLinks(broken_links));
+    }
+
+    // Stage 6c: Every page layout needs a template
+    theme
+        .templates
+        .check_layouts(&site_graph.pages, &raw_contents, &content_dir)?;
+
+    // Nav, sidebar and search flag, shared by every page through assets/site.<hash>.json
+    let site_data = template::SiteData::new(&site_graph, &config)?;
 
     // Stage 7: Render HTML templates (pure)
-    let output_pages: Vec<_> = site_graph
-        .pages
-        .iter()
-        .map(|page| {
-            let html = match ws_port {
-                Some(port) => {
-                    template::render_page_with_live_reload(page, &site_graph, &config, port)?
-                }
-                None => template::render_page(page, &site_graph, &config)?,
-            };
-            Ok((page.route.clone(), html))
-        })
-        .collect::<Result<Vec<_>, BuildError>>()?;
+    let output_pages: Vec<_> = pool.install(|| {
+        collect_in_order(
+            site_graph
+                .pages
+                .par_iter()
+                .filter(|page| {
+                    only.is_none_or(|sources| {
+                        let source = content_dir.join(&page.route.source);
+                        sources.contains(source.strip_prefix(project_root).unwrap_or(&source))
+                    })
+                })
+                .map(|page| {
+                    let html = match ws_port {
+                        Some(port) => template::render_page_with_live_reload(
+                            page,
+                            &site_graph,
+                            &config,
+                            &site_data,
+                            &theme,
+                            port,
+                        )?,
+                        None => {
+                            template::render_page(page, &site_graph, &config, &site_data, &theme)?
+                        }
+                    };
+                    Ok::<_, BuildError>((page.route.clone(), html))
+                }),
+        )
+    })?;
 
     Ok(BuildResult {
         output_pages,
         site_graph,
+        site_data,
+        theme,
+        highlight_css,
+        broken_links,
+        diagnostics,
+        warnings,
         config,
+        content_dir,
         output_dir,
         static_dir,
     })
 }
+
+/// Run one content file through stages 2b–5.
+///
+/// The inner `Err` holds the page's content errors: its schema violations along
+/// with any frontmatter error, or else the first error of the later stages. The
+/// outer one is a build failure.
+fn process_page(
+    raw: &RawContent,
+    config: &Config,
+    project_root: &Path,
+    markdown_ctx: &parser::MarkdownContext,
+) -> Result<Result<RenderedContent, Vec<Diagnostic>>, BuildError> {
+    // Stage 2b: Check frontmatter against [frontmatter.schema] (pure)
+    if !config.frontmatter.schema.is_empty() {
+        let violations = schema::check(raw, &config.frontmatter.schema);
+        if !violations.is_empty() {
+            let mut diagnostics: Vec<_> = violations.into_iter().map(Diagnostic::from).collect();
+            if let Err(err) = frontmatter::parse_frontmatter(raw) {
+                diagnostics.push(Diagnostic::from_content_error(err));
+            }
+            return Ok(Err(diagnostics));
+        }
+    }
+
+    // Stage 3: Parse frontmatter (pure)
+    let rendered = frontmatter::parse_frontmatter(raw)
+        // Stage 3b: Expand snippet and partial includes (IO)
+        .and_then(|parsed| include::expand_content(&parsed, project_root))
+        .map_err(BuildError::from)
+        // Stages 4-5: Markdown -> HTML with syntax-highlighted code blocks (pure)
+        .and_then(|parsed| parser::parse_markdown(&parsed, markdown_ctx));
+
+    match rendered {
+        Ok(rendered) => Ok(Ok(rendered)),
+        Err(BuildError::Content(err)) => Ok(Err(vec![Diagnostic::from_content_error(err)])),
+        Err(err) => Err(err),
+    }
+}
+
+/// Discover the content files that are pages.
+///
+/// Files pulled in by an `@include` are partials rather than pages; each one left
+/// out comes back as a warning naming the page that includes it.
+fn discover_pages(
+    content_dir: &Path,
+    project_root: &Path,
+) -> Result<(Vec<RawContent>, Vec<String>), BuildError> {
+    let raw_contents = loader::discover(content_dir)?;
+    let partials = include::partial_sources(&raw_contents, project_root);
+    let includer = |raw: &RawContent| {
+        raw.path
+            .canonicalize()
+            .ok()
+            .and_then(|path| partials.get(&path))
+    };
+
+    let mut pages = Vec::with_capacity(raw_contents.len());
+    let mut warnings = Vec::new();
+    for raw in raw_contents {
+        let Some(page) = includer(&raw) else {
+            pages.push(raw);
+            continue;
+        };
+        warnings.push(format!(
+            "{} is included by {}, so it is not built as a page",
+            raw.path
+                .strip_prefix(project_root)
+                .unwrap_or(&raw.path)
+                .display(),
+            page.strip_prefix(project_root).unwrap_or(page).display()
+        ));
+    }
+    Ok((pages, warnings))
+}
+
+/// Collect a parallel stage's results in input order, returning the first error by position.
+///
+/// `rayon`'s own `Result` collection short-circuits on whichever error a worker hits
+/// first, which would make error reports depend on scheduling.
+fn collect_in_order<T, E, I>(iter: I) -> Result<Vec<T>, BuildError>
+where
+    T: Send,
+    E: Send + Into<BuildError>,
+    I: ParallelIterator<Item = Result<T, E>>,
+{
+    let results: Vec<Result<T, E>> = iter.collect();
+    results
+        .into_iter()
+        .map(|result| result.map_err(Into::into))
+        .collect()
+}
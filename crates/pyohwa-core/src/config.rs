@@ -1,7 +1,9 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 
+use crate::content::schema::FieldSchema;
 use crate::error::ConfigError;
 use crate::site::graph::{NavItem, SidebarGroup};
 
@@ -17,36 +19,42 @@ pub fn load(project_root: &Path) -> Result<Config, ConfigError> {
         source: e,
     })?;
     let config: Config = toml::from_str(&content).map_err(|e| ConfigError::ParseError {
-        path: config_path,
+        path: config_path.clone(),
         reason: e.to_string(),
     })?;
+    for (table, tags) in [("head", &config.head), ("body_end", &config.body_end)] {
+        for tag in tags {
+            tag.validate().map_err(|reason| ConfigError::ParseError {
+                path: config_path.clone(),
+                reason: format!("invalid [[{table}]] entry: {reason}"),
+            })?;
+        }
+    }
+    for (name, field) in &config.frontmatter.schema {
+        field.validate().map_err(|reason| ConfigError::ParseError {
+            path: config_path.clone(),
+            reason: format!("invalid [frontmatter.schema.{name}]: {reason}"),
+        })?;
+    }
     Ok(config)
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 #[serde(default)]
 pub struct Config {
     pub site: SiteConfig,
     pub build: BuildConfig,
     pub theme: ThemeConfig,
+    pub markdown: MarkdownConfig,
+    pub frontmatter: FrontmatterConfig,
     pub nav: Vec<NavItem>,
     pub sidebar: SidebarConfig,
     pub search: SearchConfig,
     pub seo: SeoConfig,
-}
-
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            site: SiteConfig::default(),
-            build: BuildConfig::default(),
-            theme: ThemeConfig::default(),
-            nav: Vec::new(),
-            sidebar: SidebarConfig::default(),
-            search: SearchConfig::default(),
-            seo: SeoConfig::default(),
-        }
-    }
+    /// Elements added to the `<head>` of every page, after the stylesheets
+    pub head: Vec<HtmlTag>,
+    /// Elements added to the end of the `<body>` of every page, after the scripts
+    pub body_end: Vec<HtmlTag>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -75,6 +83,8 @@ pub struct BuildConfig {
     pub content_dir: PathBuf,
     pub output_dir: PathBuf,
     pub static_dir: PathBuf,
+    /// Worker threads for the per-page build stages (0 = one per CPU core)
+    pub jobs: usize,
 }
 
 impl Default for BuildConfig {
@@ -83,6 +93,7 @@ impl Default for BuildConfig {
             content_dir: PathBuf::from("content"),
             output_dir: PathBuf::from("dist"),
             static_dir: PathBuf::from("static"),
+            jobs: 0,
         }
     }
 }
@@ -91,25 +102,49 @@ impl Default for BuildConfig {
 #[serde(default)]
 pub struct ThemeConfig {
     pub name: String,
+    /// Syntect theme used for code blocks in light mode
     pub highlight_theme: String,
+    /// Syntect theme used for code blocks in dark mode
+    pub highlight_theme_dark: String,
+    /// Stylesheet relative to the project root, linked after the theme's
     pub custom_css: Option<PathBuf>,
+    /// Values for the settings the theme declares in its `theme.toml`
+    pub settings: BTreeMap<String, toml::Value>,
 }
 
 impl Default for ThemeConfig {
     fn default() -> Self {
         Self {
             name: "default".to_string(),
-            highlight_theme: "one-dark".to_string(),
+            highlight_theme: "InspiredGitHub".to_string(),
+            highlight_theme_dark: "base16-ocean.dark".to_string(),
             custom_css: None,
+            settings: BTreeMap::new(),
         }
     }
 }
 
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct MarkdownConfig {
+    /// Render `$...$` and `$$...$$` TeX math to MathML at build time
+    pub math: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FrontmatterConfig {
+    /// Rules every page's frontmatter is checked against, by field name
+    pub schema: BTreeMap<String, FieldSchema>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(default)]
 pub struct SidebarConfig {
     pub auto: bool,
     pub groups: Vec<SidebarGroup>,
+    /// Custom frontmatter field shown as a badge next to each page's link, e.g. `status`
+    pub badge: Option<String>,
 }
 
 impl Default for SidebarConfig {
@@ -117,6 +152,7 @@ impl Default for SidebarConfig {
         Self {
             auto: true,
             groups: Vec::new(),
+            badge: None,
         }
     }
 }
@@ -125,11 +161,140 @@ impl Default for SidebarConfig {
 #[serde(default)]
 pub struct SearchConfig {
     pub enabled: bool,
+    /// Custom frontmatter fields copied into the index, so `status:beta` in a
+    /// query keeps only the matching pages
+    pub filters: Vec<String>,
+    /// Fail the build when the index files together are larger than this
+    pub max_index_size: Option<ByteSize>,
+    /// Glob patterns of content files left out of the index, relative to the
+    /// content directory, e.g. `changelog/**`
+    pub exclude: Vec<String>,
+    /// Characters of text indexed per page section
+    pub max_content_length: usize,
+    /// Leave code blocks out of the index
+    pub exclude_code: bool,
+    pub weights: SearchWeights,
+    /// Words not worth indexing, by site language, e.g. `en = ["the", "a"]`
+    pub stop_words: BTreeMap<String, Vec<String>>,
 }
 
 impl Default for SearchConfig {
     fn default() -> Self {
-        Self { enabled: true }
+        Self {
+            enabled: true,
+            filters: Vec::new(),
+            max_index_size: None,
+            exclude: Vec::new(),
+            max_content_length: 5000,
+            exclude_code: false,
+            weights: SearchWeights::default(),
+            stop_words: BTreeMap::new(),
+        }
+    }
+}
+
+impl SearchConfig {
+    /// Whether a content file, given relative to the content directory, matches
+    /// an `exclude` pattern.
+    pub fn excludes(&self, source: &Path) -> bool {
+        let path = source.to_string_lossy().replace('\\', "/");
+        self.exclude
+            .iter()
+            .any(|pattern| glob_match(pattern, &path))
+    }
+
+    /// The stop words for `language`, falling back to its primary subtag
+    /// (`en` for `en-US`).
+    pub fn stop_words_for(&self, language: &str) -> &[String] {
+        let primary = language.split('-').next().unwrap_or(language);
+        self.stop_words
+            .get(language)
+            .or_else(|| self.stop_words.get(primary))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+/// How much a search match in each part of a page counts.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchWeights {
+    pub title: f32,
+    pub headings: f32,
+    pub tags: f32,
+    pub body: f32,
+}
+
+impl Default for SearchWeights {
+    fn default() -> Self {
+        Self {
+            title: 4.0,
+            headings: 2.0,
+            tags: 2.0,
+            body: 1.0,
+        }
+    }
+}
+
+/// Match a `/`-separated path against a glob pattern: `*` matches within one
+/// path segment, `**` across segments and `?` one character.
+fn glob_match(pattern: &str, path: &str) -> bool {
+    fn matches(pattern: &[char], path: &[char]) -> bool {
+        match pattern {
+            [] => path.is_empty(),
+            ['*', '*', '/', rest @ ..] => {
+                matches(rest, path)
+                    || (0..path.len()).any(|i| path[i] == '/' && matches(rest, &path[i + 1..]))
+            }
+            ['*', '*', rest @ ..] => (0..=path.len()).any(|i| matches(rest, &path[i..])),
+            ['*', rest @ ..] => (0..=path.len())
+                .take_while(|&i| i == 0 || path[i - 1] != '/')
+                .any(|i| matches(rest, &path[i..])),
+            ['?', rest @ ..] => {
+                path.first().is_some_and(|&c| c != '/') && matches(rest, &path[1..])
+            }
+            [c, rest @ ..] => path.first() == Some(c) && matches(rest, &path[1..]),
+        }
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let path: Vec<char> = path.chars().collect();
+    matches(&pattern, &path)
+}
+
+/// A size in bytes, written as a number of bytes or with a unit, e.g. `"500KB"`
+/// or `"20MB"`. Units are multiples of 1024.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "ByteSizeValue")]
+pub struct ByteSize(pub u64);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ByteSizeValue {
+    Bytes(u64),
+    Text(String),
+}
+
+impl TryFrom<ByteSizeValue> for ByteSize {
+    type Error = String;
+
+    fn try_from(value: ByteSizeValue) -> Result<Self, String> {
+        let text = match value {
+            ByteSizeValue::Bytes(bytes) => return Ok(Self(bytes)),
+            ByteSizeValue::Text(text) => text,
+        };
+        let invalid = || format!("invalid size `{text}`, expected e.g. 500KB or 20MB");
+        let trimmed = text.trim();
+        let digits = trimmed
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(trimmed.len());
+        let number: u64 = trimmed[..digits].parse().map_err(|_| invalid())?;
+        let unit: u64 = match trimmed[digits..].trim().to_ascii_uppercase().as_str() {
+            "" | "B" => 1,
+            "K" | "KB" => 1 << 10,
+            "M" | "MB" => 1 << 20,
+            "G" | "GB" => 1 << 30,
+            _ => return Err(invalid()),
+        };
+        number.checked_mul(unit).map(Self).ok_or_else(invalid)
     }
 }
 
@@ -151,6 +316,60 @@ impl Default for SeoConfig {
     }
 }
 
+/// Elements that have no content or closing tag.
+const VOID_ELEMENTS: &[&str] = &["base", "link", "meta"];
+
+/// An element from `[[head]]` or `[[body_end]]`, e.g.
+///
+/// ```toml
+/// [[head]]
+/// tag = "meta"
+/// attrs = { name = "theme-color", content = "#3eaf7c" }
+///
+/// [[body_end]]
+/// tag = "script"
+/// attrs = { src = "https://example.com/analytics.js", defer = true }
+/// ```
+///
+/// String attribute values are escaped; `true` renders a bare attribute and
+/// `false` omits it. `content` is inserted as is, so inline scripts and styles
+/// work unchanged.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HtmlTag {
+    pub tag: String,
+    #[serde(default)]
+    pub attrs: BTreeMap<String, toml::Value>,
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+impl HtmlTag {
+    /// Whether the element is written without content or closing tag.
+    pub fn is_void(&self) -> bool {
+        VOID_ELEMENTS.contains(&self.tag.to_ascii_lowercase().as_str())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let is_name = |name: &str| {
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ':')
+        };
+        if !is_name(&self.tag) {
+            return Err(format!("'{}' is not a valid tag name", self.tag));
+        }
+        if let Some(name) = self.attrs.keys().find(|name| !is_name(name)) {
+            return Err(format!("'{name}' is not a valid attribute name"));
+        }
+        if self.is_void() && self.content.is_some() {
+            return Err(format!("<{}> cannot have content", self.tag));
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,6 +399,7 @@ title = "My Docs"
         assert_eq!(config.site.title, "My Docs");
         assert_eq!(config.site.language, "en");
         assert_eq!(config.build.output_dir, PathBuf::from("dist"));
+        assert_eq!(config.build.jobs, 0);
     }
 
     #[test]
@@ -206,10 +426,12 @@ language = "ko"
 content_dir = "src"
 output_dir = "build"
 static_dir = "public"
+jobs = 4
 
 [theme]
 name = "custom"
 highlight_theme = "monokai"
+highlight_theme_dark = "Solarized (dark)"
 
 [search]
 enabled = false
@@ -225,9 +447,157 @@ og_image = "og.png"
         assert_eq!(config.site.title, "Full Site");
         assert_eq!(config.site.language, "ko");
         assert_eq!(config.build.content_dir, PathBuf::from("src"));
+        assert_eq!(config.build.jobs, 4);
         assert_eq!(config.theme.highlight_theme, "monokai");
+        assert_eq!(config.theme.highlight_theme_dark, "Solarized (dark)");
         assert!(!config.search.enabled);
+        assert_eq!(config.search.max_index_size, None);
         assert!(config.seo.rss);
         assert_eq!(config.seo.og_image, Some("og.png".to_string()));
     }
+
+    #[test]
+    fn head_and_body_end_entries_parse() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(
+            tmp.path().join("pyohwa.toml"),
+            r##"
+[[head]]
+tag = "meta"
+attrs = { name = "theme-color", content = "#fff" }
+
+[[body_end]]
+tag = "script"
+content = "console.log('hi')"
+"##,
+        )
+        .unwrap();
+        let config = load(tmp.path()).unwrap();
+        assert_eq!(config.head.len(), 1);
+        assert!(config.head[0].is_void());
+        assert_eq!(config.head[0].attrs["name"].as_str(), Some("theme-color"));
+        assert_eq!(
+            config.body_end[0].content.as_deref(),
+            Some("console.log('hi')")
+        );
+    }
+
+    #[test]
+    fn frontmatter_schema_parses_and_is_checked() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(
+            tmp.path().join("pyohwa.toml"),
+            r#"
+[frontmatter.schema.status]
+type = "string"
+required = true
+enum = ["beta", "stable"]
+
+[frontmatter.schema.date]
+type = "date"
+format = "%d.%m.%Y"
+"#,
+        )
+        .unwrap();
+        let config = load(tmp.path()).unwrap();
+        let status = &config.frontmatter.schema["status"];
+        assert!(status.required);
+        assert_eq!(status.values, vec!["beta", "stable"]);
+        assert_eq!(
+            config.frontmatter.schema["date"].format.as_deref(),
+            Some("%d.%m.%Y")
+        );
+
+        fs::write(
+            tmp.path().join("pyohwa.toml"),
+            "[frontmatter.schema.date]\ntype = \"date\"\nformat = \"%B %d\"\n",
+        )
+        .unwrap();
+        let err = load(tmp.path()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("invalid [frontmatter.schema.date]: unsupported date format '%B %d'"));
+    }
+
+    #[test]
+    fn invalid_head_entries_are_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        for entry in [
+            "tag = \"meta onload=x\"",
+            "tag = \"link\"\ncontent = \"x\"",
+            "tag = \"meta\"\nattrs = { \"a b\" = \"c\" }",
+        ] {
+            fs::write(
+                tmp.path().join("pyohwa.toml"),
+                format!("[[head]]\n{entry}\n"),
+            )
+            .unwrap();
+            let err = load(tmp.path()).unwrap_err();
+            assert!(
+                err.to_string().contains("invalid [[head]] entry"),
+                "{entry}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn max_index_size_accepts_bytes_or_units() {
+        let parse = |value: &str| {
+            toml::from_str::<SearchConfig>(&format!("max_index_size = {value}"))
+                .map(|search| search.max_index_size)
+        };
+        assert_eq!(parse("4096").unwrap(), Some(ByteSize(4096)));
+        assert_eq!(parse("\"500KB\"").unwrap(), Some(ByteSize(500 * 1024)));
+        assert_eq!(
+            parse("\"20 mb\"").unwrap(),
+            Some(ByteSize(20 * 1024 * 1024))
+        );
+        let err = parse("\"20 parsecs\"").unwrap_err();
+        assert!(err.to_string().contains("invalid size `20 parsecs`"));
+    }
+
+    #[test]
+    fn search_exclude_globs() {
+        let search = SearchConfig {
+            exclude: vec!["changelog/**".to_string(), "**/internal-*.md".to_string()],
+            ..Default::default()
+        };
+        assert!(search.excludes(Path::new("changelog/v1.md")));
+        assert!(search.excludes(Path::new("changelog/2024/v2.md")));
+        assert!(search.excludes(Path::new("internal-notes.md")));
+        assert!(search.excludes(Path::new("guide/internal-api.md")));
+        assert!(!search.excludes(Path::new("guide/changelog.md")));
+        assert!(!search.excludes(Path::new("guide/internal/api.md")));
+
+        assert!(glob_match("guide/*.md", "guide/setup.md"));
+        assert!(!glob_match("guide/*.md", "guide/cli/setup.md"));
+        assert!(glob_match("v?.md", "v1.md"));
+    }
+
+    #[test]
+    fn search_options_parse() {
+        let search: SearchConfig = toml::from_str(
+            r#"
+exclude = ["drafts/**"]
+max_content_length = 800
+exclude_code = true
+
+[weights]
+title = 8.0
+
+[stop_words]
+en = ["the", "a"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(search.max_content_length, 800);
+        assert!(search.exclude_code);
+        assert_eq!(search.weights.title, 8.0);
+        assert_eq!(search.weights.body, 1.0);
+        assert_eq!(search.stop_words_for("en-US"), ["the", "a"]);
+        assert!(search.stop_words_for("ko").is_empty());
+
+        let err = toml::from_str::<SearchConfig>("[weights]\nheading = 3.0").unwrap_err();
+        assert!(err.to_string().contains("unknown field `heading`"));
+    }
 }
@@ -1,6 +1,8 @@
 use serde::Deserialize;
+use serde_json::{Map, Value};
 
 use crate::content::page::{ParsedContent, RawContent};
+use crate::content::schema;
 use crate::error::ContentError;
 
 /// Parse frontmatter from raw content, separating YAML header from body
@@ -15,12 +17,17 @@ pub fn parse_frontmatter(raw: &RawContent) -> Result<ParsedContent, ContentError
     let parsed = matter.parse(&raw.raw);
 
     let frontmatter = if let Some(data) = parsed.data {
-        let raw_fm: RawFrontmatter =
-            data.deserialize()
-                .map_err(|e| ContentError::InvalidFrontmatter {
-                    path: raw.path.clone(),
-                    reason: e.to_string(),
-                })?;
+        let raw_fm: RawFrontmatter = data.deserialize().map_err(|e| {
+            // serde's message names neither the field nor where it is
+            let (line, column, reason) =
+                schema::locate_invalid(&raw.raw).unwrap_or((1, 1, e.to_string()));
+            ContentError::InvalidFrontmatter {
+                path: raw.path.clone(),
+                line,
+                column,
+                reason,
+            }
+        })?;
         let fm = raw_fm.into_frontmatter("");
         if fm.title.is_empty() {
             return Err(ContentError::MissingTitle {
@@ -34,14 +41,25 @@ pub fn parse_frontmatter(raw: &RawContent) -> Result<ParsedContent, ContentError
         });
     };
 
+    // The body is what follows the frontmatter block
+    let body_line = if raw.raw.ends_with(&parsed.content) {
+        raw.raw[..raw.raw.len() - parsed.content.len()]
+            .matches('\n')
+            .count()
+            + 1
+    } else {
+        1
+    };
+
     Ok(ParsedContent {
         path: raw.path.clone(),
         frontmatter,
         body: parsed.content,
+        body_line,
     })
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Frontmatter {
     pub title: String,
     pub description: Option<String>,
@@ -52,6 +70,33 @@ pub struct Frontmatter {
     pub draft: bool,
     pub prev: Option<String>,
     pub next: Option<String>,
+    /// `false` keeps the page out of the search index
+    pub search: Option<bool>,
+    /// Multiplies the page's search scores, e.g. `2.0` to rank it higher
+    pub search_boost: Option<f32>,
+    /// Keys pyohwa does not know, e.g. `status: beta`, passed through to
+    /// templates, the client and the search index
+    pub extra: Map<String, Value>,
+}
+
+impl Frontmatter {
+    /// A custom field as text: a scalar gives one value, a list one per scalar
+    /// item. Missing fields, `null` and tables give none.
+    pub fn extra_values(&self, key: &str) -> Vec<String> {
+        fn text(value: &Value) -> Option<String> {
+            match value {
+                Value::String(s) => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                Value::Bool(b) => Some(b.to_string()),
+                _ => None,
+            }
+        }
+        match self.extra.get(key) {
+            Some(Value::Array(items)) => items.iter().filter_map(text).collect(),
+            Some(value) => text(value).into_iter().collect(),
+            None => Vec::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -63,18 +108,14 @@ pub enum Layout {
     Custom(String),
 }
 
-impl Default for Frontmatter {
-    fn default() -> Self {
-        Self {
-            title: String::new(),
-            description: None,
-            layout: Layout::default(),
-            order: None,
-            tags: Vec::new(),
-            date: None,
-            draft: false,
-            prev: None,
-            next: None,
+impl Layout {
+    /// The name as written in frontmatter: `doc`, `home`, `page` or the custom name.
+    pub fn name(&self) -> &str {
+        match self {
+            Layout::Doc => "doc",
+            Layout::Home => "home",
+            Layout::Page => "page",
+            Layout::Custom(name) => name,
         }
     }
 }
@@ -91,6 +132,10 @@ pub(crate) struct RawFrontmatter {
     pub draft: Option<bool>,
     pub prev: Option<String>,
     pub next: Option<String>,
+    pub search: Option<bool>,
+    pub search_boost: Option<f32>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl RawFrontmatter {
@@ -110,6 +155,9 @@ impl RawFrontmatter {
             draft: self.draft.unwrap_or(false),
             prev: self.prev,
             next: self.next,
+            search: self.search,
+            search_boost: self.search_boost,
+            extra: self.extra,
         }
     }
 }
@@ -146,6 +194,7 @@ Body content here."#);
         assert_eq!(result.frontmatter.layout, Layout::Doc);
         assert!(!result.frontmatter.draft);
         assert_eq!(result.body, "Body content here.");
+        assert_eq!(result.body_line, 8);
     }
 
     #[test]
@@ -195,6 +244,31 @@ Body"#);
         }
     }
 
+    #[test]
+    fn invalid_field_type_reports_its_position() {
+        let input = raw("---\ntitle: Typed\norder: first\n---\nBody");
+        let err = parse_frontmatter(&input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid frontmatter in test.md:3:8: 'order' must be an integer, got a string `first`"
+        );
+    }
+
+    #[test]
+    fn search_fields_parsed() {
+        let input = raw("---\ntitle: Hidden\nsearch: false\nsearch_boost: 1.5\n---\nBody");
+        let result = parse_frontmatter(&input).unwrap();
+        assert_eq!(result.frontmatter.search, Some(false));
+        assert_eq!(result.frontmatter.search_boost, Some(1.5));
+        assert!(result.frontmatter.extra.is_empty());
+
+        let input = raw("---\ntitle: Boosted\nsearch_boost: high\n---\nBody");
+        let err = parse_frontmatter(&input).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("3:15: 'search_boost' must be a number"));
+    }
+
     #[test]
     fn draft_flag_parsed() {
         let input = raw("---\ntitle: Draft\ndraft: true\n---\nBody");
@@ -213,5 +287,30 @@ Body"#);
         assert!(!result.frontmatter.draft);
         assert!(result.frontmatter.prev.is_none());
         assert!(result.frontmatter.next.is_none());
+        assert!(result.frontmatter.search.is_none());
+        assert!(result.frontmatter.search_boost.is_none());
+        assert!(result.frontmatter.extra.is_empty());
+    }
+
+    #[test]
+    fn unknown_keys_are_kept() {
+        let input = raw(r#"---
+title: Custom
+status: beta
+owner:
+  - platform
+  - infra
+review:
+  due: 2024-06-01
+---
+Body"#);
+        let fm = parse_frontmatter(&input).unwrap().frontmatter;
+        assert_eq!(fm.extra.len(), 3);
+        assert!(!fm.extra.contains_key("title"));
+        assert_eq!(fm.extra["status"], serde_json::json!("beta"));
+        assert_eq!(fm.extra_values("owner"), vec!["platform", "infra"]);
+        assert_eq!(fm.extra_values("status"), vec!["beta"]);
+        assert!(fm.extra_values("review").is_empty());
+        assert!(fm.extra_values("missing").is_empty());
     }
 }
@@ -0,0 +1,491 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::content::page::{ParsedContent, RawContent};
+use crate::error::ContentError;
+use crate::markdown::container::FenceTracker;
+
+/// `<<< @/examples/demo.rs#region {rust title="demo.rs"}` — a source file as a code block.
+static SNIPPET_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\s*)<<<\s+([^\s{]+)\s*(?:\{(.*)\})?\s*$").unwrap());
+/// `@include(./partial.md#region)` — a Markdown partial, expanded recursively.
+static INCLUDE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\s*)@include\(\s*([^)]+?)\s*\)\s*$").unwrap());
+/// `// #region name` / `# #endregion` / `<!-- #region name -->` marker lines.
+static REGION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?://|#|--|;|/\*|<!--)\s*#(region|endregion)\b\s*([\w-]*)").unwrap()
+});
+/// `#L10`, `#L10-L20` or `#L10-20` line range selectors.
+static LINE_RANGE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^L(\d+)(?:-L?(\d+))?$").unwrap());
+
+/// A Markdown body with include directives expanded, plus every file it pulled in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expanded {
+    pub body: String,
+    pub deps: Vec<PathBuf>,
+}
+
+/// Expand the include directives of a parsed page's body.
+pub fn expand_content(
+    content: &ParsedContent,
+    project_root: &Path,
+) -> Result<ParsedContent, ContentError> {
+    let expanded = expand(&content.body, &content.path, project_root)?;
+    Ok(ParsedContent {
+        path: content.path.clone(),
+        frontmatter: content.frontmatter.clone(),
+        body: expanded.body,
+        body_line: content.body_line,
+    })
+}
+
+/// Expand `<<<` snippet and `@include(...)` directives in `markdown`.
+///
+/// Paths starting with `@/` are relative to `project_root`, anything else to the
+/// directory of `source`. Targets may not leave the project root. A `#name` suffix
+/// selects a `#region name` … `#endregion` block and `#L3-L8` a line range.
+/// Partials are expanded recursively; an include cycle is an error.
+/// Directives inside fenced code blocks are left as written.
+pub fn expand(
+    markdown: &str,
+    source: &Path,
+    project_root: &Path,
+) -> Result<Expanded, ContentError> {
+    let root = project_root
+        .canonicalize()
+        .unwrap_or_else(|_| project_root.to_path_buf());
+    let start = source
+        .canonicalize()
+        .unwrap_or_else(|_| source.to_path_buf());
+
+    let mut deps = BTreeSet::new();
+    let mut stack = vec![start];
+    let body = expand_inner(markdown, source, &root, &mut stack, &mut deps)?;
+
+    Ok(Expanded {
+        body,
+        deps: deps.into_iter().collect(),
+    })
+}
+
+/// Markdown files pulled in by the `@include` directives of `pages`, directly or
+/// through other partials, each mapped to the path of the first page including it.
+///
+/// Keys are canonical paths. Targets that do not resolve are left out here;
+/// expanding the page reports them.
+pub fn partial_sources(pages: &[RawContent], project_root: &Path) -> BTreeMap<PathBuf, PathBuf> {
+    let root = project_root
+        .canonicalize()
+        .unwrap_or_else(|_| project_root.to_path_buf());
+
+    let mut found = BTreeMap::new();
+    for page in pages {
+        collect_partials(
+            strip_frontmatter(&page.raw),
+            &page.path,
+            &root,
+            &page.path,
+            &mut found,
+        );
+    }
+    found
+}
+
+fn collect_partials(
+    markdown: &str,
+    source: &Path,
+    root: &Path,
+    page: &Path,
+    found: &mut BTreeMap<PathBuf, PathBuf>,
+) {
+    let mut fences = FenceTracker::default();
+    for line in markdown.lines() {
+        if fences.in_code(line) {
+            continue;
+        }
+        let Some(caps) = INCLUDE_RE.captures(line) else {
+            continue;
+        };
+        let (target, _) = split_selector(&caps[2]);
+        let Ok(path) = resolve_target(target, source, root) else {
+            continue;
+        };
+        if found.contains_key(&path) {
+            continue;
+        }
+        found.insert(path.clone(), page.to_path_buf());
+        if let Ok(text) = std::fs::read_to_string(&path) {
+            collect_partials(strip_frontmatter(&text), &path, root, page, found);
+        }
+    }
+}
+
+fn expand_inner(
+    markdown: &str,
+    source: &Path,
+    root: &Path,
+    stack: &mut Vec<PathBuf>,
+    deps: &mut BTreeSet<PathBuf>,
+) -> Result<String, ContentError> {
+    let mut result = String::with_capacity(markdown.len());
+    let mut fences = FenceTracker::default();
+
+    for line in markdown.lines() {
+        if fences.in_code(line) {
+            result.push_str(line);
+            result.push('\n');
+            continue;
+        }
+
+        if let Some(caps) = SNIPPET_RE.captures(line) {
+            let (target, selector) = split_selector(&caps[2]);
+            let path = resolve_target(target, source, root)?;
+            let text = read_target(&path, source)?;
+            let code = select(&text, selector, &path, source)?;
+            deps.insert(path.clone());
+
+            let info = caps
+                .get(3)
+                .map(|m| m.as_str().trim().to_string())
+                .filter(|info| !info.is_empty())
+                .or_else(|| {
+                    path.extension()
+                        .and_then(|e| e.to_str())
+                        .map(str::to_string)
+                })
+                .unwrap_or_default();
+            let fence = "`".repeat(longest_backtick_run(&code).max(2) + 1);
+            let block = format!("{fence}{info}\n{code}\n{fence}");
+            push_indented(&mut result, &block, &caps[1]);
+            continue;
+        } else if let Some(caps) = INCLUDE_RE.captures(line) {
+            let (target, selector) = split_selector(&caps[2]);
+            let path = resolve_target(target, source, root)?;
+
+            if let Some(pos) = stack.iter().position(|p| *p == path) {
+                let chain = stack[pos..]
+                    .iter()
+                    .chain(std::iter::once(&path))
+                    .map(|p| p.strip_prefix(root).unwrap_or(p).display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                return Err(ContentError::IncludeCycle {
+                    path: source.to_path_buf(),
+                    chain,
+                });
+            }
+
+            let text = read_target(&path, source)?;
+            let partial = select(strip_frontmatter(&text), selector, &path, source)?;
+            deps.insert(path.clone());
+
+            stack.push(path.clone());
+            let expanded = expand_inner(&partial, &path, root, stack, deps)?;
+            stack.pop();
+
+            push_indented(&mut result, expanded.trim_end_matches('\n'), &caps[1]);
+            continue;
+        }
+
+        result.push_str(line);
+        result.push('\n');
+    }
+
+    Ok(result)
+}
+
+fn split_selector(target: &str) -> (&str, Option<&str>) {
+    match target.split_once('#') {
+        Some((path, selector)) => (path, Some(selector).filter(|s| !s.is_empty())),
+        None => (target, None),
+    }
+}
+
+fn resolve_target(target: &str, source: &Path, root: &Path) -> Result<PathBuf, ContentError> {
+    let joined = match target.strip_prefix("@/") {
+        Some(rest) => root.join(rest),
+        None => source.parent().unwrap_or(Path::new("")).join(target),
+    };
+    let path = joined.canonicalize().map_err(|_| ContentError::Include {
+        path: source.to_path_buf(),
+        reason: format!("'{target}' not found"),
+    })?;
+    if !path.starts_with(root) {
+        return Err(ContentError::Include {
+            path: source.to_path_buf(),
+            reason: format!("'{target}' is outside the project root"),
+        });
+    }
+    Ok(path)
+}
+
+fn read_target(path: &Path, source: &Path) -> Result<String, ContentError> {
+    std::fs::read_to_string(path).map_err(|e| ContentError::Include {
+        path: source.to_path_buf(),
+        reason: format!("failed to read {}: {e}", path.display()),
+    })
+}
+
+/// Apply a `#region` or `#L3-L8` selector. Without one, region marker lines are
+/// still dropped so annotated sources can be included whole.
+fn select(
+    text: &str,
+    selector: Option<&str>,
+    path: &Path,
+    source: &Path,
+) -> Result<String, ContentError> {
+    let lines: Vec<&str> = text.lines().collect();
+
+    let Some(selector) = selector else {
+        return Ok(without_markers(&lines).join("\n"));
+    };
+
+    if let Some(caps) = LINE_RANGE_RE.captures(selector) {
+        let start: usize = caps[1].parse().unwrap_or(0);
+        let end: usize = caps
+            .get(2)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(start);
+        if start == 0 || start > end || start > lines.len() {
+            return Err(ContentError::Include {
+                path: source.to_path_buf(),
+                reason: format!(
+                    "line range '{selector}' is out of bounds for {} ({} lines)",
+                    path.display(),
+                    lines.len()
+                ),
+            });
+        }
+        return Ok(lines[start - 1..end.min(lines.len())].join("\n"));
+    }
+
+    let mut inside = false;
+    let mut selected = Vec::new();
+    for line in &lines {
+        if let Some(caps) = REGION_RE.captures(line) {
+            if &caps[2] == selector {
+                if &caps[1] == "region" {
+                    inside = true;
+                    continue;
+                }
+                if inside {
+                    return Ok(dedent(&without_markers(&selected)));
+                }
+            }
+        }
+        if inside {
+            selected.push(*line);
+        }
+    }
+
+    Err(ContentError::Include {
+        path: source.to_path_buf(),
+        reason: format!("region '{selector}' not found in {}", path.display()),
+    })
+}
+
+fn without_markers<'s>(lines: &[&'s str]) -> Vec<&'s str> {
+    lines
+        .iter()
+        .filter(|line| !REGION_RE.is_match(line))
+        .copied()
+        .collect()
+}
+
+/// Remove the indentation shared by all non-blank lines.
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn strip_frontmatter(text: &str) -> &str {
+    let Some(rest) = text.strip_prefix("---\n") else {
+        return text;
+    };
+    match rest.find("\n---") {
+        Some(end) => rest[end + 4..].trim_start_matches(['\r', '\n']),
+        None => text,
+    }
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+/// Append `block` with `indent` before every non-empty line, so an include keeps
+/// the nesting (list item, blockquote-free indentation) of its directive.
+fn push_indented(result: &mut String, block: &str, indent: &str) {
+    for line in block.lines() {
+        if !line.is_empty() {
+            result.push_str(indent);
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn project() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("content/guide")).unwrap();
+        fs::create_dir_all(root.join("examples")).unwrap();
+        fs::write(
+            root.join("examples/demo.rs"),
+            "use std::io;\n\nfn main() {\n    // #region setup\n    let x = 1;\n    let y = 2;\n    // #endregion setup\n    println!(\"{x} {y}\");\n}\n",
+        )
+        .unwrap();
+        tmp
+    }
+
+    fn page(root: &Path) -> PathBuf {
+        root.join("content/guide/page.md")
+    }
+
+    #[test]
+    fn snippet_region_becomes_code_block() {
+        let tmp = project();
+        let root = tmp.path();
+        let expanded = expand("<<< @/examples/demo.rs#setup {rust}", &page(root), root).unwrap();
+        assert_eq!(expanded.body, "```rust\nlet x = 1;\nlet y = 2;\n```\n");
+        assert_eq!(
+            expanded.deps,
+            vec![root.canonicalize().unwrap().join("examples/demo.rs")]
+        );
+    }
+
+    #[test]
+    fn snippet_defaults_language_to_extension_and_drops_markers() {
+        let tmp = project();
+        let root = tmp.path();
+        let expanded = expand("<<< @/examples/demo.rs", &page(root), root).unwrap();
+        assert!(expanded.body.starts_with("```rs\nuse std::io;\n"));
+        assert!(!expanded.body.contains("#region"));
+    }
+
+    #[test]
+    fn snippet_line_range_and_info_attributes() {
+        let tmp = project();
+        let root = tmp.path();
+        let expanded = expand(
+            "<<< ../../examples/demo.rs#L3-L4 {rust title=\"demo.rs\" {1}}",
+            &page(root),
+            root,
+        )
+        .unwrap();
+        assert_eq!(
+            expanded.body,
+            "```rust title=\"demo.rs\" {1}\nfn main() {\n    // #region setup\n```\n"
+        );
+    }
+
+    #[test]
+    fn partials_expand_recursively_and_keep_indentation() {
+        let tmp = project();
+        let root = tmp.path();
+        fs::write(
+            root.join("content/_outer.md"),
+            "---\ntitle: Outer\n---\nOuter\n@include(./guide/_inner.md)\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("content/guide/_inner.md"),
+            "Inner\n\n<<< @/examples/demo.rs#L1",
+        )
+        .unwrap();
+
+        let expanded = expand("- item\n\n  @include(../_outer.md)\n", &page(root), root).unwrap();
+        assert_eq!(
+            expanded.body,
+            "- item\n\n  Outer\n  Inner\n\n  ```rs\n  use std::io;\n  ```\n"
+        );
+        assert_eq!(expanded.deps.len(), 3);
+    }
+
+    #[test]
+    fn include_cycle_is_reported() {
+        let tmp = project();
+        let root = tmp.path();
+        fs::write(root.join("content/a.md"), "@include(./b.md)").unwrap();
+        fs::write(root.join("content/b.md"), "@include(./a.md)").unwrap();
+
+        let err = expand("@include(../a.md)", &page(root), root).unwrap_err();
+        assert!(matches!(err, ContentError::IncludeCycle { .. }));
+        assert!(err
+            .to_string()
+            .contains("content/a.md -> content/b.md -> content/a.md"));
+    }
+
+    #[test]
+    fn missing_targets_and_regions_are_errors() {
+        let tmp = project();
+        let root = tmp.path();
+        let err = expand("<<< @/examples/nope.rs", &page(root), root).unwrap_err();
+        assert!(err.to_string().contains("'@/examples/nope.rs' not found"));
+
+        let err = expand("<<< @/examples/demo.rs#nope", &page(root), root).unwrap_err();
+        assert!(err.to_string().contains("region 'nope' not found"));
+
+        let err = expand("<<< @/examples/demo.rs#L40", &page(root), root).unwrap_err();
+        assert!(err.to_string().contains("out of bounds"));
+    }
+
+    #[test]
+    fn targets_outside_the_project_are_rejected() {
+        let tmp = project();
+        let root = tmp.path().join("examples");
+        fs::write(tmp.path().join("secret.txt"), "s").unwrap();
+        let err = expand("<<< ../secret.txt", &root.join("x.md"), &root).unwrap_err();
+        assert!(err.to_string().contains("outside the project root"));
+    }
+
+    #[test]
+    fn directives_inside_fences_are_left_alone() {
+        let tmp = project();
+        let root = tmp.path();
+        let markdown = "```md\n<<< @/examples/demo.rs\n@include(./missing.md)\n```\n";
+        let expanded = expand(markdown, &page(root), root).unwrap();
+        assert_eq!(expanded.body, markdown);
+        assert!(expanded.deps.is_empty());
+    }
+
+    #[test]
+    fn partial_sources_follow_nested_includes_outside_code() {
+        let tmp = project();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("content/partials")).unwrap();
+        fs::write(root.join("content/partials/a.md"), "@include(./b.md)\n").unwrap();
+        fs::write(root.join("content/partials/b.md"), "B\n").unwrap();
+        fs::write(root.join("content/partials/c.md"), "C\n").unwrap();
+
+        let pages = vec![RawContent {
+            path: page(root),
+            raw: "---\ntitle: Page\n---\n@include(../partials/a.md)\n\n```md\n@include(../partials/c.md)\n```\n@include(./missing.md)\n".to_string(),
+        }];
+        let partials = partial_sources(&pages, root);
+
+        let content = root.canonicalize().unwrap().join("content/partials");
+        assert_eq!(
+            partials.keys().cloned().collect::<Vec<_>>(),
+            vec![content.join("a.md"), content.join("b.md")]
+        );
+        assert!(partials.values().all(|p| *p == page(root)));
+    }
+}
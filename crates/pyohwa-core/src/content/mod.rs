@@ -1,3 +1,5 @@
 pub mod frontmatter;
+pub mod include;
 pub mod loader;
 pub mod page;
+pub mod schema;
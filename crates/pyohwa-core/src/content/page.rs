@@ -16,6 +16,8 @@ pub struct ParsedContent {
     pub path: PathBuf,
     pub frontmatter: Frontmatter,
     pub body: String,
+    /// 1-based line of the source file where `body` starts
+    pub body_line: usize,
 }
 
 /// TOC item extracted from headings
@@ -0,0 +1,629 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+use crate::content::page::RawContent;
+
+/// Format used for `type = "date"` fields without their own `format`.
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The types of the fields pyohwa itself reads, used to point at the field
+/// behind a frontmatter that fails to deserialize.
+const BUILTIN_FIELDS: &[(&str, FieldType)] = &[
+    ("title", FieldType::String),
+    ("description", FieldType::String),
+    ("layout", FieldType::String),
+    ("order", FieldType::Integer),
+    ("tags", FieldType::List),
+    ("date", FieldType::String),
+    ("draft", FieldType::Boolean),
+    ("prev", FieldType::String),
+    ("next", FieldType::String),
+    ("search", FieldType::Boolean),
+    ("search_boost", FieldType::Number),
+];
+
+/// One field of `[frontmatter.schema]`, e.g.
+///
+/// ```toml
+/// [frontmatter.schema.status]
+/// type = "string"
+/// required = true
+/// enum = ["beta", "stable"]
+///
+/// [frontmatter.schema.date]
+/// type = "date"
+/// format = "%Y-%m-%d"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldSchema {
+    #[serde(rename = "type")]
+    pub kind: Option<FieldType>,
+    #[serde(default)]
+    pub required: bool,
+    /// Allowed values; for lists, allowed items
+    #[serde(rename = "enum", default)]
+    pub values: Vec<String>,
+    /// strftime-style format of a `date` field: `%Y`, `%m`, `%d`, `%H`, `%M`, `%S`
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Date,
+    List,
+}
+
+impl FieldType {
+    fn describe(self) -> &'static str {
+        match self {
+            FieldType::String => "a string",
+            FieldType::Integer => "an integer",
+            FieldType::Number => "a number",
+            FieldType::Boolean => "a boolean",
+            FieldType::Date => "a date",
+            FieldType::List => "a list",
+        }
+    }
+}
+
+impl FieldSchema {
+    /// Check the schema entry itself, when the config is loaded.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if let Some(format) = &self.format {
+            if self.kind != Some(FieldType::Date) {
+                return Err("`format` only applies to `type = \"date\"`".to_string());
+            }
+            let mut chars = format.chars();
+            while let Some(c) = chars.next() {
+                if c == '%'
+                    && !matches!(chars.next(), Some('Y' | 'm' | 'd' | 'H' | 'M' | 'S' | '%'))
+                {
+                    return Err(format!("unsupported date format '{format}'"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A frontmatter value that breaks the schema, at its position in the source file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.path.display(),
+            self.line,
+            self.column,
+            self.message
+        )
+    }
+}
+
+/// Check a content file's frontmatter against `schema`, returning every violation.
+///
+/// Files without a frontmatter block are left to `parse_frontmatter`, which
+/// reports them.
+pub fn check(raw: &RawContent, schema: &BTreeMap<String, FieldSchema>) -> Vec<SchemaViolation> {
+    let Some(header) = Header::parse(&raw.raw) else {
+        return Vec::new();
+    };
+    let violation = |line: usize, column: usize, message: String| SchemaViolation {
+        path: raw.path.clone(),
+        line,
+        column,
+        message,
+    };
+
+    let fields = match header.fields() {
+        Ok(fields) => fields,
+        Err((line, column, message)) => return vec![violation(line, column, message)],
+    };
+
+    let mut violations = Vec::new();
+    for (name, field) in schema {
+        match fields.iter().find(|(key, _)| key == name) {
+            Some((_, value)) => {
+                if let Some(message) = check_value(name, field, value) {
+                    violations.push(violation(value.line, value.column, message));
+                }
+            }
+            None if field.required => violations.push(violation(
+                header.delimiter_line,
+                1,
+                format!("missing required field '{name}'"),
+            )),
+            None => {}
+        }
+    }
+    violations.sort_by_key(|v| (v.line, v.column));
+    violations
+}
+
+/// Find the field that made a frontmatter fail to deserialize: the first one
+/// whose value does not have the type pyohwa reads it as, or a YAML syntax error.
+pub(crate) fn locate_invalid(raw: &str) -> Option<(usize, usize, String)> {
+    let header = Header::parse(raw)?;
+    let fields = match header.fields() {
+        Ok(fields) => fields,
+        Err(error) => return Some(error),
+    };
+    BUILTIN_FIELDS.iter().find_map(|(name, kind)| {
+        let (_, value) = fields.iter().find(|(key, _)| key == name)?;
+        let field = FieldSchema {
+            kind: Some(*kind),
+            ..Default::default()
+        };
+        // A missing optional value is fine for every built-in field
+        if matches!(&value.node, Node::Scalar { plain: true, value } if is_null(value)) {
+            return None;
+        }
+        check_value(name, &field, value).map(|message| (value.line, value.column, message))
+    })
+}
+
+fn check_value(name: &str, field: &FieldSchema, value: &Spanned) -> Option<String> {
+    let scalars: Vec<&str> = match (&value.node, field.kind) {
+        (Node::List(items), Some(FieldType::List) | None) => {
+            let mut scalars = Vec::new();
+            for item in items {
+                match &item.node {
+                    Node::Scalar { value, .. } => scalars.push(value.as_str()),
+                    _ => return Some(format!("'{name}' must be a list of values")),
+                }
+            }
+            scalars
+        }
+        (_, Some(FieldType::List)) => {
+            return Some(format!("'{name}' must be a list, got {}", value.describe()));
+        }
+        (Node::Scalar { value: text, plain }, kind) => {
+            if let Some(kind) = kind {
+                if !scalar_has_type(text, *plain, kind, field) {
+                    return Some(match kind {
+                        FieldType::Date => format!(
+                            "'{name}' must be a date in the format {}, got `{text}`",
+                            field.format.as_deref().unwrap_or(DEFAULT_DATE_FORMAT)
+                        ),
+                        _ => format!(
+                            "'{name}' must be {}, got {}",
+                            kind.describe(),
+                            value.describe()
+                        ),
+                    });
+                }
+            }
+            vec![text.as_str()]
+        }
+        (_, Some(kind)) => {
+            return Some(format!(
+                "'{name}' must be {}, got {}",
+                kind.describe(),
+                value.describe()
+            ));
+        }
+        (_, None) => Vec::new(),
+    };
+
+    if field.values.is_empty() {
+        return None;
+    }
+    scalars
+        .into_iter()
+        .find(|scalar| !field.values.iter().any(|allowed| allowed == scalar))
+        .map(|scalar| {
+            format!(
+                "'{name}' must be one of {}; got `{scalar}`",
+                field.values.join(", ")
+            )
+        })
+}
+
+fn scalar_has_type(text: &str, plain: bool, kind: FieldType, field: &FieldSchema) -> bool {
+    match kind {
+        FieldType::String => !plain || scalar_kind(text) == "a string",
+        FieldType::Integer => plain && text.parse::<i64>().is_ok(),
+        FieldType::Number => plain && text.parse::<f64>().is_ok(),
+        FieldType::Boolean => plain && is_bool(text),
+        FieldType::Date => {
+            matches_date_format(text, field.format.as_deref().unwrap_or(DEFAULT_DATE_FORMAT))
+        }
+        FieldType::List => false,
+    }
+}
+
+/// How YAML reads an unquoted scalar.
+fn scalar_kind(text: &str) -> &'static str {
+    if is_null(text) {
+        "null"
+    } else if is_bool(text) {
+        "a boolean"
+    } else if text.parse::<i64>().is_ok() {
+        "an integer"
+    } else if text.parse::<f64>().is_ok() {
+        "a number"
+    } else {
+        "a string"
+    }
+}
+
+fn is_null(text: &str) -> bool {
+    matches!(text, "" | "~" | "null" | "Null" | "NULL")
+}
+
+fn is_bool(text: &str) -> bool {
+    matches!(text, "true" | "True" | "TRUE" | "false" | "False" | "FALSE")
+}
+
+/// Whether `text` matches a strftime-style `format` exactly.
+fn matches_date_format(text: &str, format: &str) -> bool {
+    let mut rest = text;
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        let (width, range) = match (c, chars.clone().next()) {
+            ('%', Some('Y')) => (4, 0..=9999),
+            ('%', Some('m')) => (2, 1..=12),
+            ('%', Some('d')) => (2, 1..=31),
+            ('%', Some('H')) => (2, 0..=23),
+            ('%', Some('M' | 'S')) => (2, 0..=59),
+            ('%', Some('%')) => {
+                chars.next();
+                match rest.strip_prefix('%') {
+                    Some(after) => {
+                        rest = after;
+                        continue;
+                    }
+                    None => return false,
+                }
+            }
+            _ => match rest.strip_prefix(c) {
+                Some(after) => {
+                    rest = after;
+                    continue;
+                }
+                None => return false,
+            },
+        };
+        chars.next();
+        let Some(digits) = rest.get(..width) else {
+            return false;
+        };
+        if !digits.bytes().all(|b| b.is_ascii_digit())
+            || !range.contains(&digits.parse::<u32>().unwrap_or(u32::MAX))
+        {
+            return false;
+        }
+        rest = &rest[width..];
+    }
+    rest.is_empty()
+}
+
+/// The YAML between a file's `---` delimiters.
+struct Header<'a> {
+    yaml: &'a str,
+    /// Line of the opening `---` in the file
+    delimiter_line: usize,
+}
+
+impl<'a> Header<'a> {
+    fn parse(raw: &'a str) -> Option<Self> {
+        let mut offset = 0;
+        let mut start = None;
+        for (index, line) in raw.split_inclusive('\n').enumerate() {
+            let trimmed = line.trim_end();
+            match start {
+                None if trimmed.is_empty() => {}
+                None if trimmed == "---" => start = Some((offset + line.len(), index + 1)),
+                None => return None,
+                Some((begin, delimiter_line)) if trimmed == "---" => {
+                    return Some(Self {
+                        yaml: &raw[begin..offset],
+                        delimiter_line,
+                    });
+                }
+                Some(_) => {}
+            }
+            offset += line.len();
+        }
+        None
+    }
+
+    /// Top-level fields with their values, positioned in the file. A YAML syntax
+    /// error comes back as its line, column and message.
+    fn fields(&self) -> Result<Vec<(String, Spanned)>, (usize, usize, String)> {
+        let mut builder = TreeBuilder::default();
+        Parser::new_from_str(self.yaml)
+            .load(&mut builder, false)
+            .map_err(|err| {
+                let (line, column) = self.position(err.marker());
+                (line, column, format!("invalid YAML: {}", err.info()))
+            })?;
+
+        let Some(root) = builder.root else {
+            return Ok(Vec::new());
+        };
+        let Node::Map(entries) = root.node else {
+            let (line, column) = (root.line, root.column);
+            return Err((
+                line + self.delimiter_line,
+                column,
+                "frontmatter must be a map of fields".to_string(),
+            ));
+        };
+        Ok(entries
+            .into_iter()
+            .map(|(key, mut value)| {
+                value.line += self.delimiter_line;
+                value.shift(self.delimiter_line);
+                (key, value)
+            })
+            .collect())
+    }
+
+    /// File line and column (both 1-based) of a marker in the header.
+    fn position(&self, marker: &Marker) -> (usize, usize) {
+        (self.delimiter_line + marker.line(), marker.col() + 1)
+    }
+}
+
+/// A YAML value with the 1-based line and column where it starts; lines are
+/// relative to the header until [`Header::fields`] shifts them.
+#[derive(Debug)]
+struct Spanned {
+    node: Node,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Debug)]
+enum Node {
+    /// `plain` is false for quoted and block scalars, which are always strings
+    Scalar {
+        value: String,
+        plain: bool,
+    },
+    List(Vec<Spanned>),
+    Map(Vec<(String, Spanned)>),
+    Alias,
+}
+
+impl Spanned {
+    fn describe(&self) -> String {
+        match &self.node {
+            Node::Scalar { value, plain: true } => format!("{} `{value}`", scalar_kind(value)),
+            Node::Scalar { value, .. } => format!("a string `{value}`"),
+            Node::List(_) => "a list".to_string(),
+            Node::Map(_) => "a map".to_string(),
+            Node::Alias => "an alias".to_string(),
+        }
+    }
+
+    /// Move nested values down by `lines`; the caller has moved this one.
+    fn shift(&mut self, lines: usize) {
+        match &mut self.node {
+            Node::List(items) => items.iter_mut().for_each(|item| {
+                item.line += lines;
+                item.shift(lines);
+            }),
+            Node::Map(entries) => entries.iter_mut().for_each(|(_, value)| {
+                value.line += lines;
+                value.shift(lines);
+            }),
+            Node::Scalar { .. } | Node::Alias => {}
+        }
+    }
+}
+
+/// Builds a [`Spanned`] tree from the parser's events.
+#[derive(Default)]
+struct TreeBuilder {
+    /// Open lists and maps, with the key waiting for its value in a map
+    stack: Vec<(Spanned, Option<String>)>,
+    root: Option<Spanned>,
+}
+
+impl TreeBuilder {
+    fn push(&mut self, value: Spanned) {
+        let Some((parent, pending_key)) = self.stack.last_mut() else {
+            self.root = Some(value);
+            return;
+        };
+        match &mut parent.node {
+            Node::List(items) => items.push(value),
+            Node::Map(entries) => match pending_key.take() {
+                Some(key) => entries.push((key, value)),
+                None => {
+                    // The parser marks a block map at its first value; its first key reads better
+                    if entries.is_empty() {
+                        parent.line = value.line;
+                        parent.column = value.column;
+                    }
+                    *pending_key = Some(match value.node {
+                        Node::Scalar { value, .. } => value,
+                        _ => String::new(),
+                    });
+                }
+            },
+            Node::Scalar { .. } | Node::Alias => {}
+        }
+    }
+}
+
+impl MarkedEventReceiver for TreeBuilder {
+    fn on_event(&mut self, event: Event, marker: Marker) {
+        let spanned = |node| Spanned {
+            node,
+            line: marker.line(),
+            column: marker.col() + 1,
+        };
+        match event {
+            Event::Scalar(value, style, ..) => self.push(spanned(Node::Scalar {
+                value,
+                plain: style == TScalarStyle::Plain,
+            })),
+            Event::Alias(_) => self.push(spanned(Node::Alias)),
+            Event::SequenceStart(..) => self.stack.push((spanned(Node::List(Vec::new())), None)),
+            Event::MappingStart(..) => self.stack.push((spanned(Node::Map(Vec::new())), None)),
+            Event::SequenceEnd | Event::MappingEnd => {
+                if let Some((value, _)) = self.stack.pop() {
+                    self.push(value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(content: &str) -> RawContent {
+        RawContent {
+            path: PathBuf::from("content/guide.md"),
+            raw: content.to_string(),
+        }
+    }
+
+    fn schema(toml: &str) -> BTreeMap<String, FieldSchema> {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn messages(violations: &[SchemaViolation]) -> Vec<String> {
+        violations.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn valid_frontmatter_passes() {
+        let schema = schema(
+            r#"
+status = { type = "string", required = true, enum = ["beta", "stable"] }
+owners = { type = "list", enum = ["platform", "infra"] }
+order = { type = "integer" }
+weight = { type = "number" }
+published = { type = "boolean" }
+date = { type = "date" }
+"#,
+        );
+        let page = raw(
+            "---\ntitle: Guide\nstatus: beta\nowners: [platform, infra]\norder: 3\nweight: 0.5\npublished: true\ndate: 2024-02-29\n---\nBody\n",
+        );
+        assert_eq!(check(&page, &schema), vec![]);
+    }
+
+    #[test]
+    fn violations_point_at_the_value() {
+        let schema = schema(
+            r#"
+status = { type = "string", enum = ["beta", "stable"] }
+order = { type = "integer" }
+date = { type = "date", format = "%Y/%m/%d" }
+owners = { type = "list" }
+draft = { type = "boolean" }
+"#,
+        );
+        let page = raw(
+            "---\ntitle: Guide\nstatus:   alpha\norder: \"3\"\ndate: 2024-01-01\nowners: platform\ndraft:\n  nested: true\n---\n",
+        );
+        assert_eq!(
+            messages(&check(&page, &schema)),
+            vec![
+                "content/guide.md:3:11: 'status' must be one of beta, stable; got `alpha`",
+                "content/guide.md:4:8: 'order' must be an integer, got a string `3`",
+                "content/guide.md:5:7: 'date' must be a date in the format %Y/%m/%d, got `2024-01-01`",
+                "content/guide.md:6:9: 'owners' must be a list, got a string `platform`",
+                "content/guide.md:8:3: 'draft' must be a boolean, got a map",
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_required_fields_point_at_the_header() {
+        let schema = schema(r#"owner = { required = true }"#);
+        let page = raw("\n---\ntitle: Guide\n---\n");
+        assert_eq!(
+            messages(&check(&page, &schema)),
+            vec!["content/guide.md:2:1: missing required field 'owner'"]
+        );
+    }
+
+    #[test]
+    fn list_items_are_checked_against_the_enum() {
+        let schema = schema(r#"tags = { enum = ["rust", "docs"] }"#);
+        let page = raw("---\ntitle: Guide\ntags:\n  - rust\n  - go\n---\n");
+        assert_eq!(
+            messages(&check(&page, &schema)),
+            vec!["content/guide.md:4:3: 'tags' must be one of rust, docs; got `go`"]
+        );
+    }
+
+    #[test]
+    fn yaml_syntax_errors_are_located() {
+        let schema = schema(r#"status = { type = "string" }"#);
+        let page = raw("---\ntitle: Guide\nstatus: [beta\n---\n");
+        let violations = check(&page, &schema);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].message.starts_with("invalid YAML: "));
+        assert_eq!(violations[0].line, 4);
+    }
+
+    #[test]
+    fn date_formats() {
+        assert!(matches_date_format("2024-01-31", "%Y-%m-%d"));
+        assert!(matches_date_format("2024-01-31 23:59", "%Y-%m-%d %H:%M"));
+        assert!(matches_date_format("100%", "100%%"));
+        assert!(!matches_date_format("2024-13-01", "%Y-%m-%d"));
+        assert!(!matches_date_format("2024-1-01", "%Y-%m-%d"));
+        assert!(!matches_date_format("2024-01-01T", "%Y-%m-%d"));
+        assert!(!matches_date_format("日付", "%Y"));
+    }
+
+    #[test]
+    fn schema_entries_are_validated() {
+        let bad_format = FieldSchema {
+            kind: Some(FieldType::Date),
+            format: Some("%Y-%j".to_string()),
+            ..Default::default()
+        };
+        assert!(bad_format.validate().is_err());
+
+        let format_without_date = FieldSchema {
+            kind: Some(FieldType::String),
+            format: Some("%Y".to_string()),
+            ..Default::default()
+        };
+        assert!(format_without_date.validate().is_err());
+    }
+
+    #[test]
+    fn locates_builtin_type_errors() {
+        assert_eq!(
+            locate_invalid("---\ntitle: Guide\norder: first\n---\n"),
+            Some((
+                3,
+                8,
+                "'order' must be an integer, got a string `first`".to_string()
+            ))
+        );
+        assert_eq!(locate_invalid("---\ntitle: Guide\norder: ~\n---\n"), None);
+    }
+}
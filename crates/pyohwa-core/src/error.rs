@@ -9,11 +9,30 @@ pub enum ContentError {
     #[error("missing frontmatter in {path}")]
     MissingFrontmatter { path: PathBuf },
 
-    #[error("invalid frontmatter in {path}: {reason}")]
-    InvalidFrontmatter { path: PathBuf, reason: String },
+    #[error("invalid frontmatter in {path}:{line}:{column}: {reason}")]
+    InvalidFrontmatter {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        reason: String,
+    },
 
     #[error("missing required field 'title' in {path}")]
     MissingTitle { path: PathBuf },
+
+    #[error("invalid math in {path}:{line}:{column}: {reason}")]
+    Math {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        reason: String,
+    },
+
+    #[error("invalid include in {path}: {reason}")]
+    Include { path: PathBuf, reason: String },
+
+    #[error("include cycle in {path}: {chain}")]
+    IncludeCycle { path: PathBuf, chain: String },
 }
 
 #[derive(Error, Debug)]
@@ -26,15 +45,49 @@ pub enum ConfigError {
 
     #[error("failed to parse config file {path}: {reason}")]
     ParseError { path: PathBuf, reason: String },
+
+    #[error("unknown highlight theme '{name}' (available: {available})")]
+    UnknownHighlightTheme { name: String, available: String },
+
+    #[error("theme '{name}' not found: {path} does not exist")]
+    ThemeNotFound { name: String, path: PathBuf },
+
+    #[error("theme.custom_css not found: {path}")]
+    CustomCssNotFound { path: PathBuf },
+
+    #[error("invalid theme manifest {path}: {reason}")]
+    InvalidThemeManifest { path: PathBuf, reason: String },
+
+    #[error("theme '{theme}' does not accept setting '{key}' (accepted: {accepted})")]
+    UnknownThemeSetting {
+        theme: String,
+        key: String,
+        accepted: String,
+    },
+
+    #[error("theme setting '{key}' must be a {expected}, got a {found}")]
+    InvalidThemeSetting {
+        key: String,
+        expected: String,
+        found: String,
+    },
 }
 
 #[derive(Error, Debug)]
 pub enum RenderError {
-    #[error("template error: {0}")]
-    Template(String),
+    #[error("template error in {}:{line}: {reason}", file.display())]
+    Template {
+        file: PathBuf,
+        line: usize,
+        reason: String,
+    },
 
-    #[error("layout not found: {0}")]
-    LayoutNotFound(String),
+    #[error("layout not found: '{name}' (in {}:{line})", file.display())]
+    LayoutNotFound {
+        name: String,
+        file: PathBuf,
+        line: usize,
+    },
 
     #[error("serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
@@ -59,4 +112,16 @@ pub enum BuildError {
 
     #[error("search error: {0}")]
     Search(String),
+
+    #[error("highlight error: {0}")]
+    Highlight(String),
+
+    #[error("thread pool error: {0}")]
+    ThreadPool(String),
+
+    #[error("{} broken link(s):\n{}", .0.len(), .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"))]
+    BrokenLinks(Vec<crate::site::links::BrokenLink>),
+
+    #[error("content errors:\n\n{0}")]
+    Diagnostics(crate::build::diagnostics::Diagnostics),
 }
@@ -0,0 +1,217 @@
+use std::cell::RefCell;
+
+use comrak::nodes::{AlertType, Ast, AstNode, NodeHtmlBlock, NodeValue};
+use comrak::Arena;
+
+use crate::render::labels::primary_language;
+use crate::render::template::escape_html;
+
+/// Callout kinds shared by `::: kind` containers and GitHub `> [!KIND]` alerts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdmonitionKind {
+    Note,
+    Info,
+    Tip,
+    Important,
+    Warning,
+    Danger,
+    Caution,
+    Details,
+}
+
+impl AdmonitionKind {
+    /// Container name as written after `:::`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "note" => Some(Self::Note),
+            "info" => Some(Self::Info),
+            "tip" => Some(Self::Tip),
+            "important" => Some(Self::Important),
+            "warning" => Some(Self::Warning),
+            "danger" => Some(Self::Danger),
+            "caution" => Some(Self::Caution),
+            "details" => Some(Self::Details),
+            _ => None,
+        }
+    }
+
+    pub fn from_alert(alert: AlertType) -> Self {
+        match alert {
+            AlertType::Note => Self::Note,
+            AlertType::Tip => Self::Tip,
+            AlertType::Important => Self::Important,
+            AlertType::Warning => Self::Warning,
+            AlertType::Caution => Self::Caution,
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            Self::Note => "note",
+            Self::Info => "info",
+            Self::Tip => "tip",
+            Self::Important => "important",
+            Self::Warning => "warning",
+            Self::Danger => "danger",
+            Self::Caution => "caution",
+            Self::Details => "details",
+        }
+    }
+
+    /// Default title in the site's language (`ko`, `ko-KR`, ...), falling back to English.
+    pub fn label(self, language: &str) -> &'static str {
+        // Indexed by the declaration order of `AdmonitionKind`
+        let labels: [&str; 8] = match primary_language(language).as_str() {
+            "ko" => [
+                "참고",
+                "정보",
+                "팁",
+                "중요",
+                "경고",
+                "위험",
+                "주의",
+                "자세히",
+            ],
+            "ja" => [
+                "注記",
+                "情報",
+                "ヒント",
+                "重要",
+                "警告",
+                "危険",
+                "注意",
+                "詳細",
+            ],
+            "zh" => [
+                "备注", "信息", "提示", "重要", "警告", "危险", "注意", "详情",
+            ],
+            "de" => [
+                "Hinweis", "Info", "Tipp", "Wichtig", "Warnung", "Gefahr", "Vorsicht", "Details",
+            ],
+            "fr" => [
+                "Remarque",
+                "Info",
+                "Astuce",
+                "Important",
+                "Avertissement",
+                "Danger",
+                "Attention",
+                "Détails",
+            ],
+            "es" => [
+                "Nota",
+                "Información",
+                "Consejo",
+                "Importante",
+                "Advertencia",
+                "Peligro",
+                "Precaución",
+                "Detalles",
+            ],
+            _ => [
+                "Note",
+                "Info",
+                "Tip",
+                "Important",
+                "Warning",
+                "Danger",
+                "Caution",
+                "Details",
+            ],
+        };
+        labels[self as usize]
+    }
+}
+
+/// Opening markup for an admonition. `title` overrides the localized label.
+///
+/// `details` becomes a collapsible `<details>`; every other kind is an `<aside>`.
+pub fn open_html(kind: AdmonitionKind, title: Option<&str>, language: &str) -> String {
+    let title = escape_html(title.unwrap_or_else(|| kind.label(language)));
+    match kind {
+        AdmonitionKind::Details => format!(
+            "<details class=\"admonition admonition-details\">\n<summary class=\"admonition-title\">{title}</summary>\n"
+        ),
+        _ => format!(
+            "<aside class=\"admonition admonition-{}\" role=\"note\">\n<p class=\"admonition-title\">{title}</p>\n",
+            kind.class()
+        ),
+    }
+}
+
+pub fn close_html(kind: AdmonitionKind) -> String {
+    match kind {
+        AdmonitionKind::Details => "</details>\n".to_string(),
+        _ => "</aside>\n".to_string(),
+    }
+}
+
+/// Rewrite comrak's GitHub alert nodes into the same markup as `:::` admonitions,
+/// keeping the alert's children in place between the opening and closing HTML.
+pub fn convert_alerts<'a>(arena: &'a Arena<AstNode<'a>>, root: &'a AstNode<'a>, language: &str) {
+    let alerts: Vec<_> = root
+        .descendants()
+        .filter(|node| matches!(node.data.borrow().value, NodeValue::Alert(_)))
+        .collect();
+
+    for node in alerts {
+        let (kind, title, start) = {
+            let data = node.data.borrow();
+            let NodeValue::Alert(ref alert) = data.value else {
+                continue;
+            };
+            (
+                AdmonitionKind::from_alert(alert.alert_type),
+                alert.title.clone(),
+                data.sourcepos.start,
+            )
+        };
+
+        let html_node = |literal: String| {
+            arena.alloc(AstNode::new(RefCell::new(Ast::new(
+                NodeValue::HtmlBlock(NodeHtmlBlock {
+                    block_type: 0,
+                    literal,
+                }),
+                start,
+            ))))
+        };
+
+        node.insert_before(html_node(open_html(kind, title.as_deref(), language)));
+        while let Some(child) = node.first_child() {
+            node.insert_before(child);
+        }
+        node.insert_before(html_node(close_html(kind)));
+        node.detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_follow_site_language() {
+        assert_eq!(AdmonitionKind::Tip.label("en"), "Tip");
+        assert_eq!(AdmonitionKind::Warning.label("ko"), "경고");
+        assert_eq!(AdmonitionKind::Details.label("ko-KR"), "자세히");
+        assert_eq!(AdmonitionKind::Danger.label("pt-BR"), "Danger");
+    }
+
+    #[test]
+    fn open_html_escapes_custom_title() {
+        let html = open_html(AdmonitionKind::Info, Some("<b>Heads up</b>"), "en");
+        assert_eq!(
+            html,
+            "<aside class=\"admonition admonition-info\" role=\"note\">\n<p class=\"admonition-title\">&lt;b&gt;Heads up&lt;/b&gt;</p>\n"
+        );
+    }
+
+    #[test]
+    fn details_is_collapsible() {
+        let html = open_html(AdmonitionKind::Details, None, "ja");
+        assert!(html.starts_with("<details class=\"admonition admonition-details\">"));
+        assert!(html.contains("<summary class=\"admonition-title\">詳細</summary>"));
+        assert_eq!(close_html(AdmonitionKind::Details), "</details>\n");
+    }
+}
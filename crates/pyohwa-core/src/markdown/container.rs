@@ -0,0 +1,230 @@
+use std::sync::LazyLock;
+
+use comrak::nodes::{AstNode, NodeHtmlBlock, NodeValue};
+use regex::Regex;
+
+use crate::markdown::admonition::{self, AdmonitionKind};
+use crate::markdown::highlight::{self, CodeBlockMeta, Highlighter};
+use crate::markdown::parser::{collect_text, MarkdownContext};
+
+/// `::: name extra` opens a container, a bare `:::` closes it. Outer containers
+/// may use more colons (`::::`) so their closing marker pairs with the right opener.
+static MARKER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(:{3,})\s*(\S*)\s*(.*)$").unwrap());
+
+/// Put every `:::` marker line on its own paragraph so it survives as a separate
+/// block node; CommonMark would otherwise fold it into the neighbouring paragraph.
+///
+/// Only closing markers and openers of known containers count, indented by at
+/// most three spaces like any other block start. Other lines, and lines inside
+/// fenced code blocks, are left untouched.
+pub fn isolate_markers(markdown: &str) -> String {
+    let mut result = String::with_capacity(markdown.len());
+    let mut fences = FenceTracker::default();
+    let mut previous_blank = true;
+
+    for line in markdown.lines() {
+        if !fences.in_code(line) && is_marker_line(line) {
+            if !previous_blank {
+                result.push('\n');
+            }
+            result.push_str(line);
+            result.push_str("\n\n");
+            previous_blank = true;
+            continue;
+        }
+
+        result.push_str(line);
+        result.push('\n');
+        previous_blank = line.trim().is_empty();
+    }
+
+    result
+}
+
+fn is_marker_line(line: &str) -> bool {
+    let unindented = line.trim_start_matches(' ');
+    if line.len() - unindented.len() > 3 {
+        return false;
+    }
+    MARKER_RE
+        .captures(unindented)
+        .is_some_and(|caps| caps[2].is_empty() || is_known(&caps[2]))
+}
+
+/// Whether `name` is a container `build_containers` renders.
+fn is_known(name: &str) -> bool {
+    name == "code-group" || AdmonitionKind::from_name(name).is_some()
+}
+
+/// Tracks whether successive source lines are inside a fenced code block, for
+/// line-based passes that must not touch code examples.
+#[derive(Default)]
+pub(crate) struct FenceTracker<'s> {
+    open: Option<&'s str>,
+}
+
+impl<'s> FenceTracker<'s> {
+    /// Feed the next line. Returns `true` for fence delimiters and fenced content.
+    pub(crate) fn in_code(&mut self, line: &'s str) -> bool {
+        let trimmed = line.trim_start();
+        let run = fence_run(trimmed);
+        match self.open {
+            Some(open) => {
+                if run.len() >= open.len()
+                    && run.starts_with(&open[..1])
+                    && trimmed[run.len()..].trim().is_empty()
+                {
+                    self.open = None;
+                }
+                true
+            }
+            None if run.len() >= 3 => {
+                self.open = Some(run);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Leading run of backticks or tildes that could open or close a code fence.
+fn fence_run(line: &str) -> &str {
+    let Some(first) = line.chars().next().filter(|c| *c == '`' || *c == '~') else {
+        return "";
+    };
+    let end = line.find(|c| c != first).unwrap_or(line.len());
+    &line[..end]
+}
+
+enum Marker {
+    Open {
+        colons: usize,
+        name: String,
+        rest: String,
+    },
+    Close {
+        colons: usize,
+    },
+}
+
+fn marker<'a>(node: &'a AstNode<'a>) -> Option<Marker> {
+    if !matches!(node.data.borrow().value, NodeValue::Paragraph) {
+        return None;
+    }
+    let text = collect_text(node);
+    let caps = MARKER_RE.captures(text.trim())?;
+    let colons = caps[1].len();
+    let name = caps[2].to_string();
+    if name.is_empty() {
+        Some(Marker::Close { colons })
+    } else {
+        Some(Marker::Open {
+            colons,
+            name,
+            rest: caps[3].trim().to_string(),
+        })
+    }
+}
+
+/// Pair up container markers among the children of every node and replace
+/// known containers with their rendered HTML. Unknown container names are
+/// left as plain paragraphs.
+pub fn build_containers<'a>(node: &'a AstNode<'a>, ctx: &MarkdownContext) {
+    let children: Vec<_> = node.children().collect();
+    let mut open: Vec<(usize, usize, String, String)> = Vec::new();
+
+    for (index, child) in children.iter().enumerate() {
+        match marker(child) {
+            Some(Marker::Open { colons, name, rest }) => open.push((index, colons, name, rest)),
+            Some(Marker::Close { colons })
+                if open.last().is_some_and(|(_, c, _, _)| *c == colons) =>
+            {
+                if let Some((start, _, name, rest)) = open.pop() {
+                    let body = &children[start + 1..index];
+                    if name == "code-group" {
+                        code_group(children[start], body, child, ctx.highlighter);
+                    } else if let Some(kind) = AdmonitionKind::from_name(&name) {
+                        let title = Some(rest.as_str()).filter(|t| !t.is_empty());
+                        replace_with_html(
+                            children[start],
+                            admonition::open_html(kind, title, ctx.language),
+                        );
+                        replace_with_html(child, admonition::close_html(kind));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for child in node.children() {
+        build_containers(child, ctx);
+    }
+}
+
+/// Render the fenced blocks of a `::: code-group` as tabs into the opening
+/// marker's node and drop the blocks and the closing marker from the tree.
+fn code_group<'a>(
+    open: &'a AstNode<'a>,
+    body: &[&'a AstNode<'a>],
+    close: &'a AstNode<'a>,
+    highlighter: &Highlighter,
+) {
+    let mut blocks = Vec::new();
+    for node in body {
+        let data = node.data.borrow();
+        if let NodeValue::CodeBlock(ref block) = data.value {
+            blocks.push((block.literal.clone(), CodeBlockMeta::parse(&block.info)));
+            drop(data);
+            node.detach();
+        }
+    }
+
+    replace_with_html(open, highlight::render_code_group(&blocks, highlighter));
+    close.detach();
+}
+
+/// Turn a marker paragraph into a raw HTML block, dropping its inline children.
+fn replace_with_html<'a>(node: &'a AstNode<'a>, literal: String) {
+    while let Some(child) = node.first_child() {
+        child.detach();
+    }
+    node.data.borrow_mut().value = NodeValue::HtmlBlock(NodeHtmlBlock {
+        block_type: 0,
+        literal,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isolates_markers_from_surrounding_lines() {
+        let markdown = "::: code-group\n```sh\ncargo add pyohwa\n```\n:::\nAfter";
+        assert_eq!(
+            isolate_markers(markdown),
+            "::: code-group\n\n```sh\ncargo add pyohwa\n```\n\n:::\n\nAfter\n"
+        );
+    }
+
+    #[test]
+    fn leaves_markers_inside_fences_alone() {
+        let markdown = "```md\n::: tip\n:::\n```\n";
+        assert_eq!(isolate_markers(markdown), markdown);
+
+        let nested = "````md\n```\n:::\n````\n";
+        assert_eq!(isolate_markers(nested), nested);
+    }
+
+    #[test]
+    fn leaves_other_colon_lines_alone() {
+        // Prose starting with colons, an unknown name, and an indented code block
+        let markdown = "Some text\n::: not a container\nmore text\n\n    ::: tip\n    code\n";
+        assert_eq!(isolate_markers(markdown), markdown);
+
+        let indented = "Text\n   ::: tip\n";
+        assert_eq!(isolate_markers(indented), "Text\n\n   ::: tip\n\n");
+    }
+}
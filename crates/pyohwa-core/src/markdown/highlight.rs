@@ -1,172 +1,698 @@
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
 use syntect::highlighting::ThemeSet;
-use syntect::html::{ClassStyle, ClassedHTMLGenerator};
-use syntect::parsing::SyntaxSet;
-use syntect::util::LinesWithEndings;
+use syntect::html::{line_tokens_to_classed_spans, ClassStyle};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxReference, SyntaxSet};
+
+use crate::error::{BuildError, ConfigError};
+use crate::render::template::escape_html;
+
+/// Project directory holding extra `.sublime-syntax` grammars.
+pub const SYNTAXES_DIR: &str = "syntaxes";
+/// Project directory searched (recursively) for extra `.tmTheme` color themes.
+pub const THEMES_DIR: &str = "themes";
+
+/// Syntax definitions and color themes shared by every page of a build.
+///
+/// Loading syntect's sets is expensive, so the pipeline builds one `Highlighter`
+/// per build and hands it to each page.
+pub struct Highlighter {
+    pub syntaxes: SyntaxSet,
+    pub themes: ThemeSet,
+}
+
+impl Highlighter {
+    /// Syntect's built-in syntaxes and themes only.
+    pub fn defaults() -> Self {
+        Self {
+            syntaxes: SyntaxSet::load_defaults_newlines(),
+            themes: ThemeSet::load_defaults(),
+        }
+    }
 
-use crate::content::page::RenderedContent;
-use crate::error::BuildError;
+    /// Built-in definitions merged with the project's `syntaxes/*.sublime-syntax`
+    /// and `themes/**/*.tmTheme` files. Custom themes are keyed by file stem.
+    pub fn load(project_root: &Path) -> Result<Self, BuildError> {
+        let mut highlighter = Self::defaults();
 
-/// Apply syntax highlighting to fenced code blocks in rendered HTML.
+        let syntaxes_dir = project_root.join(SYNTAXES_DIR);
+        if syntaxes_dir.is_dir() {
+            let mut builder = highlighter.syntaxes.into_builder();
+            builder.add_from_folder(&syntaxes_dir, true).map_err(|e| {
+                BuildError::Highlight(format!(
+                    "failed to load syntaxes from {}: {e}",
+                    syntaxes_dir.display()
+                ))
+            })?;
+            highlighter.syntaxes = builder.build();
+        }
+
+        let themes_dir = project_root.join(THEMES_DIR);
+        if themes_dir.is_dir() {
+            highlighter
+                .themes
+                .add_from_folder(&themes_dir)
+                .map_err(|e| {
+                    BuildError::Highlight(format!(
+                        "failed to load themes from {}: {e}",
+                        themes_dir.display()
+                    ))
+                })?;
+        }
+
+        Ok(highlighter)
+    }
+}
+
+/// Attributes parsed from a fenced code block's info string.
 ///
-/// Finds `<pre><code class="language-XXX">...</code></pre>` blocks and replaces
-/// the code content with syntect-highlighted HTML using CSS classes.
-pub fn apply_syntax_highlighting(content: &RenderedContent) -> Result<RenderedContent, BuildError> {
-    let highlighted = highlight_code_blocks(&content.html);
-    Ok(RenderedContent {
-        path: content.path.clone(),
-        frontmatter: content.frontmatter.clone(),
-        html: highlighted,
-        toc: content.toc.clone(),
-    })
-}
-
-/// Generate a CSS stylesheet for syntax highlighting.
-pub fn generate_css() -> String {
-    let ts = ThemeSet::load_defaults();
-    let theme = &ts.themes["InspiredGitHub"];
-    syntect::html::css_for_theme_with_class_style(theme, ClassStyle::Spaced).unwrap_or_default()
-}
-
-fn highlight_code_blocks(html: &str) -> String {
-    let ss = SyntaxSet::load_defaults_newlines();
-    let mut result = String::with_capacity(html.len());
-    let mut remaining = html;
-
-    // Pattern: <pre><code class="language-XXX">...CODE...</code></pre>
-    while let Some(pre_start) = remaining.find("<pre><code class=\"language-") {
-        // Copy everything before this match
-        result.push_str(&remaining[..pre_start]);
-
-        let after_prefix = &remaining[pre_start + 26..]; // skip `<pre><code class="language-`
-        let lang_end = match after_prefix.find('"') {
-            Some(pos) => pos,
-            None => {
-                result.push_str(&remaining[pre_start..pre_start + 26]);
-                remaining = after_prefix;
-                continue;
+/// ````text
+/// ```rust title="main.rs" {1,3-5} showLineNumbers
+/// ````
+///
+/// The first word is the language (a trailing `{...}` may be attached to it).
+/// Unrecognized attributes are ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CodeBlockMeta {
+    pub lang: String,
+    pub title: Option<String>,
+    pub highlight_lines: Vec<RangeInclusive<usize>>,
+    pub line_numbers: bool,
+}
+
+impl CodeBlockMeta {
+    pub fn parse(info: &str) -> Self {
+        let mut meta = Self::default();
+        let mut words = split_info(info).into_iter();
+
+        if let Some(first) = words.next() {
+            let rest = match first.find('{') {
+                Some(brace) => {
+                    meta.lang = first[..brace].to_string();
+                    Some(first[brace..].to_string())
+                }
+                None if first.contains('=') => Some(first),
+                None => {
+                    meta.lang = first;
+                    None
+                }
+            };
+            for word in rest.into_iter().chain(words) {
+                meta.apply_attribute(&word);
             }
-        };
-        let lang = &after_prefix[..lang_end];
-
-        // Skip past `">`
-        let code_start_offset = lang_end + 2; // skip `">`
-        let code_html = &after_prefix[code_start_offset..];
-
-        let code_end = match code_html.find("</code></pre>") {
-            Some(pos) => pos,
-            None => {
-                result.push_str(&remaining[pre_start..pre_start + 26 + code_start_offset]);
-                remaining = code_html;
-                continue;
+        }
+
+        meta
+    }
+
+    fn apply_attribute(&mut self, word: &str) {
+        if let Some(ranges) = word.strip_prefix('{').and_then(|w| w.strip_suffix('}')) {
+            self.highlight_lines
+                .extend(ranges.split(',').filter_map(parse_line_range));
+        } else if let Some(title) = word.strip_prefix("title=") {
+            self.title = Some(unquote(title).to_string());
+        } else if word == "showLineNumbers" {
+            self.line_numbers = true;
+        }
+    }
+
+    fn is_highlighted(&self, line: usize) -> bool {
+        self.highlight_lines
+            .iter()
+            .any(|range| range.contains(&line))
+    }
+}
+
+/// Split an info string on whitespace, keeping quoted values together.
+fn split_info(info: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+
+    for c in info.chars() {
+        match quote {
+            Some(q) if c == q => {
+                quote = None;
+                current.push(c);
             }
-        };
+            Some(_) => current.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                current.push(c);
+            }
+            None if c.is_whitespace() => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+            }
+            None => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn unquote(value: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|&q| value.strip_prefix(q).and_then(|v| v.strip_suffix(q)))
+        .unwrap_or(value)
+}
+
+/// Parse `3` or `3-5` into a 1-based inclusive line range.
+fn parse_line_range(spec: &str) -> Option<RangeInclusive<usize>> {
+    let spec = spec.trim();
+    match spec.split_once('-') {
+        Some((start, end)) => Some(start.trim().parse().ok()?..=end.trim().parse().ok()?),
+        None => {
+            let line = spec.parse().ok()?;
+            Some(line..=line)
+        }
+    }
+}
 
-        let code_text = &code_html[..code_end];
-        let decoded = decode_html_entities(code_text);
+/// Trailing `// [!code ++]` / `# [!code --]` / `<!-- [!code ++] -->` annotations.
+static DIFF_MARKER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\s*(?://|#|--|;|/\*|<!--)\s*\[!code (\+\+|--)\]\s*(?:\*/|-->)?\s*$").unwrap()
+});
 
-        let syntax = ss
-            .find_syntax_by_token(lang)
-            .unwrap_or_else(|| ss.find_syntax_plain_text());
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Diff {
+    Add,
+    Remove,
+}
 
-        let mut generator =
-            ClassedHTMLGenerator::new_with_class_style(syntax, &ss, ClassStyle::Spaced);
+/// Strip a diff annotation from the end of a line, returning the remaining code.
+fn strip_diff_marker(line: &str) -> (&str, Option<Diff>) {
+    match DIFF_MARKER_RE.captures(line) {
+        Some(caps) => {
+            let diff = if &caps[1] == "++" {
+                Diff::Add
+            } else {
+                Diff::Remove
+            };
+            (
+                &line[..caps.get(0).map_or(line.len(), |m| m.start())],
+                Some(diff),
+            )
+        }
+        None => (line, None),
+    }
+}
 
-        for line in LinesWithEndings::from(&decoded) {
-            let _ = generator.parse_html_for_line_which_includes_newline(line);
+/// Render a fenced code block to highlighted HTML.
+///
+/// Each source line becomes a `<span class="line">` so highlighted ranges, diff
+/// annotations and CSS-counter line numbers can target it. A `title` wraps the
+/// block in a `div.code-block` with a filename header.
+pub fn render_code_block(code: &str, meta: &CodeBlockMeta, highlighter: &Highlighter) -> String {
+    let ss = &highlighter.syntaxes;
+    let syntax = ss
+        .find_syntax_by_token(&meta.lang)
+        .unwrap_or_else(|| ss.find_syntax_plain_text());
+
+    let (lines, diffs): (Vec<&str>, Vec<Option<Diff>>) =
+        code.lines().map(strip_diff_marker).unzip();
+    let highlighted = highlight_lines(&lines, syntax, ss)
+        .unwrap_or_else(|| lines.iter().map(|line| escape_html(line)).collect());
+
+    let mut body = Vec::with_capacity(lines.len());
+    for (index, (html, diff)) in highlighted.iter().zip(&diffs).enumerate() {
+        let mut class = String::from("line");
+        if meta.is_highlighted(index + 1) {
+            class.push_str(" highlighted");
         }
+        match diff {
+            Some(Diff::Add) => class.push_str(" diff add"),
+            Some(Diff::Remove) => class.push_str(" diff remove"),
+            None => {}
+        }
+        body.push(format!("<span class=\"{class}\">{html}</span>"));
+    }
 
-        let highlighted = generator.finalize();
-        result.push_str(&format!(
-            "<pre class=\"highlight\"><code class=\"language-{lang}\">{highlighted}</code></pre>"
+    let mut pre_class = String::from("highlight");
+    if meta.line_numbers {
+        pre_class.push_str(" line-numbers");
+    }
+    if diffs.iter().any(Option::is_some) {
+        pre_class.push_str(" has-diff");
+    }
+    let code_class = if meta.lang.is_empty() {
+        String::new()
+    } else {
+        format!(" class=\"language-{}\"", escape_html(&meta.lang))
+    };
+    let pre = format!(
+        "<pre class=\"{pre_class}\"><code{code_class}>{}</code></pre>",
+        body.join("\n")
+    );
+
+    match &meta.title {
+        Some(title) => format!(
+            "<div class=\"code-block\"><div class=\"code-title\">{}</div>{pre}</div>\n",
+            escape_html(title)
+        ),
+        None => format!("{pre}\n"),
+    }
+}
+
+/// Render the blocks of a `::: code-group` as a tab strip plus one panel per block.
+///
+/// Tabs are labeled by each block's `title`, falling back to its language. The first
+/// tab is active; the client switches tabs by `data-code-group-tab` label.
+pub fn render_code_group(blocks: &[(String, CodeBlockMeta)], highlighter: &Highlighter) -> String {
+    let mut tabs = String::new();
+    let mut panels = String::new();
+
+    for (index, (code, meta)) in blocks.iter().enumerate() {
+        let label = match (&meta.title, meta.lang.is_empty()) {
+            (Some(title), _) => title.clone(),
+            (None, false) => meta.lang.clone(),
+            (None, true) => format!("Tab {}", index + 1),
+        };
+        let label = escape_html(&label);
+        let active = if index == 0 { " active" } else { "" };
+        let panel_meta = CodeBlockMeta {
+            title: None,
+            ..meta.clone()
+        };
+
+        tabs.push_str(&format!(
+            "<button type=\"button\" class=\"code-group-tab{active}\" role=\"tab\" aria-selected=\"{}\" data-code-group-tab=\"{label}\">{label}</button>",
+            index == 0
         ));
+        panels.push_str(&format!(
+            "<div class=\"code-group-panel{active}\" role=\"tabpanel\" data-code-group-panel=\"{label}\">{}</div>\n",
+            render_code_block(code, &panel_meta, highlighter).trim_end()
+        ));
+    }
 
-        remaining = &code_html[code_end + 13..]; // skip `</code></pre>`
+    format!(
+        "<div class=\"code-group\">\n<div class=\"code-group-tabs\" role=\"tablist\">{tabs}</div>\n{panels}</div>\n"
+    )
+}
+
+/// Highlight lines one at a time, closing and reopening scope spans at line
+/// boundaries so every line's HTML is balanced on its own.
+fn highlight_lines(
+    lines: &[&str],
+    syntax: &SyntaxReference,
+    ss: &SyntaxSet,
+) -> Option<Vec<String>> {
+    let mut state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut result = Vec::with_capacity(lines.len());
+
+    for line in lines {
+        let mut html = String::new();
+        for scope in stack.as_slice() {
+            html.push_str(&format!(
+                "<span class=\"{}\">",
+                scope.build_string().replace('.', " ")
+            ));
+        }
+        let open = stack.len() as isize;
+
+        // Grammars loaded with `load_defaults_newlines` expect the trailing newline
+        let source = format!("{line}\n");
+        let ops = state.parse_line(&source, ss).ok()?;
+        let (spans, delta) =
+            line_tokens_to_classed_spans(&source, &ops, ClassStyle::Spaced, &mut stack).ok()?;
+        html.push_str(&spans.replace('\n', ""));
+        for _ in 0..(open + delta).max(0) {
+            html.push_str("</span>");
+        }
+        result.push(html);
     }
 
-    result.push_str(remaining);
-    result
+    Some(result)
+}
+
+/// Selector every highlighted code block carries (see `render_code_block`).
+const HIGHLIGHT_SCOPE: &str = "pre.highlight";
+
+/// Generate the `assets/highlight.css` stylesheet from a light and a dark syntect theme.
+///
+/// Light rules apply by default. Dark rules apply when `<html>` has the `dark` class,
+/// or when the OS prefers a dark scheme and `<html>` is not marked `light`.
+/// Unknown theme names return an error listing the themes in `theme_set`.
+pub fn generate_css(theme_set: &ThemeSet, light: &str, dark: &str) -> Result<String, ConfigError> {
+    let light_css = theme_css(theme_set, light)?;
+    let dark_css = theme_css(theme_set, dark)?;
+
+    let mut css = format!("/* Syntax highlighting: light \"{light}\", dark \"{dark}\" */\n\n");
+    css.push_str(&scope_css(&light_css, HIGHLIGHT_SCOPE));
+    css.push('\n');
+    css.push_str(&scope_css(
+        &dark_css,
+        &format!("html.dark {HIGHLIGHT_SCOPE}"),
+    ));
+    css.push_str("\n@media (prefers-color-scheme: dark) {\n");
+    css.push_str(&scope_css(
+        &dark_css,
+        &format!("html:not(.light) {HIGHLIGHT_SCOPE}"),
+    ));
+    css.push_str("}\n");
+    Ok(css)
+}
+
+/// Theme names from before syntect highlighting, mapped to the closest bundled theme.
+const DEPRECATED_THEMES: &[(&str, &str)] = &[("one-dark", "base16-ocean.dark")];
+
+/// Bundled replacement for a deprecated theme name that `theme_set` does not define.
+///
+/// A project theme file with the old name (`themes/one-dark.tmTheme`) still takes precedence.
+pub fn deprecated_theme_replacement(theme_set: &ThemeSet, name: &str) -> Option<&'static str> {
+    if theme_set.themes.contains_key(name) {
+        return None;
+    }
+    DEPRECATED_THEMES
+        .iter()
+        .find(|(old, _)| *old == name)
+        .map(|(_, new)| *new)
 }
 
-/// Decode basic HTML entities that comrak encodes in code blocks.
-fn decode_html_entities(s: &str) -> String {
-    s.replace("&amp;", "&")
-        .replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&#39;", "'")
+fn theme_css(theme_set: &ThemeSet, name: &str) -> Result<String, ConfigError> {
+    let theme = theme_set
+        .themes
+        .get(name)
+        .ok_or_else(|| ConfigError::UnknownHighlightTheme {
+            name: name.to_string(),
+            available: theme_set
+                .themes
+                .keys()
+                .map(|k| k.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        })?;
+    Ok(
+        syntect::html::css_for_theme_with_class_style(theme, ClassStyle::Spaced)
+            .unwrap_or_default(),
+    )
+}
+
+/// Prefix every selector of a syntect stylesheet with `scope`.
+///
+/// Syntect emits bare scope classes (`.comment`, `.string .regexp`) that would clash
+/// with page markup, plus a `.code` rule for the block itself, which becomes `scope`.
+fn scope_css(css: &str, scope: &str) -> String {
+    let mut scoped = String::new();
+    for rule in css.split('}') {
+        let Some((selectors, body)) = rule.split_once('{') else {
+            continue;
+        };
+        let selectors = match selectors.rfind("*/") {
+            Some(end) => &selectors[end + 2..],
+            None => selectors,
+        };
+        let selectors = selectors
+            .split(',')
+            .map(str::trim)
+            .filter(|sel| !sel.is_empty())
+            .map(|sel| {
+                if sel == ".code" {
+                    scope.to_string()
+                } else {
+                    format!("{scope} {sel}")
+                }
+            })
+            .collect::<Vec<_>>();
+        if selectors.is_empty() {
+            continue;
+        }
+        scoped.push_str(&format!(
+            "{} {{\n{}\n}}\n",
+            selectors.join(", "),
+            body.trim_matches('\n')
+        ));
+    }
+    scoped
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::content::frontmatter::Frontmatter;
-    use std::path::PathBuf;
-
-    fn make_rendered(html: &str) -> RenderedContent {
-        RenderedContent {
-            path: PathBuf::from("test.md"),
-            frontmatter: Frontmatter {
-                title: "Test".to_string(),
-                ..Default::default()
-            },
-            html: html.to_string(),
-            toc: vec![],
-        }
+
+    static DEFAULTS: LazyLock<Highlighter> = LazyLock::new(Highlighter::defaults);
+
+    const DSL_SYNTAX: &str = r#"%YAML 1.2
+---
+name: Pyohwa DSL
+file_extensions: [pdsl]
+scope: source.pdsl
+contexts:
+  main:
+    - match: '\b(route|page)\b'
+      scope: keyword.control.pdsl
+"#;
+
+    const DARK_THEME: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>name</key><string>Test Dark</string>
+  <key>settings</key>
+  <array>
+    <dict>
+      <key>settings</key>
+      <dict>
+        <key>background</key><string>#101010</string>
+        <key>foreground</key><string>#eeeeee</string>
+      </dict>
+    </dict>
+    <dict>
+      <key>scope</key><string>keyword</string>
+      <key>settings</key>
+      <dict><key>foreground</key><string>#ff8800</string></dict>
+    </dict>
+  </array>
+</dict>
+</plist>
+"#;
+
+    fn render(info: &str, code: &str) -> String {
+        render_code_block(code, &CodeBlockMeta::parse(info), &DEFAULTS)
     }
 
     #[test]
     fn highlights_rust_code_block() {
-        let html = r#"<pre><code class="language-rust">fn main() {
-    println!("Hello");
-}
-</code></pre>"#;
-        let content = make_rendered(html);
-        let result = apply_syntax_highlighting(&content).unwrap();
-        assert!(result.html.contains("class=\"highlight\""));
-        assert!(result.html.contains("<span"));
+        let html = render("rust", "fn main() {\n    println!(\"Hello\");\n}\n");
+        assert!(html.contains("class=\"highlight\""));
+        assert!(html.contains("class=\"language-rust\""));
+        assert!(html.contains("<span class=\"source rust\">"));
+        assert_eq!(html.matches("<span class=\"line\">").count(), 3);
     }
 
     #[test]
-    fn preserves_non_code_html() {
-        let html = "<p>Hello <strong>world</strong></p>";
-        let content = make_rendered(html);
-        let result = apply_syntax_highlighting(&content).unwrap();
-        assert_eq!(result.html, html);
+    fn lines_are_balanced_across_multiline_scopes() {
+        let html = render("rust", "/* one\ntwo */\nlet x = 1;\n");
+        for line in html.split('\n').filter(|l| l.contains("class=\"line")) {
+            assert_eq!(
+                line.matches("<span").count(),
+                line.matches("</span>").count(),
+                "unbalanced line: {line}"
+            );
+        }
+        // The comment scope is reopened on its second line
+        assert_eq!(html.matches("comment block rust").count(), 2);
     }
 
     #[test]
     fn handles_unknown_language() {
-        let html = r#"<pre><code class="language-unknownlang">some code</code></pre>"#;
-        let content = make_rendered(html);
-        let result = apply_syntax_highlighting(&content).unwrap();
+        let html = render("unknownlang", "some code");
         // Should not crash, falls back to plain text
-        assert!(result.html.contains("some code"));
+        assert!(html.contains("some code"));
+        assert!(html.contains("class=\"language-unknownlang\""));
+    }
+
+    #[test]
+    fn escapes_html_in_code() {
+        let html = render("rust", "let x = 1 && 2 < 3;");
+        assert!(html.contains("&amp;"));
+        assert!(html.contains("&lt;"));
+        assert!(!html.contains("&amp;amp;"));
+    }
+
+    #[test]
+    fn parses_info_string_attributes() {
+        let meta = CodeBlockMeta::parse(r#"rust title="src/main.rs" {1,3-5} showLineNumbers"#);
+        assert_eq!(meta.lang, "rust");
+        assert_eq!(meta.title.as_deref(), Some("src/main.rs"));
+        assert_eq!(meta.highlight_lines, vec![1..=1, 3..=5]);
+        assert!(meta.line_numbers);
+    }
+
+    #[test]
+    fn parses_attached_ranges_and_unknown_attributes() {
+        let meta = CodeBlockMeta::parse("js{2} foo=bar title='a b.js' --weird");
+        assert_eq!(meta.lang, "js");
+        assert_eq!(meta.highlight_lines, vec![2..=2]);
+        assert_eq!(meta.title.as_deref(), Some("a b.js"));
+        assert!(!meta.line_numbers);
+
+        let meta = CodeBlockMeta::parse("{3}");
+        assert_eq!(meta.lang, "");
+        assert_eq!(meta.highlight_lines, vec![3..=3]);
+    }
+
+    #[test]
+    fn renders_title_header() {
+        let html = render(r#"rust title="<main>.rs""#, "fn main() {}");
+        assert!(html.starts_with(
+            "<div class=\"code-block\"><div class=\"code-title\">&lt;main&gt;.rs</div>"
+        ));
+        assert!(html.trim_end().ends_with("</pre></div>"));
+    }
+
+    #[test]
+    fn marks_highlighted_lines_and_line_numbers() {
+        let html = render("text {2-3} showLineNumbers", "a\nb\nc\nd");
+        assert!(html.contains("<pre class=\"highlight line-numbers\">"));
+        let lines: Vec<&str> = html.split('\n').collect();
+        assert!(!lines[0].contains("highlighted"));
+        assert!(lines[1].contains("class=\"line highlighted\""));
+        assert!(lines[2].contains("class=\"line highlighted\""));
+        assert!(!lines[3].contains("highlighted"));
     }
 
     #[test]
-    fn handles_html_entities_in_code() {
-        let html =
-            r#"<pre><code class="language-rust">let x = 1 &amp;&amp; 2 &lt; 3;</code></pre>"#;
-        let content = make_rendered(html);
-        let result = apply_syntax_highlighting(&content).unwrap();
-        assert!(!result.html.contains("&amp;amp;"));
+    fn strips_diff_markers() {
+        let html = render(
+            "js",
+            "const a = 1; // [!code --]\nconst a = 2; // [!code ++]\nconst b = 3;",
+        );
+        assert!(html.contains("<pre class=\"highlight has-diff\">"));
+        assert!(!html.contains("[!code"));
+        let lines: Vec<&str> = html.split('\n').collect();
+        assert!(lines[0].contains("class=\"line diff remove\""));
+        assert!(lines[1].contains("class=\"line diff add\""));
+        assert!(lines[2].contains("class=\"line\""));
     }
 
     #[test]
-    fn multiple_code_blocks() {
-        let html = r#"<p>Text</p>
-<pre><code class="language-rust">let x = 1;</code></pre>
-<p>More text</p>
-<pre><code class="language-python">x = 1</code></pre>"#;
-        let content = make_rendered(html);
-        let result = apply_syntax_highlighting(&content).unwrap();
-        assert_eq!(result.html.matches("class=\"highlight\"").count(), 2);
+    fn code_group_labels_fall_back_to_language() {
+        let blocks = vec![
+            (
+                "npm i".to_string(),
+                CodeBlockMeta::parse("sh title=\"npm\""),
+            ),
+            ("print()".to_string(), CodeBlockMeta::parse("python")),
+            ("plain".to_string(), CodeBlockMeta::parse("{1}")),
+        ];
+        let html = render_code_group(&blocks, &DEFAULTS);
+        assert!(html.contains("data-code-group-tab=\"npm\">npm</button>"));
+        assert!(html.contains("data-code-group-tab=\"python\">python</button>"));
+        assert!(html.contains("data-code-group-tab=\"Tab 3\">Tab 3</button>"));
+        assert_eq!(html.matches("code-group-panel active").count(), 1);
+        assert_eq!(html.matches("class=\"code-group-panel").count(), 3);
+    }
+
+    #[test]
+    fn strips_diff_markers_in_other_comment_styles() {
+        assert_eq!(
+            strip_diff_marker("x = 1  # [!code ++]"),
+            ("x = 1", Some(Diff::Add))
+        );
+        assert_eq!(
+            strip_diff_marker("<p>hi</p> <!-- [!code --] -->"),
+            ("<p>hi</p>", Some(Diff::Remove))
+        );
+        assert_eq!(strip_diff_marker("a -- b"), ("a -- b", None));
     }
 
     #[test]
     fn generate_css_returns_non_empty() {
-        let css = generate_css();
+        let css = generate_css(&DEFAULTS.themes, "InspiredGitHub", "base16-ocean.dark").unwrap();
         assert!(!css.is_empty());
         assert!(css.contains("color"));
     }
+
+    #[test]
+    fn generate_css_scopes_light_and_dark_rules() {
+        let css = generate_css(&DEFAULTS.themes, "InspiredGitHub", "base16-ocean.dark").unwrap();
+        assert!(css.contains("pre.highlight {"));
+        assert!(css.contains("pre.highlight .comment"));
+        assert!(css.contains("html.dark pre.highlight .comment"));
+        assert!(css.contains("@media (prefers-color-scheme: dark)"));
+        assert!(css.contains("html:not(.light) pre.highlight {"));
+        assert!(!css.contains("\n.comment"));
+        assert!(!css.contains(".code"));
+    }
+
+    #[test]
+    fn generate_css_rejects_unknown_theme() {
+        let err = generate_css(&DEFAULTS.themes, "InspiredGitHub", "no-such-theme").unwrap_err();
+        let message = err.to_string();
+        assert!(matches!(err, ConfigError::UnknownHighlightTheme { .. }));
+        assert!(message.contains("no-such-theme"));
+        assert!(message.contains("base16-ocean.dark"));
+        assert!(message.contains("InspiredGitHub"));
+    }
+
+    #[test]
+    fn maps_deprecated_theme_names() {
+        assert_eq!(
+            deprecated_theme_replacement(&DEFAULTS.themes, "one-dark"),
+            Some("base16-ocean.dark")
+        );
+        assert_eq!(
+            deprecated_theme_replacement(&DEFAULTS.themes, "InspiredGitHub"),
+            None
+        );
+        assert_eq!(
+            deprecated_theme_replacement(&DEFAULTS.themes, "no-such-theme"),
+            None
+        );
+    }
+
+    #[test]
+    fn load_merges_project_syntaxes_and_themes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join(SYNTAXES_DIR)).unwrap();
+        std::fs::create_dir_all(root.join(THEMES_DIR).join("extra")).unwrap();
+        std::fs::write(root.join("syntaxes/pdsl.sublime-syntax"), DSL_SYNTAX).unwrap();
+        std::fs::write(root.join("themes/extra/test-dark.tmTheme"), DARK_THEME).unwrap();
+
+        let highlighter = Highlighter::load(root).unwrap();
+
+        // Defaults are still available
+        assert!(highlighter.syntaxes.find_syntax_by_token("rust").is_some());
+        assert!(highlighter.themes.themes.contains_key("InspiredGitHub"));
+
+        let meta = CodeBlockMeta::parse("pdsl");
+        let html = render_code_block("route home", &meta, &highlighter);
+        assert!(html.contains("keyword control pdsl"));
+
+        let css = generate_css(&highlighter.themes, "InspiredGitHub", "test-dark").unwrap();
+        assert!(css.contains("#101010"));
+        assert!(css.contains("#ff8800"));
+    }
+
+    #[test]
+    fn load_without_project_dirs_uses_defaults() {
+        let tmp = tempfile::tempdir().unwrap();
+        let highlighter = Highlighter::load(tmp.path()).unwrap();
+        assert_eq!(
+            highlighter.themes.themes.len(),
+            DEFAULTS.themes.themes.len()
+        );
+    }
+
+    #[test]
+    fn load_reports_invalid_syntax_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join(SYNTAXES_DIR)).unwrap();
+        std::fs::write(root.join("syntaxes/broken.sublime-syntax"), "not: [valid").unwrap();
+
+        let err = Highlighter::load(root).err().unwrap();
+        assert!(matches!(err, BuildError::Highlight(_)));
+        assert!(err.to_string().contains("syntaxes"));
+    }
 }
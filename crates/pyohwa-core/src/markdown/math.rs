@@ -0,0 +1,146 @@
+//! TeX math rendered to MathML with KaTeX, which runs in an embedded JavaScript
+//! engine at build time. Formulas KaTeX cannot parse are reported as errors.
+
+use std::sync::LazyLock;
+
+/// KaTeX options for inline and display formulas: MathML only, no HTML layout.
+static OPTS: LazyLock<[katex::Opts; 2]> = LazyLock::new(|| {
+    [false, true].map(|display| {
+        katex::Opts::builder()
+            .output_type(katex::OutputType::Mathml)
+            .display_mode(display)
+            .throw_on_error(true)
+            .build()
+            .expect("static KaTeX options are valid")
+    })
+});
+
+/// Render a TeX formula as a `<math>` element inside KaTeX's `<span class="katex">`.
+/// `display` selects block layout, where big operators take their limits above and below.
+///
+/// The TeX source is kept as an `application/x-tex` annotation.
+pub fn latex_to_mathml(latex: &str, display: bool) -> Result<String, String> {
+    katex::render_with_opts(latex.trim(), &OPTS[usize::from(display)]).map_err(error_message)
+}
+
+/// KaTeX's own message, e.g. `Double superscript at position 4: x^a^b`.
+///
+/// The engine hands errors over as the debug form of the thrown JavaScript value,
+/// `String("ParseError: KaTeX parse error: ...")`, with the position underlined by
+/// combining characters.
+fn error_message(err: katex::Error) -> String {
+    let detail = match err {
+        katex::Error::JsExecError(detail) => detail,
+        other => return other.to_string(),
+    };
+    let detail = detail
+        .strip_prefix("String(\"")
+        .and_then(|rest| rest.strip_suffix("\")"))
+        .map(unescape_debug)
+        .unwrap_or(detail);
+    let message = detail
+        .strip_prefix("ParseError: KaTeX parse error: ")
+        .unwrap_or(&detail);
+    message.chars().filter(|&c| c != '\u{332}').collect()
+}
+
+/// Undo the escapes of Rust's `{:?}` string formatting.
+fn unescape_debug(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('u') => {
+                let code: String = chars
+                    .by_ref()
+                    .skip_while(|&c| c == '{')
+                    .take_while(|&c| c != '}')
+                    .collect();
+                if let Some(c) = u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+                    result.push(c);
+                }
+            }
+            Some(c) => result.push(c),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The formula's MathML without the wrappers and annotation.
+    fn body(latex: &str) -> String {
+        let html = latex_to_mathml(latex, false).unwrap();
+        let start = html.find("<semantics><mrow>").unwrap() + "<semantics><mrow>".len();
+        let end = html.find("</mrow><annotation").unwrap();
+        html[start..end].to_string()
+    }
+
+    #[test]
+    fn converts_scripts_fractions_and_symbols() {
+        assert_eq!(
+            body("x^2 + y_{i,j}"),
+            "<msup><mi>x</mi><mn>2</mn></msup><mo>+</mo><msub><mi>y</mi><mrow><mi>i</mi><mo separator=\"true\">,</mo><mi>j</mi></mrow></msub>"
+        );
+        assert_eq!(
+            body(r"\frac{a}{b} \leq \sqrt[3]{\alpha}"),
+            "<mfrac><mi>a</mi><mi>b</mi></mfrac><mo>≤</mo><mroot><mi>α</mi><mn>3</mn></mroot>"
+        );
+        assert_eq!(body("a < b"), "<mi>a</mi><mo>&lt;</mo><mi>b</mi>");
+    }
+
+    #[test]
+    fn display_mode_marks_the_math_element() {
+        let inline = latex_to_mathml("x", false).unwrap();
+        assert!(inline.starts_with(
+            "<span class=\"katex\"><math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics>"
+        ));
+        let display = latex_to_mathml(r"\lim_{x \to 0} x", true).unwrap();
+        assert!(display.starts_with(
+            "<span class=\"katex\"><math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\">"
+        ));
+        assert!(display.contains("<munder>"));
+    }
+
+    #[test]
+    fn keeps_source_as_annotation() {
+        let html = latex_to_mathml(" a<b\n", false).unwrap();
+        assert!(html.ends_with(
+            "<annotation encoding=\"application/x-tex\">a&lt;b</annotation></semantics></math></span>"
+        ));
+    }
+
+    #[test]
+    fn reports_invalid_formulas() {
+        assert_eq!(
+            latex_to_mathml("x^a^b", false).unwrap_err(),
+            "Double superscript at position 4: x^a^b"
+        );
+        assert_eq!(
+            latex_to_mathml(r"\foo x", false).unwrap_err(),
+            r"Undefined control sequence: \foo at position 1: \foo x"
+        );
+        assert_eq!(
+            latex_to_mathml(r"\frac{a}{b", false).unwrap_err(),
+            r"Unexpected end of input in a macro argument, expected '}' at end of input: \frac{a}{b"
+        );
+    }
+
+    #[test]
+    fn deep_nesting_fails_instead_of_overflowing() {
+        let nested = format!("{}x{}", "{".repeat(100_000), "}".repeat(100_000));
+        assert!(latex_to_mathml(&nested, false)
+            .unwrap_err()
+            .contains("stack overflow"));
+    }
+}
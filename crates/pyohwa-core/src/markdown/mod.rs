@@ -1,2 +1,5 @@
+pub mod admonition;
+pub mod container;
 pub mod highlight;
+pub mod math;
 pub mod parser;
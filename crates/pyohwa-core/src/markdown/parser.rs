@@ -1,15 +1,50 @@
-use comrak::nodes::NodeValue;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use comrak::nodes::{AstNode, NodeHtmlBlock, NodeValue};
 use comrak::{format_html, parse_document, Arena, Options};
+use regex::Regex;
 
 use crate::content::page::{ParsedContent, RenderedContent, TocItem};
-use crate::error::BuildError;
+use crate::error::{BuildError, ContentError};
+use crate::markdown::highlight::{self, CodeBlockMeta, Highlighter};
+use crate::markdown::{admonition, container, math};
+use crate::render::template::escape_html;
+
+/// `## Heading {#custom-id}` — an explicit id at the end of a heading.
+static CUSTOM_ID_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*\{#([^\s{}]+)\}\s*$").unwrap());
+/// Opening heading tags as comrak renders them.
+static HEADING_TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<h([1-6])>").unwrap());
+
+/// Build-wide settings shared by every page's Markdown conversion.
+pub struct MarkdownContext<'a> {
+    pub highlighter: &'a Highlighter,
+    /// `site.language`, used for admonition labels.
+    pub language: &'a str,
+    /// `[markdown] math`: render `$...$`, `$$...$$` and ```` ```math ```` blocks as MathML.
+    pub math: bool,
+}
 
 /// Parse Markdown body to HTML and extract TOC headings.
 ///
-/// Uses comrak with CommonMark + GFM extensions (tables, strikethrough, tasklist, autolink).
-/// Headings are collected into a flat TocItem list with slugified ids.
-pub fn parse_markdown(content: &ParsedContent) -> Result<RenderedContent, BuildError> {
-    let (html, toc) = markdown_to_html_with_toc(&content.body);
+/// Uses comrak with CommonMark + GFM extensions (tables, strikethrough, tasklist, autolink,
+/// alerts). Headings are collected into a flat TocItem list with GitHub-style ids,
+/// unique within the page; `{#id}` after a heading sets its id explicitly.
+/// Fenced code blocks with an info string are syntax-highlighted, and `:::` containers
+/// and `> [!NOTE]` alerts become admonitions. With `ctx.math`, TeX formulas are
+/// rendered to MathML; an invalid formula fails with the page's path.
+pub fn parse_markdown(
+    content: &ParsedContent,
+    ctx: &MarkdownContext,
+) -> Result<RenderedContent, BuildError> {
+    let (html, toc) =
+        markdown_to_html_with_toc(&content.body, ctx).map_err(|err| ContentError::Math {
+            path: content.path.clone(),
+            line: content.body_line + err.line - 1,
+            column: err.column,
+            reason: err.reason,
+        })?;
 
     Ok(RenderedContent {
         path: content.path.clone(),
@@ -19,15 +54,41 @@ pub fn parse_markdown(content: &ParsedContent) -> Result<RenderedContent, BuildE
     })
 }
 
+/// An invalid formula: its 1-based line and column in the Markdown, and what is wrong.
+struct MathError {
+    line: usize,
+    column: usize,
+    reason: String,
+}
+
 /// Convert markdown string to HTML and extract TOC items.
-fn markdown_to_html_with_toc(markdown: &str) -> (String, Vec<TocItem>) {
+///
+/// Fails only on an invalid math formula.
+fn markdown_to_html_with_toc(
+    markdown: &str,
+    ctx: &MarkdownContext,
+) -> Result<(String, Vec<TocItem>), MathError> {
     let arena = Arena::new();
-    let options = comrak_options();
+    let options = comrak_options(ctx.math);
 
-    let root = parse_document(&arena, markdown, &options);
+    let source = markdown;
+    let markdown = container::isolate_markers(markdown);
+    let root = parse_document(&arena, &markdown, &options);
 
-    let mut toc = Vec::new();
-    collect_toc(root, &mut toc);
+    let (toc, ids) = collect_toc(root);
+    container::build_containers(root, ctx);
+    admonition::convert_alerts(&arena, root, ctx.language);
+    if ctx.math {
+        render_math(root).map_err(|(formula, reason)| {
+            let (line, column) = formula_position(source, &formula);
+            MathError {
+                line,
+                column,
+                reason,
+            }
+        })?;
+    }
+    render_code_blocks(root, ctx.highlighter);
 
     // Insert id attributes into headings in the AST is not straightforward with comrak,
     // so we render HTML first, then post-process heading tags to add ids.
@@ -35,50 +96,167 @@ fn markdown_to_html_with_toc(markdown: &str) -> (String, Vec<TocItem>) {
     format_html(root, &options, &mut html_buf).unwrap_or_default();
     let html = String::from_utf8_lossy(&html_buf).to_string();
 
-    let html = inject_heading_ids(&html, &toc);
+    let html = inject_heading_ids(&html, &ids);
 
-    (html, toc)
+    Ok((html, toc))
 }
 
-fn comrak_options() -> Options<'static> {
+fn comrak_options(math: bool) -> Options<'static> {
     let mut options = Options::default();
     options.extension.table = true;
     options.extension.strikethrough = true;
     options.extension.tasklist = true;
     options.extension.autolink = true;
+    options.extension.alerts = true;
+    options.extension.math_dollars = math;
+    options.extension.math_code = math;
     options.render.unsafe_ = true;
     options
 }
 
+/// Replace math spans and ```` ```math ```` blocks with MathML.
+///
+/// Fails with the first invalid formula and the reason.
+fn render_math<'a>(root: &'a AstNode<'a>) -> Result<(), (String, String)> {
+    for node in root.descendants() {
+        let mut data = node.data.borrow_mut();
+        let (literal, display, block) = match data.value {
+            NodeValue::Math(ref math) => (&math.literal, math.display_math, false),
+            NodeValue::CodeBlock(ref code) if code.fenced && code.info.trim() == "math" => {
+                (&code.literal, true, true)
+            }
+            _ => continue,
+        };
+
+        let mathml =
+            math::latex_to_mathml(literal, display).map_err(|reason| (literal.clone(), reason))?;
+        data.value = if block {
+            NodeValue::HtmlBlock(NodeHtmlBlock {
+                block_type: 0,
+                literal: format!("{mathml}\n"),
+            })
+        } else {
+            NodeValue::HtmlInline(mathml)
+        };
+    }
+    Ok(())
+}
+
+/// Where `formula` starts in `markdown`: the first line holding its first line of text.
+///
+/// Comrak's source positions refer to the text after `isolate_markers`, which may
+/// add blank lines, so the formula is looked up in the original text instead.
+/// Formulas fail in document order, so the first match is the failing one.
+fn formula_position(markdown: &str, formula: &str) -> (usize, usize) {
+    let start = formula
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    markdown
+        .lines()
+        .enumerate()
+        .find_map(|(index, line)| {
+            let byte = line.find(start)?;
+            Some((index + 1, line[..byte].chars().count() + 1))
+        })
+        .unwrap_or((1, 1))
+}
+
+/// Replace fenced code blocks that carry an info string with pre-rendered,
+/// highlighted HTML blocks. Bare fences and indented blocks are left to comrak.
+fn render_code_blocks<'a>(root: &'a AstNode<'a>, highlighter: &Highlighter) {
+    for node in root.descendants() {
+        let mut data = node.data.borrow_mut();
+        let NodeValue::CodeBlock(ref block) = data.value else {
+            continue;
+        };
+        if !block.fenced || block.info.trim().is_empty() {
+            continue;
+        }
+
+        let meta = CodeBlockMeta::parse(&block.info);
+        let literal = highlight::render_code_block(&block.literal, &meta, highlighter);
+        data.value = NodeValue::HtmlBlock(NodeHtmlBlock {
+            block_type: 0,
+            literal,
+        });
+    }
+}
+
 /// Walk the AST to extract heading nodes and build TocItem list.
-fn collect_toc<'a>(node: &'a comrak::nodes::AstNode<'a>, toc: &mut Vec<TocItem>) {
-    let data = node.data.borrow();
-    if let NodeValue::Heading(ref heading) = data.value {
+///
+/// Also returns the id of every heading in document order, `None` for headings
+/// without text. Explicit `{#id}` suffixes are removed from the heading and
+/// reserved before any slug is generated, so generated ids never collide with them.
+fn collect_toc<'a>(root: &'a AstNode<'a>) -> (Vec<TocItem>, Vec<Option<String>>) {
+    let headings: Vec<_> = root
+        .descendants()
+        .filter(|node| matches!(node.data.borrow().value, NodeValue::Heading(_)))
+        .collect();
+    let custom_ids: Vec<_> = headings.iter().map(|node| take_custom_id(node)).collect();
+
+    let mut slugger = Slugger::default();
+    for id in custom_ids.iter().flatten() {
+        slugger.seen.insert(id.clone());
+    }
+
+    let mut toc = Vec::new();
+    let mut ids = Vec::new();
+    for (node, custom_id) in headings.into_iter().zip(custom_ids) {
+        let level = match node.data.borrow().value {
+            NodeValue::Heading(ref heading) => heading.level,
+            _ => continue,
+        };
         let text = collect_text(node);
-        if !text.is_empty() {
-            let id = slugify(&text);
-            toc.push(TocItem {
-                id,
-                text,
-                level: heading.level,
-            });
+        if text.is_empty() && custom_id.is_none() {
+            ids.push(None);
+            continue;
         }
-    }
-    drop(data);
 
-    for child in node.children() {
-        collect_toc(child, toc);
+        let id = custom_id.unwrap_or_else(|| slugger.slug(&text));
+        toc.push(TocItem {
+            id: id.clone(),
+            text,
+            level,
+        });
+        ids.push(Some(id));
     }
+
+    (toc, ids)
+}
+
+/// Strip a trailing `{#id}` from a heading's last text node and return the id.
+fn take_custom_id<'a>(heading: &'a AstNode<'a>) -> Option<String> {
+    let last = heading.last_child()?;
+    let mut data = last.data.borrow_mut();
+    let NodeValue::Text(ref mut text) = data.value else {
+        return None;
+    };
+    let caps = CUSTOM_ID_RE.captures(text)?;
+    let id = caps[1].to_string();
+    let start = caps.get(0).map_or(text.len(), |m| m.start());
+    text.truncate(start);
+    Some(id)
+}
+
+/// The ids `parse_markdown` gives the headings of `markdown`, in document order,
+/// without rendering anything.
+pub(crate) fn heading_ids(markdown: &str, math: bool) -> Vec<String> {
+    let arena = Arena::new();
+    let markdown = container::isolate_markers(markdown);
+    let root = parse_document(&arena, &markdown, &comrak_options(math));
+    collect_toc(root).1.into_iter().flatten().collect()
 }
 
 /// Recursively collect all text content from a node and its children.
-fn collect_text<'a>(node: &'a comrak::nodes::AstNode<'a>) -> String {
+pub(crate) fn collect_text<'a>(node: &'a AstNode<'a>) -> String {
     let mut text = String::new();
     collect_text_inner(node, &mut text);
     text
 }
 
-fn collect_text_inner<'a>(node: &'a comrak::nodes::AstNode<'a>, buf: &mut String) {
+fn collect_text_inner<'a>(node: &'a AstNode<'a>, buf: &mut String) {
     let data = node.data.borrow();
     if let NodeValue::Text(ref s) = data.value {
         buf.push_str(s);
@@ -92,47 +270,55 @@ fn collect_text_inner<'a>(node: &'a comrak::nodes::AstNode<'a>, buf: &mut String
     }
 }
 
-/// Convert text to a URL-safe slug for heading IDs.
+/// Hands out heading ids unique within one page, GitHub-style: the second
+/// `## Example` becomes `example-1`, the third `example-2`.
+#[derive(Default)]
+struct Slugger {
+    seen: HashSet<String>,
+}
+
+impl Slugger {
+    fn slug(&mut self, text: &str) -> String {
+        let base = slugify(text);
+        let mut slug = base.clone();
+        let mut suffix = 0;
+        while self.seen.contains(&slug) {
+            suffix += 1;
+            slug = format!("{base}-{suffix}");
+        }
+        self.seen.insert(slug.clone());
+        slug
+    }
+}
+
+/// Convert heading text to a slug the way GitHub does: lowercase, drop
+/// punctuation, turn each space into `-`. Letters and digits of any script
+/// (Hangul, kana, accented Latin, ...) are kept as they are.
 fn slugify(text: &str) -> String {
-    text.to_lowercase()
+    text.trim()
+        .to_lowercase()
         .chars()
-        .map(|c| {
-            if c.is_alphanumeric() {
-                c
-            } else if c == ' ' || c == '-' || c == '_' {
-                '-'
-            } else {
-                '\0'
-            }
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
         })
-        .filter(|&c| c != '\0')
-        .collect::<String>()
-        .split('-')
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<_>>()
-        .join("-")
+        .collect()
 }
 
 /// Post-process HTML to inject id attributes into heading tags.
 ///
-/// Matches `<h1>`, `<h2>`, etc. and adds `id="slug"` based on the corresponding TocItem.
-fn inject_heading_ids(html: &str, toc: &[TocItem]) -> String {
-    let mut result = html.to_string();
-    for item in toc {
-        // Find `<hN>` and replace with `<hN id="slug">`
-        let tag = format!("<h{}>", item.level);
-        let tag_with_id = format!("<h{} id=\"{}\">", item.level, item.id);
-        // Only replace the first occurrence for each TOC item
-        if let Some(pos) = result.find(&tag) {
-            result = format!(
-                "{}{}{}",
-                &result[..pos],
-                tag_with_id,
-                &result[pos + tag.len()..]
-            );
-        }
-    }
-    result
+/// comrak renders headings in document order, so the n-th `<hN>` tag gets the
+/// n-th id from `collect_toc`.
+fn inject_heading_ids(html: &str, ids: &[Option<String>]) -> String {
+    let mut ids = ids.iter();
+    HEADING_TAG_RE
+        .replace_all(html, |caps: &regex::Captures| match ids.next() {
+            Some(Some(id)) => format!("<h{} id=\"{}\">", &caps[1], escape_html(id)),
+            _ => caps[0].to_string(),
+        })
+        .into_owned()
 }
 
 #[cfg(test)]
@@ -140,6 +326,17 @@ mod tests {
     use super::*;
     use crate::content::frontmatter::Frontmatter;
     use std::path::PathBuf;
+    use std::sync::LazyLock;
+
+    static DEFAULTS: LazyLock<Highlighter> = LazyLock::new(Highlighter::defaults);
+
+    fn ctx() -> MarkdownContext<'static> {
+        MarkdownContext {
+            highlighter: &DEFAULTS,
+            language: "en",
+            math: false,
+        }
+    }
 
     fn make_parsed(body: &str) -> ParsedContent {
         ParsedContent {
@@ -149,13 +346,14 @@ mod tests {
                 ..Default::default()
             },
             body: body.to_string(),
+            body_line: 1,
         }
     }
 
     #[test]
     fn basic_markdown_conversion() {
         let content = make_parsed("Hello **world**!");
-        let result = parse_markdown(&content).unwrap();
+        let result = parse_markdown(&content, &ctx()).unwrap();
         assert!(result.html.contains("<strong>world</strong>"));
         assert!(result.html.contains("<p>"));
     }
@@ -163,7 +361,7 @@ mod tests {
     #[test]
     fn gfm_table_support() {
         let content = make_parsed("| Foo | Bar |\n|-----|-----|\n| Baz | Bim |");
-        let result = parse_markdown(&content).unwrap();
+        let result = parse_markdown(&content, &ctx()).unwrap();
         assert!(result.html.contains("<table>"));
         assert!(result.html.contains("<td>Baz</td>"));
     }
@@ -171,7 +369,7 @@ mod tests {
     #[test]
     fn gfm_tasklist_support() {
         let content = make_parsed("- [x] Done\n- [ ] Todo");
-        let result = parse_markdown(&content).unwrap();
+        let result = parse_markdown(&content, &ctx()).unwrap();
         assert!(result.html.contains("checked"));
         assert!(result.html.contains("type=\"checkbox\""));
     }
@@ -179,7 +377,7 @@ mod tests {
     #[test]
     fn gfm_strikethrough_support() {
         let content = make_parsed("~~deleted~~");
-        let result = parse_markdown(&content).unwrap();
+        let result = parse_markdown(&content, &ctx()).unwrap();
         assert!(result.html.contains("<del>deleted</del>"));
     }
 
@@ -188,7 +386,7 @@ mod tests {
         let content = make_parsed(
             "# Introduction\n\nSome text.\n\n## Getting Started\n\nMore text.\n\n### Sub Section",
         );
-        let result = parse_markdown(&content).unwrap();
+        let result = parse_markdown(&content, &ctx()).unwrap();
         assert_eq!(result.toc.len(), 3);
         assert_eq!(result.toc[0].text, "Introduction");
         assert_eq!(result.toc[0].level, 1);
@@ -204,30 +402,252 @@ mod tests {
     #[test]
     fn heading_ids_injected_into_html() {
         let content = make_parsed("## Hello World");
-        let result = parse_markdown(&content).unwrap();
+        let result = parse_markdown(&content, &ctx()).unwrap();
         assert!(result.html.contains("id=\"hello-world\""));
     }
 
     #[test]
     fn empty_markdown_produces_empty_html() {
         let content = make_parsed("");
-        let result = parse_markdown(&content).unwrap();
+        let result = parse_markdown(&content, &ctx()).unwrap();
         assert!(result.toc.is_empty());
     }
 
     #[test]
     fn code_block_preserved() {
         let content = make_parsed("```rust\nfn main() {}\n```");
-        let result = parse_markdown(&content).unwrap();
+        let result = parse_markdown(&content, &ctx()).unwrap();
         assert!(result.html.contains("<code"));
-        assert!(result.html.contains("fn main()"));
+        assert!(result.html.contains(">fn</span> "));
+        assert!(result.html.contains("main"));
+    }
+
+    #[test]
+    fn fenced_code_block_is_highlighted() {
+        let content =
+            make_parsed("Text\n\n```rust\nlet x = 1;\n```\n\nMore\n\n```python\nx = 1\n```");
+        let result = parse_markdown(&content, &ctx()).unwrap();
+        assert_eq!(result.html.matches("<pre class=\"highlight\">").count(), 2);
+        assert!(result.html.contains("<span class=\"source rust\">"));
+        assert!(result.html.contains("<p>More</p>"));
+    }
+
+    #[test]
+    fn code_block_metadata_from_info_string() {
+        let content = make_parsed(
+            "```rust title=\"main.rs\" {2} showLineNumbers\nfn main() {\n    run(); // [!code ++]\n}\n```",
+        );
+        let result = parse_markdown(&content, &ctx()).unwrap();
+        assert!(result
+            .html
+            .contains("<div class=\"code-title\">main.rs</div>"));
+        assert!(result
+            .html
+            .contains("<pre class=\"highlight line-numbers has-diff\">"));
+        assert!(result.html.contains("class=\"line highlighted diff add\""));
+        assert!(!result.html.contains("title="));
+        assert!(!result.html.contains("[!code"));
+    }
+
+    #[test]
+    fn code_block_in_list_is_highlighted() {
+        let content = make_parsed("- item\n\n  ```rust\n  let x = 1;\n  ```");
+        let result = parse_markdown(&content, &ctx()).unwrap();
+        assert!(result.html.contains("<li>"));
+        assert!(result.html.contains("<pre class=\"highlight\">"));
+    }
+
+    #[test]
+    fn bare_fence_is_left_unhighlighted() {
+        let content = make_parsed("```\n<b>raw</b>\n```");
+        let result = parse_markdown(&content, &ctx()).unwrap();
+        assert!(result.html.contains("<pre><code>&lt;b&gt;raw&lt;/b&gt;"));
+    }
+
+    #[test]
+    fn code_group_renders_tabs_from_titles() {
+        let content = make_parsed(
+            "::: code-group\n```sh title=\"cargo\"\ncargo install pyohwa\n```\n```sh title=\"npm\"\nnpm i -g pyohwa\n```\n:::\n\nAfter",
+        );
+        let result = parse_markdown(&content, &ctx()).unwrap();
+        assert!(result.html.contains("<div class=\"code-group\">"));
+        assert!(result.html.contains(
+            "<button type=\"button\" class=\"code-group-tab active\" role=\"tab\" aria-selected=\"true\" data-code-group-tab=\"cargo\">cargo</button>"
+        ));
+        assert!(result
+            .html
+            .contains("data-code-group-tab=\"npm\">npm</button>"));
+        assert_eq!(result.html.matches("<pre class=\"highlight\">").count(), 2);
+        assert!(!result.html.contains("code-title"));
+        assert!(!result.html.contains(":::"));
+        assert!(result.html.contains("<p>After</p>"));
+    }
+
+    #[test]
+    fn unknown_container_is_left_as_text() {
+        let content = make_parsed("::: nope\nbody\n:::");
+        let result = parse_markdown(&content, &ctx()).unwrap();
+        // Not isolated either: the lines stay one paragraph, as without containers
+        assert!(result.html.contains("<p>::: nope\nbody</p>"));
+    }
+
+    #[test]
+    fn admonition_containers_render_with_default_and_custom_titles() {
+        let content = make_parsed(
+            "::: tip\nUse **bold**.\n:::\n\n::: danger Do not do this\nBad.\n:::\n\n::: details\nHidden\n:::",
+        );
+        let result = parse_markdown(&content, &ctx()).unwrap();
+        assert!(result.html.contains(
+            "<aside class=\"admonition admonition-tip\" role=\"note\">\n<p class=\"admonition-title\">Tip</p>\n<p>Use <strong>bold</strong>.</p>\n</aside>"
+        ));
+        assert!(result
+            .html
+            .contains("<p class=\"admonition-title\">Do not do this</p>"));
+        assert!(result.html.contains(
+            "<summary class=\"admonition-title\">Details</summary>\n<p>Hidden</p>\n</details>"
+        ));
+        assert!(!result.html.contains(":::"));
+    }
+
+    #[test]
+    fn nested_containers_pair_by_colon_count() {
+        let content = make_parsed(":::: details More\n::: warning\nInner\n:::\nOuter\n::::");
+        let result = parse_markdown(&content, &ctx()).unwrap();
+        let details = result.html.find("<details").unwrap();
+        let aside = result.html.find("<aside").unwrap();
+        let aside_end = result.html.find("</aside>").unwrap();
+        let outer = result.html.find("<p>Outer</p>").unwrap();
+        let details_end = result.html.find("</details>").unwrap();
+        assert!(details < aside && aside < aside_end && aside_end < outer && outer < details_end);
+    }
+
+    #[test]
+    fn github_alerts_render_as_admonitions() {
+        let content = make_parsed("> [!WARNING]\n> Mind the gap.\n\n> [!NOTE] Custom\n> Text");
+        let result = parse_markdown(&content, &ctx()).unwrap();
+        assert!(result.html.contains(
+            "<aside class=\"admonition admonition-warning\" role=\"note\">\n<p class=\"admonition-title\">Warning</p>\n<p>Mind the gap.</p>\n</aside>"
+        ));
+        assert!(result
+            .html
+            .contains("<p class=\"admonition-title\">Custom</p>"));
+        assert!(!result.html.contains("markdown-alert"));
+        assert!(!result.html.contains("<blockquote>"));
+    }
+
+    #[test]
+    fn admonition_labels_are_localized() {
+        let content = make_parsed("::: warning\n조심\n:::\n\n> [!TIP]\n> 팁");
+        let ko = MarkdownContext {
+            highlighter: &DEFAULTS,
+            language: "ko",
+            math: false,
+        };
+        let result = parse_markdown(&content, &ko).unwrap();
+        assert!(result
+            .html
+            .contains("<p class=\"admonition-title\">경고</p>"));
+        assert!(result.html.contains("<p class=\"admonition-title\">팁</p>"));
+    }
+
+    #[test]
+    fn math_renders_to_mathml_when_enabled() {
+        let content = make_parsed(
+            "Inline $x^2$ and\n\n$$\n\\frac{a}{b}\n$$\n\n```math\ny = mx\n```\n\nCost: $5",
+        );
+        let math = MarkdownContext {
+            math: true,
+            ..ctx()
+        };
+        let result = parse_markdown(&content, &math).unwrap();
+        assert!(result.html.contains(
+            "Inline <span class=\"katex\"><math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mrow><msup><mi>x</mi><mn>2</mn></msup></mrow>"
+        ));
+        assert!(result
+            .html
+            .contains("display=\"block\"><semantics><mrow><mfrac>"));
+        assert!(result
+            .html
+            .contains("<mi>y</mi><mo>=</mo><mi>m</mi><mi>x</mi>"));
+        assert!(!result.html.contains("language-math"));
+        assert!(result.html.contains("Cost: $5"));
+
+        let plain = parse_markdown(&content, &ctx()).unwrap();
+        assert!(plain.html.contains("Inline $x^2$"));
+    }
+
+    #[test]
+    fn invalid_math_reports_page_path_and_line() {
+        let mut content = make_parsed(
+            "# Title\n\n::: tip\nFine: $x$\n:::\n\nBroken: $x^a^b$\n\n$$\n\\frac{a\n$$\n",
+        );
+        content.body_line = 4;
+        let math = MarkdownContext {
+            math: true,
+            ..ctx()
+        };
+        let err = parse_markdown(&content, &math).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "content error: invalid math in test.md:10:10: Double superscript at position 4: x^a^b"
+        );
+
+        let content = make_parsed("Text\n\n```math\n\n\\frac{a\n```\n");
+        let err = parse_markdown(&content, &math).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("content error: invalid math in test.md:5:1: "));
     }
 
     #[test]
     fn slugify_handles_special_chars() {
         assert_eq!(slugify("Hello World!"), "hello-world");
-        assert_eq!(slugify("C++ & Rust"), "c-rust");
-        assert_eq!(slugify("  multiple   spaces  "), "multiple-spaces");
+        assert_eq!(slugify("C++ & Rust"), "c--rust");
+        assert_eq!(slugify("  multiple   spaces  "), "multiple---spaces");
         assert_eq!(slugify("already-slugged"), "already-slugged");
+        assert_eq!(slugify("snake_case"), "snake_case");
+    }
+
+    #[test]
+    fn slugify_keeps_unicode_letters() {
+        assert_eq!(slugify("설치 방법"), "설치-방법");
+        assert_eq!(slugify("Café — Menü"), "café--menü");
+        assert_eq!(slugify("はじめに"), "はじめに");
+    }
+
+    #[test]
+    fn duplicate_headings_get_numbered_ids() {
+        let content = make_parsed("## Example\n\n## Example\n\n### Example\n\n## Example 1");
+        let result = parse_markdown(&content, &ctx()).unwrap();
+        let ids: Vec<_> = result.toc.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["example", "example-1", "example-2", "example-1-1"]
+        );
+        assert!(result.html.contains("<h2 id=\"example\">"));
+        assert!(result.html.contains("<h2 id=\"example-1\">"));
+        assert!(result.html.contains("<h3 id=\"example-2\">"));
+        assert!(result.html.contains("<h2 id=\"example-1-1\">"));
+    }
+
+    #[test]
+    fn custom_heading_id_overrides_slug() {
+        let content = make_parsed("## Setup\n\n## Installation {#setup}\n\n## 설치 {#install}");
+        let result = parse_markdown(&content, &ctx()).unwrap();
+        let ids: Vec<_> = result.toc.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, vec!["setup-1", "setup", "install"]);
+        assert_eq!(result.toc[1].text, "Installation");
+        assert!(result.html.contains("<h2 id=\"setup\">Installation</h2>"));
+        assert!(result.html.contains("<h2 id=\"install\">설치</h2>"));
+        assert!(!result.html.contains("{#"));
+    }
+
+    #[test]
+    fn empty_heading_does_not_shift_ids() {
+        let content = make_parsed("##\n\n## Next");
+        let result = parse_markdown(&content, &ctx()).unwrap();
+        assert_eq!(result.toc.len(), 1);
+        assert!(result.html.contains("<h2></h2>"));
+        assert!(result.html.contains("<h2 id=\"next\">Next</h2>"));
     }
 }
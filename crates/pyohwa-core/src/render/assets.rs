@@ -1,6 +1,8 @@
 use std::fs;
 use std::path::Path;
 
+use walkdir::WalkDir;
+
 use crate::error::BuildError;
 
 /// Recursively copy static assets from static_dir to output_dir.
@@ -14,6 +16,32 @@ pub fn copy_static_assets(static_dir: &Path, output_dir: &Path) -> Result<(), Bu
     copy_dir_recursive(static_dir, output_dir)
 }
 
+/// Copy the files next to the pages in `content_dir` (images, downloads) to the
+/// same relative paths in output_dir. Markdown files are pages or partials and
+/// are skipped.
+pub fn copy_content_assets(content_dir: &Path, output_dir: &Path) -> Result<(), BuildError> {
+    for entry in WalkDir::new(content_dir)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) == Some("md") {
+            continue;
+        }
+        let Ok(relative) = path.strip_prefix(content_dir) else {
+            continue;
+        };
+        let dst = output_dir.join(relative);
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(path, dst)?;
+    }
+    Ok(())
+}
+
 fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), BuildError> {
     if !dst.exists() {
         fs::create_dir_all(dst)?;
@@ -69,6 +97,21 @@ mod tests {
         let _ = fs::remove_dir_all(&tmp);
     }
 
+    #[test]
+    fn test_copy_content_assets() {
+        let tmp = tempfile::tempdir().unwrap();
+        let content = tmp.path().join("content");
+        let dst = tmp.path().join("dist");
+        fs::create_dir_all(content.join("guide")).unwrap();
+        fs::write(content.join("guide/intro.md"), "# Intro").unwrap();
+        fs::write(content.join("guide/diagram.png"), "png data").unwrap();
+
+        copy_content_assets(&content, &dst).unwrap();
+
+        assert!(dst.join("guide/diagram.png").exists());
+        assert!(!dst.join("guide/intro.md").exists());
+    }
+
     #[test]
     fn test_copy_nonexistent_static_dir() {
         let result = copy_static_assets(
@@ -3,3 +3,27 @@ pub const ELM_JS: &str = include_str!(concat!(env!("OUT_DIR"), "/elm.min.js"));
 
 /// Theme CSS — built by build.rs from themes/default/ (Tailwind v4 in Phase 2+)
 pub const THEME_CSS: &str = include_str!(concat!(env!("OUT_DIR"), "/theme.css"));
+
+/// Default page templates, overridable from `themes/<name>/templates/`
+pub const TEMPLATES: &[(&str, &str)] = &[
+    ("base.html", include_str!("templates/base.html")),
+    ("doc.html", include_str!("templates/doc.html")),
+    ("home.html", include_str!("templates/home.html")),
+    ("page.html", include_str!("templates/page.html")),
+    (
+        "partials/navbar.html",
+        include_str!("templates/partials/navbar.html"),
+    ),
+    (
+        "partials/sidebar.html",
+        include_str!("templates/partials/sidebar.html"),
+    ),
+    (
+        "partials/toc.html",
+        include_str!("templates/partials/toc.html"),
+    ),
+    (
+        "partials/footer.html",
+        include_str!("templates/partials/footer.html"),
+    ),
+];
@@ -0,0 +1,336 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use minijinja::{AutoEscape, Environment, ErrorKind, Value};
+
+use crate::content::frontmatter::Layout;
+use crate::content::page::{Page, RawContent};
+use crate::error::{BuildError, RenderError};
+use crate::render::embedded;
+use crate::render::template::escape_html;
+
+/// Page templates rendered with minijinja.
+///
+/// The embedded defaults (`base.html`, `doc.html`, `home.html`, `page.html` and
+/// the `partials/` they include) are loaded first; any file of the same name in
+/// the project's `themes/<theme.name>/templates/` replaces its default, and other
+/// `.html` files there become additional templates: `<name>.html` is the layout
+/// for pages with `layout: <name>` in their frontmatter.
+#[derive(Debug)]
+pub struct Templates {
+    env: Environment<'static>,
+    /// Where each template was loaded from, for error messages
+    sources: BTreeMap<String, PathBuf>,
+}
+
+impl Templates {
+    /// The embedded default templates only.
+    pub fn builtin() -> Self {
+        let mut env = Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.set_keep_trailing_newline(true);
+        // Same escaping as the rest of the renderer: minijinja's default would
+        // also escape `/` and `'`, turning every href into entities
+        env.set_formatter(|out, state, value| {
+            if matches!(state.auto_escape(), AutoEscape::Html)
+                && !value.is_safe()
+                && !value.is_undefined()
+                && !value.is_none()
+            {
+                out.write_str(&escape_html(&value.to_string()))?;
+                return Ok(());
+            }
+            minijinja::escape_formatter(out, state, value)
+        });
+
+        let mut templates = Self {
+            env,
+            sources: BTreeMap::new(),
+        };
+        for (name, source) in embedded::TEMPLATES {
+            templates
+                .add(
+                    name,
+                    source.to_string(),
+                    PathBuf::from(format!("<built-in>/{name}")),
+                )
+                .expect("embedded templates are valid");
+        }
+        templates
+    }
+
+    /// The embedded defaults overridden by the `.html` files in `dir`, if it exists.
+    /// Error messages show template paths relative to `project_root`.
+    pub fn load(project_root: &Path, dir: &Path) -> Result<Self, BuildError> {
+        let mut templates = Self::builtin();
+        if !dir.is_dir() {
+            return Ok(templates);
+        }
+
+        for entry in walkdir::WalkDir::new(dir).sort_by_file_name() {
+            let entry = entry.map_err(std::io::Error::from)?;
+            let path = entry.path();
+            if !entry.file_type().is_file() || path.extension().is_none_or(|ext| ext != "html") {
+                continue;
+            }
+            let name = path
+                .strip_prefix(dir)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let source = std::fs::read_to_string(path)?;
+            let file = path
+                .strip_prefix(project_root)
+                .unwrap_or(path)
+                .to_path_buf();
+            templates.add(&name, source, file)?;
+        }
+        Ok(templates)
+    }
+
+    fn add(&mut self, name: &str, source: String, file: PathBuf) -> Result<(), RenderError> {
+        self.sources.insert(name.to_string(), file);
+        self.env
+            .add_template_owned(name.to_string(), source)
+            .map_err(|err| self.error(err))
+    }
+
+    /// Whether a template with this name exists, e.g. `changelog.html`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.sources.contains_key(name)
+    }
+
+    /// The template a page layout renders with: `doc.html`, `home.html`,
+    /// `page.html`, or `<name>.html` for a custom layout. `None` when a custom
+    /// layout has no template; `base` and the partials are not layouts.
+    pub fn layout(&self, layout: &Layout) -> Option<String> {
+        if let Layout::Custom(name) = layout {
+            if name == "base" || name.contains('/') {
+                return None;
+            }
+        }
+        let template = format!("{}.html", layout.name());
+        self.contains(&template).then_some(template)
+    }
+
+    /// Check that every page's layout has a template, before any page is rendered.
+    /// A missing one is reported at the frontmatter line that names it.
+    pub fn check_layouts(
+        &self,
+        pages: &[Page],
+        raw_contents: &[RawContent],
+        content_dir: &Path,
+    ) -> Result<(), RenderError> {
+        for page in pages {
+            if self.layout(&page.frontmatter.layout).is_some() {
+                continue;
+            }
+            let file = content_dir.join(&page.route.source);
+            let line = raw_contents
+                .iter()
+                .find(|raw| raw.path == file)
+                .and_then(|raw| {
+                    raw.raw
+                        .lines()
+                        .position(|line| line.trim_start().starts_with("layout:"))
+                })
+                .map_or(1, |index| index + 1);
+            return Err(RenderError::LayoutNotFound {
+                name: page.frontmatter.layout.name().to_string(),
+                file,
+                line,
+            });
+        }
+        Ok(())
+    }
+
+    /// Render the named template with `context`.
+    pub fn render(&self, name: &str, context: Value) -> Result<String, RenderError> {
+        self.env
+            .get_template(name)
+            .and_then(|template| template.render(context))
+            .map_err(|err| self.error(err))
+    }
+
+    /// Convert a minijinja error into a [`RenderError`] pointing at the template
+    /// file and line it came from.
+    fn error(&self, err: minijinja::Error) -> RenderError {
+        let file = err
+            .name()
+            .map(|name| {
+                self.sources
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| PathBuf::from(name))
+            })
+            .unwrap_or_default();
+        let line = err.line().unwrap_or(0);
+
+        if err.kind() == ErrorKind::TemplateNotFound {
+            // The detail reads `template "name" does not exist`
+            let name = err
+                .detail()
+                .and_then(|detail| detail.split('"').nth(1))
+                .unwrap_or_default()
+                .to_string();
+            return RenderError::LayoutNotFound { name, file, line };
+        }
+
+        let reason = match err.detail() {
+            Some(detail) => format!("{}: {detail}", err.kind()),
+            None => err.kind().to_string(),
+        };
+        RenderError::Template { file, line, reason }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minijinja::context;
+
+    fn context(layout: &str) -> Value {
+        context! {
+            title => "Intro | Docs",
+            description => "",
+            head => Value::from_safe_string(String::new()),
+            scripts => Value::from_safe_string(String::new()),
+            page => context! {
+                title => "Intro",
+                path => "/guide/intro",
+                toc => vec![context! { id => "setup", text => "Set <up>", level => 2 }],
+                layout => layout,
+                content => Value::from_safe_string("<p>Hello</p>".to_string()),
+            },
+            site => context! {
+                title => "Docs",
+                base => "/",
+                language => "en",
+                nav => vec![context! { text => "Guide", link => "/guide/", active => false }],
+                sidebar => vec![context! {
+                    text => "Guide",
+                    items => vec![
+                        context! { text => "Intro", link => "/guide/intro", active => true },
+                        context! { text => "Setup", link => "/guide/setup", active => false },
+                    ],
+                }],
+                search => context! { enabled => true },
+                labels => Value::from_serialize(crate::render::labels::Labels::for_language("en")),
+            },
+            next => context! { title => "Setup", link => "/guide/setup" },
+        }
+    }
+
+    fn render(name: &str) -> String {
+        Templates::builtin().render(name, context(name)).unwrap()
+    }
+
+    #[test]
+    fn test_doc_layout() {
+        let html = render("doc.html");
+        assert!(html.contains("<a class=\"pyohwa-navbar-title\" href=\"/\">Docs</a>"));
+        assert!(html.contains("<a class=\"pyohwa-navbar-link\" href=\"/guide/\">Guide</a>"));
+        assert!(html.contains("<button class=\"pyohwa-navbar-search\">"));
+        assert!(html
+            .contains("<a class=\"pyohwa-sidebar-link active\" href=\"/guide/intro\">Intro</a>"));
+        assert!(html.contains("<a class=\"pyohwa-sidebar-link\" href=\"/guide/setup\">Setup</a>"));
+        assert!(html.contains(
+            "<a class=\"pyohwa-toc-link\" data-level=\"2\" href=\"#setup\">Set &lt;up&gt;</a>"
+        ));
+        assert!(html.contains("<div class=\"pyohwa-prose\" id=\"content\"><p>Hello</p></div>"));
+        assert!(html.contains("<div></div>\n        <a class=\"pyohwa-footer-link pyohwa-footer-next\" href=\"/guide/setup\">"));
+    }
+
+    #[test]
+    fn test_home_layout() {
+        let html = render("home.html");
+        assert!(html.contains("pyohwa-layout-home"));
+        assert!(html.contains("pyohwa-navbar"));
+        assert!(!html.contains("pyohwa-sidebar"));
+        assert!(!html.contains("pyohwa-footer"));
+        assert!(html.contains("<p>Hello</p>"));
+    }
+
+    #[test]
+    fn test_page_layout() {
+        let html = render("page.html");
+        assert!(html.contains("pyohwa-layout-page"));
+        assert!(html.contains("pyohwa-content--centered"));
+        assert!(html.contains("pyohwa-footer"));
+        assert!(!html.contains("pyohwa-toc"));
+    }
+
+    #[test]
+    fn test_search_button_follows_flag() {
+        let templates = Templates::builtin();
+        for enabled in [true, false] {
+            let ctx = context! { site => context! { search => context! { enabled } } };
+            let html = templates.render("partials/navbar.html", ctx).unwrap();
+            assert_eq!(html.contains("pyohwa-navbar-search"), enabled);
+        }
+    }
+
+    #[test]
+    fn test_project_templates_override_defaults() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("themes/default/templates/partials");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("footer.html"),
+            "<footer class=\"custom\">{{ page.title }}</footer>",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.parent().unwrap().join("changelog.html"),
+            "{% extends \"base.html\" %}{% block main %}<ol>{{ page.content }}</ol>{% endblock %}",
+        )
+        .unwrap();
+
+        let templates =
+            Templates::load(tmp.path(), &tmp.path().join("themes/default/templates")).unwrap();
+        let html = templates.render("doc.html", context("doc")).unwrap();
+        assert!(html.contains("<footer class=\"custom\">Intro</footer>"));
+        assert!(html.contains("pyohwa-sidebar"));
+
+        assert!(templates.contains("changelog.html"));
+        let html = templates
+            .render("changelog.html", context("changelog"))
+            .unwrap();
+        assert!(html.contains("<ol><p>Hello</p></ol>"));
+        assert!(html.contains("pyohwa-navbar"));
+    }
+
+    #[test]
+    fn test_errors_report_template_file_and_line() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("themes/default/templates");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::fs::write(dir.join("doc.html"), "<main>\n{% if %}\n</main>").unwrap();
+        let err =
+            Templates::load(tmp.path(), &tmp.path().join("themes/default/templates")).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("themes/default/templates/doc.html:2"),
+            "{err}"
+        );
+
+        std::fs::write(
+            dir.join("doc.html"),
+            "<main>\n\n{% include \"partials/missing.html\" %}\n</main>",
+        )
+        .unwrap();
+        let templates =
+            Templates::load(tmp.path(), &tmp.path().join("themes/default/templates")).unwrap();
+        let err = templates.render("doc.html", context("doc")).unwrap_err();
+        match err {
+            RenderError::LayoutNotFound { name, file, line } => {
+                assert_eq!(name, "partials/missing.html");
+                assert_eq!(file, Path::new("themes/default/templates/doc.html"));
+                assert_eq!(line, 3);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+}
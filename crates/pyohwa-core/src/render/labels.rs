@@ -0,0 +1,58 @@
+use serde::Serialize;
+
+/// Fixed theme text, shared by the server-rendered templates and the Elm client
+/// through `site.labels`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Labels {
+    /// Table of contents heading
+    pub on_this_page: &'static str,
+    pub previous: &'static str,
+    pub next: &'static str,
+}
+
+impl Labels {
+    /// Labels in the site's language (`ko`, `ko-KR`, ...), falling back to English.
+    /// Covers the same languages as the admonition titles.
+    pub fn for_language(language: &str) -> Self {
+        let (on_this_page, previous, next) = match primary_language(language).as_str() {
+            "ko" => ("이 페이지의 내용", "이전", "다음"),
+            "ja" => ("このページの内容", "前へ", "次へ"),
+            "zh" => ("本页目录", "上一页", "下一页"),
+            "de" => ("Auf dieser Seite", "Zurück", "Weiter"),
+            "fr" => ("Sur cette page", "Précédent", "Suivant"),
+            "es" => ("En esta página", "Anterior", "Siguiente"),
+            _ => ("On this page", "Previous", "Next"),
+        };
+        Self {
+            on_this_page,
+            previous,
+            next,
+        }
+    }
+}
+
+/// The primary subtag of a language tag, lowercased: `ko` for `ko-KR` or `ko_KR`.
+pub(crate) fn primary_language(language: &str) -> String {
+    language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_follow_site_language() {
+        assert_eq!(
+            Labels::for_language("ko-KR").on_this_page,
+            "이 페이지의 내용"
+        );
+        assert_eq!(Labels::for_language("de").previous, "Zurück");
+        assert_eq!(Labels::for_language("pt-BR"), Labels::for_language("en"));
+        assert_eq!(Labels::for_language("en").next, "Next");
+    }
+}
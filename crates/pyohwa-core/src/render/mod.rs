@@ -1,4 +1,6 @@
 pub mod assets;
 pub mod embedded;
-pub mod layout;
+pub mod engine;
+pub mod labels;
 pub mod template;
+pub mod theme;
@@ -1,25 +1,112 @@
-use serde_json::json;
+use std::collections::BTreeMap;
 
-use crate::config::Config;
+use minijinja::context;
+use serde_json::{json, Value};
+
+use crate::build::incremental::hash_content;
+use crate::config::{Config, HtmlTag};
 use crate::content::page::Page;
 use crate::error::RenderError;
 use crate::render::embedded;
-use crate::render::layout::wrap_layout;
+use crate::render::labels::Labels;
+use crate::render::theme::Theme;
 use crate::site::graph::SiteGraph;
 
+/// Site-wide data shared by every page: nav, sidebar, the search flag and the
+/// theme labels in the site's language.
+///
+/// It is written once per build to `assets/site.<hash>.json`, and each page's
+/// bootstrap script fetches it before starting Elm, so it is not repeated in
+/// every page. The hash of the contents is in the file name, so the file can be
+/// cached forever.
+#[derive(Debug, Clone)]
+pub struct SiteData {
+    pub value: Value,
+    pub json: String,
+    /// Output path relative to the output directory
+    pub path: String,
+    /// The `graph` template context. It is built once per build; the value is
+    /// reference-counted, so every page shares it instead of copying the site.
+    pub graph: minijinja::Value,
+}
+
+impl SiteData {
+    pub fn new(site_graph: &SiteGraph, config: &Config) -> Result<Self, RenderError> {
+        let nav_items: Vec<_> = site_graph
+            .nav
+            .iter()
+            .map(|item| {
+                json!({
+                    "text": item.text,
+                    "link": item.link,
+                    "active": false,
+                })
+            })
+            .collect();
+
+        // `active` depends on the page, so the bootstrap script adds it
+        let sidebar_groups: Vec<_> = site_graph
+            .sidebar
+            .iter()
+            .map(|group| {
+                let items: Vec<_> = group
+                    .items
+                    .iter()
+                    // text, link and the badge if there is one
+                    .map(|item| json!(item))
+                    .collect();
+                json!({
+                    "text": group.text,
+                    "items": items,
+                })
+            })
+            .collect();
+
+        let value = json!({
+            "nav": nav_items,
+            "sidebar": sidebar_groups,
+            "search": {
+                "enabled": config.search.enabled,
+            },
+            "labels": Labels::for_language(&config.site.language),
+        });
+        let json = serde_json::to_string(&value).map_err(RenderError::Serialization)?;
+        let path = format!("assets/site.{}.json", &hash_content(&json)[..10]);
+        let graph = minijinja::Value::from_serialize(graph_context(site_graph));
+
+        Ok(Self {
+            value,
+            json,
+            path,
+            graph,
+        })
+    }
+}
+
 /// Render a page to a complete HTML5 document.
 ///
-/// This is a pure function that combines:
-/// - Page content (HTML)
-/// - Site graph data (nav, sidebar)
-/// - Config (title, language, theme)
-/// - Embedded assets (Elm JS, CSS)
+/// The page's layout template (`doc.html`, `home.html`, `page.html` or a custom
+/// layout's `<name>.html`) is rendered with a context of:
+/// - `page`: title, description, path, TOC, layout and the content HTML
+/// - `frontmatter`: the page's frontmatter fields, custom ones included
+/// - `site`: config title, description, base and language, with the nav and
+///   search flag of the shared [`SiteData`] and the sidebar collapsed to the
+///   page's group
+/// - `graph`: every page of the site graph (with its custom frontmatter), nav
+///   and sidebar, shared by all pages through [`SiteData::graph`]
+/// - `theme`: name, version, highlight theme and the resolved theme settings
+/// - `prev` / `next` links, and the document `title` and `description`
+/// - `head` / `scripts`: the generated tags `base.html` places in the document
 ///
-/// The generated HTML includes `window.__PYOHWA_DATA__` for Elm initialization.
+/// The generated HTML includes `window.__PYOHWA_DATA__` for Elm initialization, and
+/// the navbar, sidebar, TOC and footer rendered from that same data. The content
+/// HTML is only in the body; the bootstrap script reads it from there.
 pub fn render_page(
     page: &Page,
     site_graph: &SiteGraph,
     config: &Config,
+    site_data: &SiteData,
+    theme: &Theme,
 ) -> Result<String, RenderError> {
     let page_title = build_page_title(&page.frontmatter.title, &config.site.title);
     let description = page
@@ -28,47 +115,73 @@ pub fn render_page(
         .as_deref()
         .unwrap_or(&config.site.description);
 
-    let body_content = wrap_layout(&page.frontmatter.layout, &page.html);
-
-    let pyohwa_data = build_pyohwa_data(page, site_graph, config)?;
-
     let base = normalize_base_url(&config.site.base_url);
-    let og_tags = build_og_tags(page, config, &page_title, description);
+    let data = build_pyohwa_data(
+        page,
+        site_graph,
+        config,
+        &format!("{base}{}", site_data.path),
+    );
+    let pyohwa_data = script_json(&data)?;
 
-    let html = format!(
-        r#"<!DOCTYPE html>
-<html lang="{lang}">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>{title}</title>
-    <meta name="description" content="{description}">
-{og_tags}
+    let og_tags = build_og_tags(page, config, &page_title, description);
+    let mut head = format!(
+        r#"{og_tags}
     <link rel="stylesheet" href="{base}assets/theme.css">
-</head>
-<body class="bg-white text-gray-900 dark:bg-gray-950 dark:text-gray-100">
-    <div id="app">
-        {body_content}
-    </div>
+    <link rel="stylesheet" href="{base}assets/highlight.css">"#
+    );
+    if let Some(custom) = &theme.custom_css {
+        head.push_str(&format!(
+            "\n    <link rel=\"stylesheet\" href=\"{base}{}\">",
+            custom.path
+        ));
+    }
+    head.push_str(&render_html_tags(&config.head));
 
-    <script>
+    let mut scripts = format!(
+        r#"    <script>
     window.__PYOHWA_DATA__ = {pyohwa_data};
     </script>
     <script src="{base}assets/elm.min.js"></script>
     <script>
-    if (typeof Elm !== 'undefined') {{
+    function activateCodeGroupTab(label) {{
+        document.querySelectorAll('.code-group').forEach(function(group) {{
+            var tabs = group.querySelectorAll(':scope > .code-group-tabs > .code-group-tab');
+            var panels = group.querySelectorAll(':scope > .code-group-panel');
+            var index = Array.prototype.findIndex.call(tabs, function(tab) {{
+                return tab.dataset.codeGroupTab === label;
+            }});
+            if (index < 0) {{ return; }}
+            tabs.forEach(function(tab, i) {{
+                tab.classList.toggle('active', i === index);
+                tab.setAttribute('aria-selected', String(i === index));
+            }});
+            panels.forEach(function(panel, i) {{ panel.classList.toggle('active', i === index); }});
+        }});
+    }}
+    function startElm(data) {{
+        var codeGroupTab = null;
+        try {{ codeGroupTab = localStorage.getItem('pyohwa-code-group-tab'); }} catch (e) {{}}
+        data.preferences = {{ codeGroupTab: codeGroupTab }};
         var app = Elm.Main.init({{
             node: document.getElementById('app'),
-            flags: window.__PYOHWA_DATA__
+            flags: data
         }});
         // Set innerHTML after Elm renders (property "innerHTML" needs a frame)
         requestAnimationFrame(function() {{
             var el = document.getElementById('content');
-            if (el && window.__PYOHWA_DATA__ && window.__PYOHWA_DATA__.page) {{
-                el.innerHTML = window.__PYOHWA_DATA__.page.content;
+            if (el) {{
+                el.innerHTML = data.page.content;
             }}
+            if (codeGroupTab) {{ activateCodeGroupTab(codeGroupTab); }}
         }});
         if (app.ports) {{
+            if (app.ports.selectCodeGroupTab) {{
+                app.ports.selectCodeGroupTab.subscribe(function(label) {{
+                    activateCodeGroupTab(label);
+                    try {{ localStorage.setItem('pyohwa-code-group-tab', label); }} catch (e) {{}}
+                }});
+            }}
             if (app.ports.scrollToElement) {{
                 app.ports.scrollToElement.subscribe(function(id) {{
                     var el = document.getElementById(id);
@@ -89,19 +202,184 @@ pub fn render_page(
             }}
         }}
     }}
-    </script>
-</body>
-</html>"#,
-        lang = config.site.language,
-        title = escape_html(&page_title),
-        description = escape_html(description),
-        base = base,
-        og_tags = og_tags,
-        body_content = body_content,
-        pyohwa_data = pyohwa_data,
+    if (typeof Elm !== 'undefined' && window.fetch) {{
+        var data = window.__PYOHWA_DATA__;
+        // The content is only in the server-rendered body, and nav, sidebar and
+        // search come from the shared site data; on failure the static page stays
+        var content = document.getElementById('content');
+        data.page.content = content ? content.innerHTML : '';
+        fetch(data.siteData)
+            .then(function(response) {{ return response.json(); }})
+            .then(function(shared) {{
+                data.site.nav = shared.nav;
+                data.site.sidebar = shared.sidebar.map(function(group) {{
+                    return {{
+                        text: group.text,
+                        items: group.items.map(function(item) {{
+                            return {{ text: item.text, link: item.link, badge: item.badge, active: item.link === data.page.path }};
+                        }})
+                    }};
+                }});
+                data.site.labels = shared.labels;
+                data.search = shared.search;
+                startElm(data);
+            }})
+            .catch(function() {{}});
+    }}
+    </script>"#
     );
+    scripts.push_str(&render_html_tags(&config.body_end));
+
+    let mut page_context: BTreeMap<String, minijinja::Value> = data["page"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(key, value)| (key.clone(), minijinja::Value::from_serialize(value)))
+        .collect();
+    page_context.insert(
+        "content".to_string(),
+        minijinja::Value::from_safe_string(page.html.clone()),
+    );
+
+    let context = context! {
+        title => page_title,
+        description => description,
+        head => minijinja::Value::from_safe_string(head),
+        scripts => minijinja::Value::from_safe_string(scripts),
+        page => page_context,
+        frontmatter => minijinja::Value::from_serialize(frontmatter_context(page)),
+        site => minijinja::Value::from_serialize(site_context(page, config, site_data)),
+        graph => site_data.graph.clone(),
+        prev => minijinja::Value::from_serialize(data.get("prev")),
+        next => minijinja::Value::from_serialize(data.get("next")),
+        theme => minijinja::Value::from_serialize(json!({
+            "name": theme.name,
+            "version": theme.manifest.version,
+            "highlightTheme": config.theme.highlight_theme,
+            "settings": theme.settings,
+        })),
+    };
+
+    let layout = theme
+        .templates
+        .layout(&page.frontmatter.layout)
+        .ok_or_else(|| {
+            // The build checks layouts up front with the exact frontmatter line
+            // (`Templates::check_layouts`); here the frontmatter's start has to do
+            RenderError::LayoutNotFound {
+                name: page.frontmatter.layout.name().to_string(),
+                file: page.route.source.clone(),
+                line: 1,
+            }
+        })?;
+    theme.templates.render(&layout, context)
+}
+
+/// The known frontmatter fields plus the page's custom ones.
+fn frontmatter_context(page: &Page) -> Value {
+    let frontmatter = &page.frontmatter;
+    let mut context = json!({
+        "title": frontmatter.title,
+        "description": frontmatter.description,
+        "layout": frontmatter.layout.name(),
+        "order": frontmatter.order,
+        "tags": frontmatter.tags,
+        "date": frontmatter.date,
+        "draft": frontmatter.draft,
+        "prev": frontmatter.prev,
+        "next": frontmatter.next,
+    });
+    for (key, value) in &frontmatter.extra {
+        context[key] = value.clone();
+    }
+    context
+}
+
+/// Config site fields plus the shared nav and search flag, and the sidebar
+/// collapsed to the page's section.
+///
+/// Only the groups holding the page list their items, with the page's marked
+/// active as the bootstrap script does for Elm. Every other group has just its
+/// title and a `link` to its first page, so a page does not grow with the size
+/// of the site; Elm renders the full sidebar from the shared site data.
+fn site_context(page: &Page, config: &Config, site_data: &SiteData) -> Value {
+    let path = page.route.path();
+    let sidebar: Vec<_> = site_data.value["sidebar"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|group| {
+            let items = group["items"]
+                .as_array()
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            if !items.iter().any(|item| item["link"] == path) {
+                return json!({
+                    "text": group["text"],
+                    "link": items.first().map(|item| &item["link"]),
+                    "items": [],
+                });
+            }
+            let items: Vec<_> = items
+                .iter()
+                .map(|item| {
+                    let mut item = item.clone();
+                    item["active"] = json!(item["link"] == path);
+                    item
+                })
+                .collect();
+            json!({ "text": group["text"], "items": items })
+        })
+        .collect();
+
+    json!({
+        "title": config.site.title,
+        "description": config.site.description,
+        "base": normalize_base_url(&config.site.base_url),
+        "language": config.site.language,
+        "nav": site_data.value["nav"],
+        "sidebar": sidebar,
+        "search": site_data.value["search"],
+        "labels": site_data.value["labels"],
+    })
+}
+
+fn graph_context(site_graph: &SiteGraph) -> Value {
+    let pages: Vec<_> = site_graph
+        .pages
+        .iter()
+        .map(|page| {
+            json!({
+                "title": page.frontmatter.title,
+                "description": page.frontmatter.description,
+                "path": page.route.path(),
+                "layout": page.frontmatter.layout.name(),
+                "tags": page.frontmatter.tags,
+                "date": page.frontmatter.date,
+                "draft": page.frontmatter.draft,
+                "frontmatter": page.frontmatter.extra,
+            })
+        })
+        .collect();
+    let nav: Vec<_> = site_graph
+        .nav
+        .iter()
+        .map(|item| json!({ "text": item.text, "link": item.link }))
+        .collect();
+    let sidebar: Vec<_> = site_graph
+        .sidebar
+        .iter()
+        .map(|group| {
+            let items: Vec<_> = group.items.iter().map(|item| json!(item)).collect();
+            json!({ "text": group.text, "items": items })
+        })
+        .collect();
 
-    Ok(html)
+    json!({
+        "pages": pages,
+        "nav": nav,
+        "sidebar": sidebar,
+    })
 }
 
 fn build_page_title(page_title: &str, site_title: &str) -> String {
@@ -126,7 +404,8 @@ fn build_pyohwa_data(
     page: &Page,
     site_graph: &SiteGraph,
     config: &Config,
-) -> Result<String, RenderError> {
+    site_data_url: &str,
+) -> Value {
     let toc_items: Vec<_> = page
         .toc
         .iter()
@@ -139,47 +418,6 @@ fn build_pyohwa_data(
         })
         .collect();
 
-    let nav_items: Vec<_> = site_graph
-        .nav
-        .iter()
-        .map(|item| {
-            json!({
-                "text": item.text,
-                "link": item.link,
-                "active": false,
-            })
-        })
-        .collect();
-
-    let sidebar_groups: Vec<_> = site_graph
-        .sidebar
-        .iter()
-        .map(|group| {
-            let items: Vec<_> = group
-                .items
-                .iter()
-                .map(|item| {
-                    json!({
-                        "text": item.text,
-                        "link": item.link,
-                        "active": item.link == page.route.path,
-                    })
-                })
-                .collect();
-            json!({
-                "text": group.text,
-                "items": items,
-            })
-        })
-        .collect();
-
-    let layout_str = match &page.frontmatter.layout {
-        crate::content::frontmatter::Layout::Doc => "doc",
-        crate::content::frontmatter::Layout::Home => "home",
-        crate::content::frontmatter::Layout::Page => "page",
-        crate::content::frontmatter::Layout::Custom(s) => s.as_str(),
-    };
-
     let prev_link = page.prev.as_ref().and_then(|route| {
         find_page_title(site_graph, route.path())
             .map(|title| json!({ "title": title, "link": route.path() }))
@@ -194,25 +432,20 @@ fn build_pyohwa_data(
         "page": {
             "title": page.frontmatter.title,
             "description": page.frontmatter.description.as_deref().unwrap_or(""),
-            "content": page.html,
+            "path": page.route.path(),
             "toc": toc_items,
-            "layout": layout_str,
-            "frontmatter": {}
+            "layout": page.frontmatter.layout.name(),
+            "frontmatter": page.frontmatter.extra,
         },
         "site": {
             "title": config.site.title,
             "description": config.site.description,
             "base": config.site.base_url,
-            "nav": nav_items,
-            "sidebar": sidebar_groups,
         },
         "theme": {
             "highlightTheme": config.theme.highlight_theme,
-        }
-    });
-
-    data["search"] = json!({
-        "enabled": config.search.enabled,
+        },
+        "siteData": site_data_url,
     });
 
     if let Some(prev) = prev_link {
@@ -222,7 +455,18 @@ fn build_pyohwa_data(
         data["next"] = next;
     }
 
-    serde_json::to_string(&data).map_err(RenderError::Serialization)
+    data
+}
+
+/// Serialize `value` for an inline `<script>`. `<`, `>` and `&` only occur inside
+/// JSON strings, where their `\u` escapes decode to the same text, so content
+/// like `</script>` or `<!--` cannot end or change the script element.
+fn script_json(value: &Value) -> Result<String, RenderError> {
+    let json = serde_json::to_string(value).map_err(RenderError::Serialization)?;
+    Ok(json
+        .replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026"))
 }
 
 /// Find a page's title by its route path from the site graph
@@ -243,6 +487,31 @@ fn find_page_title(site_graph: &SiteGraph, path: &str) -> Option<String> {
         .map(|p| p.frontmatter.title.clone())
 }
 
+/// Render `[[head]]` / `[[body_end]]` entries, one per line.
+fn render_html_tags(tags: &[HtmlTag]) -> String {
+    let mut html = String::new();
+    for tag in tags {
+        html.push_str("\n    <");
+        html.push_str(&tag.tag);
+        for (name, value) in &tag.attrs {
+            match value {
+                toml::Value::Boolean(true) => html.push_str(&format!(" {name}")),
+                toml::Value::Boolean(false) => {}
+                toml::Value::String(value) => {
+                    html.push_str(&format!(" {name}=\"{}\"", escape_html(value)));
+                }
+                other => html.push_str(&format!(" {name}=\"{}\"", escape_html(&other.to_string()))),
+            }
+        }
+        html.push('>');
+        if !tag.is_void() {
+            html.push_str(tag.content.as_deref().unwrap_or_default());
+            html.push_str(&format!("</{}>", tag.tag));
+        }
+    }
+    html
+}
+
 fn build_og_tags(page: &Page, config: &Config, page_title: &str, description: &str) -> String {
     let base = normalize_base_url(&config.site.base_url);
     let page_url = format!("{}{}", base.trim_end_matches('/'), page.route.path());
@@ -273,7 +542,7 @@ fn build_og_tags(page: &Page, config: &Config, page_title: &str, description: &s
     tags
 }
 
-fn escape_html(s: &str) -> String {
+pub(crate) fn escape_html(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -286,9 +555,11 @@ pub fn render_page_with_live_reload(
     page: &Page,
     site_graph: &SiteGraph,
     config: &Config,
+    site_data: &SiteData,
+    theme: &Theme,
     ws_port: u16,
 ) -> Result<String, RenderError> {
-    let html = render_page(page, site_graph, config)?;
+    let html = render_page(page, site_graph, config, site_data, theme)?;
     let script = live_reload_client_js(ws_port);
     Ok(html.replace("</body>", &format!("{script}\n</body>")))
 }
@@ -332,8 +603,10 @@ pub fn write_embedded_assets(output_dir: &std::path::Path) -> Result<(), crate::
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::content::frontmatter::Frontmatter;
+    use crate::content::frontmatter::{Frontmatter, Layout};
     use crate::content::page::TocItem;
+    use crate::render::engine::Templates;
+    use crate::render::theme::CustomCss;
     use crate::site::graph::{NavItem, SidebarGroup, SidebarItem, SiteGraph};
     use crate::site::route::Route;
     use std::path::PathBuf;
@@ -369,6 +642,7 @@ mod tests {
                 items: vec![SidebarItem {
                     text: "Introduction".to_string(),
                     link: "/guide/intro".to_string(),
+                    badge: None,
                 }],
             }],
             nav: vec![NavItem {
@@ -384,7 +658,14 @@ mod tests {
         let graph = make_test_graph();
         let config = Config::default();
 
-        let html = render_page(&page, &graph, &config).unwrap();
+        let html = render_page(
+            &page,
+            &graph,
+            &config,
+            &SiteData::new(&graph, &config).unwrap(),
+            &Theme::builtin(),
+        )
+        .unwrap();
 
         assert!(html.starts_with("<!DOCTYPE html>"));
         assert!(html.contains("<html lang=\"en\">"));
@@ -392,6 +673,8 @@ mod tests {
         assert!(html.contains("<title>Introduction | Documentation</title>"));
         assert!(html.contains("window.__PYOHWA_DATA__"));
         assert!(html.contains("Elm.Main.init"));
+        assert!(html.contains("href=\"/assets/highlight.css\""));
+        assert!(html.contains("app.ports.selectCodeGroupTab"));
     }
 
     #[test]
@@ -400,7 +683,14 @@ mod tests {
         let graph = make_test_graph();
         let config = Config::default();
 
-        let html = render_page(&page, &graph, &config).unwrap();
+        let html = render_page(
+            &page,
+            &graph,
+            &config,
+            &SiteData::new(&graph, &config).unwrap(),
+            &Theme::builtin(),
+        )
+        .unwrap();
 
         assert!(html.contains("Hello world"));
     }
@@ -426,7 +716,15 @@ mod tests {
         let graph = make_test_graph();
         let config = Config::default();
 
-        let html = render_page_with_live_reload(&page, &graph, &config, 3000).unwrap();
+        let html = render_page_with_live_reload(
+            &page,
+            &graph,
+            &config,
+            &SiteData::new(&graph, &config).unwrap(),
+            &Theme::builtin(),
+            3000,
+        )
+        .unwrap();
         assert!(html.contains("__pyohwa_ws"));
         assert!(html.contains("WebSocket"));
     }
@@ -437,7 +735,15 @@ mod tests {
         let graph = make_test_graph();
         let config = Config::default();
 
-        let html = render_page_with_live_reload(&page, &graph, &config, 3000).unwrap();
+        let html = render_page_with_live_reload(
+            &page,
+            &graph,
+            &config,
+            &SiteData::new(&graph, &config).unwrap(),
+            &Theme::builtin(),
+            3000,
+        )
+        .unwrap();
         let ws_pos = html.find("__pyohwa_ws").unwrap();
         let body_pos = html.find("</body>").unwrap();
         assert!(ws_pos < body_pos);
@@ -455,7 +761,14 @@ mod tests {
         let graph = make_test_graph();
         let config = Config::default();
 
-        let html = render_page(&page, &graph, &config).unwrap();
+        let html = render_page(
+            &page,
+            &graph,
+            &config,
+            &SiteData::new(&graph, &config).unwrap(),
+            &Theme::builtin(),
+        )
+        .unwrap();
         assert!(html.contains("og:title"));
         assert!(html.contains("og:description"));
         assert!(html.contains("og:type"));
@@ -471,20 +784,335 @@ mod tests {
         let mut config = Config::default();
         config.seo.og_image = Some("https://example.com/og.png".to_string());
 
-        let html = render_page(&page, &graph, &config).unwrap();
+        let html = render_page(
+            &page,
+            &graph,
+            &config,
+            &SiteData::new(&graph, &config).unwrap(),
+            &Theme::builtin(),
+        )
+        .unwrap();
         assert!(html.contains("og:image"));
         assert!(html.contains("twitter:image"));
         assert!(html.contains("https://example.com/og.png"));
     }
 
+    /// The `window.__PYOHWA_DATA__` payload embedded in a rendered page.
+    fn embedded_data(html: &str) -> Value {
+        let start = html.find("window.__PYOHWA_DATA__ = ").unwrap() + 25;
+        let end = start + html[start..].find(";\n").unwrap();
+        serde_json::from_str(&html[start..end]).unwrap()
+    }
+
+    #[test]
+    fn test_server_rendered_navigation_matches_pyohwa_data() {
+        let mut page = make_test_page();
+        page.next = Some(Route {
+            path: "/guide/setup".to_string(),
+            source: PathBuf::from("guide/setup.md"),
+            output: PathBuf::from("guide/setup/index.html"),
+        });
+        let mut graph = make_test_graph();
+        graph.sidebar[0].items.push(SidebarItem {
+            text: "Setup".to_string(),
+            link: "/guide/setup".to_string(),
+            badge: None,
+        });
+        let config = Config::default();
+
+        let site_data = SiteData::new(&graph, &config).unwrap();
+        let html = render_page(&page, &graph, &config, &site_data, &Theme::builtin()).unwrap();
+        let data = embedded_data(&html);
+        let site = &site_data.value;
+
+        for item in site["nav"].as_array().unwrap() {
+            assert!(html.contains(&format!(
+                "<a class=\"pyohwa-navbar-link\" href=\"{}\">{}</a>",
+                item["link"].as_str().unwrap(),
+                item["text"].as_str().unwrap()
+            )));
+        }
+        for group in site["sidebar"].as_array().unwrap() {
+            assert!(html.contains(&format!(
+                "<div class=\"pyohwa-sidebar-group-title\">{}</div>",
+                group["text"].as_str().unwrap()
+            )));
+            for item in group["items"].as_array().unwrap() {
+                // The bootstrap script marks the item linking to the page as active
+                let class = if item["link"] == data["page"]["path"] {
+                    "pyohwa-sidebar-link active"
+                } else {
+                    "pyohwa-sidebar-link"
+                };
+                assert!(html.contains(&format!(
+                    "<a class=\"{class}\" href=\"{}\">{}</a>",
+                    item["link"].as_str().unwrap(),
+                    item["text"].as_str().unwrap()
+                )));
+            }
+        }
+        for item in data["page"]["toc"].as_array().unwrap() {
+            assert!(html.contains(&format!(
+                "data-level=\"{}\" href=\"#{}\">{}</a>",
+                item["level"],
+                item["id"].as_str().unwrap(),
+                item["text"].as_str().unwrap()
+            )));
+        }
+        assert!(html.contains(&format!(
+            "<a class=\"pyohwa-footer-link pyohwa-footer-next\" href=\"{}\"><span class=\"pyohwa-footer-label\">Next</span><span class=\"pyohwa-footer-title\">{}</span></a>",
+            data["next"]["link"].as_str().unwrap(),
+            data["next"]["title"].as_str().unwrap()
+        )));
+        assert!(data.get("prev").is_none());
+        assert!(html.contains(&format!(
+            "<a class=\"pyohwa-navbar-title\" href=\"{}\">{}</a>",
+            data["site"]["base"].as_str().unwrap(),
+            data["site"]["title"].as_str().unwrap()
+        )));
+    }
+
     #[test]
-    fn test_search_flag_in_pyohwa_data() {
+    fn test_server_rendered_sidebar_collapses_other_groups() {
         let page = make_test_page();
+        let mut graph = make_test_graph();
+        graph.sidebar.push(SidebarGroup {
+            text: "Reference".to_string(),
+            items: vec![
+                SidebarItem {
+                    text: "Config".to_string(),
+                    link: "/reference/config".to_string(),
+                    badge: None,
+                },
+                SidebarItem {
+                    text: "CLI".to_string(),
+                    link: "/reference/cli".to_string(),
+                    badge: None,
+                },
+            ],
+        });
+        let config = Config::default();
+
+        let site_data = SiteData::new(&graph, &config).unwrap();
+        let html = render_page(&page, &graph, &config, &site_data, &Theme::builtin()).unwrap();
+
+        assert!(html.contains(
+            "<a class=\"pyohwa-sidebar-link active\" href=\"/guide/intro\">Introduction</a>"
+        ));
+        assert!(html.contains(
+            "<a class=\"pyohwa-sidebar-group-title\" href=\"/reference/config\">Reference</a>"
+        ));
+        assert!(!html.contains("href=\"/reference/cli\""));
+        // The full sidebar stays in the shared site data and the graph
+        assert_eq!(site_data.value["sidebar"][1]["items"][1]["text"], "CLI");
+    }
+
+    #[test]
+    fn test_custom_layout_renders_its_template() {
+        let mut page = make_test_page();
+        page.frontmatter.layout = Layout::Custom("changelog".to_string());
+        let graph = make_test_graph();
+        let config = Config::default();
+        let site_data = SiteData::new(&graph, &config).unwrap();
+
+        let err = render_page(&page, &graph, &config, &site_data, &Theme::builtin()).unwrap_err();
+        assert!(
+            matches!(err, RenderError::LayoutNotFound { ref name, .. } if name == "changelog"),
+            "{err}"
+        );
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("themes/default/templates");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("changelog.html"),
+            "{% extends \"base.html\" %}{% block main %}<ol data-layout=\"{{ frontmatter.layout }}\">{{ page.content }}</ol>{% endblock %}",
+        )
+        .unwrap();
+        let theme = Theme {
+            templates: Templates::load(tmp.path(), &dir).unwrap(),
+            ..Theme::builtin()
+        };
+
+        let html = render_page(&page, &graph, &config, &site_data, &theme).unwrap();
+        assert!(html.contains("<ol data-layout=\"changelog\"><h1>Introduction</h1>"));
+        assert!(!html.contains("pyohwa-sidebar"));
+        assert_eq!(embedded_data(&html)["page"]["layout"], json!("changelog"));
+    }
+
+    #[test]
+    fn test_server_rendered_labels_follow_site_language() {
+        let mut page = make_test_page();
+        page.next = Some(Route {
+            path: "/guide/setup".to_string(),
+            source: PathBuf::from("guide/setup.md"),
+            output: PathBuf::from("guide/setup/index.html"),
+        });
+        let mut graph = make_test_graph();
+        graph.sidebar[0].items.push(SidebarItem {
+            text: "Setup".to_string(),
+            link: "/guide/setup".to_string(),
+            badge: None,
+        });
+        let mut config = Config::default();
+        config.site.language = "ko".to_string();
+        let site_data = SiteData::new(&graph, &config).unwrap();
+
+        let html = render_page(&page, &graph, &config, &site_data, &Theme::builtin()).unwrap();
+        assert!(html.contains("<div class=\"pyohwa-toc-title\">이 페이지의 내용</div>"));
+        assert!(html.contains("<span class=\"pyohwa-footer-label\">다음</span>"));
+        assert!(!html.contains("On this page"));
+        // The client gets the same labels through the shared site data
+        assert_eq!(
+            site_data.value["labels"]["onThisPage"],
+            json!("이 페이지의 내용")
+        );
+        assert_eq!(site_data.value["labels"]["previous"], json!("이전"));
+    }
+
+    #[test]
+    fn test_search_flag_in_site_data() {
+        let graph = make_test_graph();
+        let mut config = Config::default();
+
+        let site_data = SiteData::new(&graph, &config).unwrap();
+        assert_eq!(site_data.value["search"]["enabled"], json!(true));
+
+        config.search.enabled = false;
+        let disabled = SiteData::new(&graph, &config).unwrap();
+        assert_eq!(disabled.value["search"]["enabled"], json!(false));
+        assert_ne!(site_data.path, disabled.path);
+    }
+
+    #[test]
+    fn test_pyohwa_data_links_site_data_without_duplicating_content() {
+        let page = make_test_page();
+        let graph = make_test_graph();
+        let mut config = Config::default();
+        config.site.base_url = "/docs".to_string();
+        let site_data = SiteData::new(&graph, &config).unwrap();
+
+        let html = render_page(&page, &graph, &config, &site_data, &Theme::builtin()).unwrap();
+        let data = embedded_data(&html);
+
+        assert!(site_data.path.starts_with("assets/site."));
+        assert!(site_data.path.ends_with(".json"));
+        assert_eq!(data["siteData"], json!(format!("/docs/{}", site_data.path)));
+        assert_eq!(data["page"]["path"], json!("/guide/intro"));
+        assert!(data["page"].get("content").is_none());
+        assert!(data["site"].get("sidebar").is_none());
+        assert_eq!(html.matches("Hello world").count(), 1);
+    }
+
+    #[test]
+    fn test_custom_css_and_injected_tags() {
+        let page = make_test_page();
+        let graph = make_test_graph();
+        let config: Config = toml::from_str(
+            r##"
+[[head]]
+tag = "meta"
+attrs = { name = "theme-color", content = "#fff\"" }
+
+[[head]]
+tag = "link"
+attrs = { rel = "preconnect", href = "https://fonts.example.com", crossorigin = true, hidden = false }
+
+[[body_end]]
+tag = "script"
+attrs = { defer = true }
+content = "track('<page>');"
+"##,
+        )
+        .unwrap();
+        let site_data = SiteData::new(&graph, &config).unwrap();
+        let theme = Theme {
+            custom_css: Some(CustomCss {
+                css: "a {}".to_string(),
+                path: "assets/custom.0123456789.css".to_string(),
+            }),
+            ..Theme::builtin()
+        };
+
+        let html = render_page(&page, &graph, &config, &site_data, &theme).unwrap();
+        let (head, body) = html.split_once("</head>").unwrap();
+
+        let theme_css = head.find("href=\"/assets/theme.css\"").unwrap();
+        let custom_css = head
+            .find("<link rel=\"stylesheet\" href=\"/assets/custom.0123456789.css\">")
+            .unwrap();
+        assert!(theme_css < custom_css);
+        assert!(head.contains("<meta content=\"#fff&quot;\" name=\"theme-color\">"));
+        assert!(head
+            .contains("<link crossorigin href=\"https://fonts.example.com\" rel=\"preconnect\">"));
+        assert!(!head.contains("hidden"));
+
+        let script = "<script defer>track('<page>');</script>";
+        assert!(body.contains(&format!("{script}\n</body>")), "{body}");
+        assert!(body.find("elm.min.js").unwrap() < body.find(script).unwrap());
+    }
+
+    #[test]
+    fn test_custom_frontmatter_reaches_templates_and_client() {
+        let mut page = make_test_page();
+        page.frontmatter
+            .extra
+            .insert("status".to_string(), json!("beta"));
+        let mut graph = make_test_graph();
+        graph.sidebar[0].items[0].badge = Some("<beta>".to_string());
+        graph.pages.push(page.clone());
+        let config = Config::default();
+        let site_data = SiteData::new(&graph, &config).unwrap();
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("themes/default/templates");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("doc.html"),
+            "{% extends \"base.html\" %}{% block main %}<p class=\"status\">{{ frontmatter.status }}</p>{% for p in graph.pages %}<i>{{ p.frontmatter.status }}</i>{% endfor %}{% include \"partials/sidebar.html\" %}{% endblock %}",
+        )
+        .unwrap();
+        let theme = Theme {
+            templates: Templates::load(tmp.path(), &dir).unwrap(),
+            ..Theme::builtin()
+        };
+
+        let html = render_page(&page, &graph, &config, &site_data, &theme).unwrap();
+        assert!(html.contains("<p class=\"status\">beta</p><i>beta</i>"));
+        assert!(html
+            .contains("Introduction <span class=\"pyohwa-sidebar-badge\">&lt;beta&gt;</span></a>"));
+        assert_eq!(
+            embedded_data(&html)["page"]["frontmatter"],
+            json!({ "status": "beta" })
+        );
+        assert_eq!(
+            site_data.value["sidebar"][0]["items"][0]["badge"],
+            json!("<beta>")
+        );
+        let plain = SiteData::new(&make_test_graph(), &config).unwrap();
+        assert!(plain.value["sidebar"][0]["items"][0].get("badge").is_none());
+    }
+
+    #[test]
+    fn test_inline_data_cannot_close_the_script() {
+        let mut page = make_test_page();
+        let hostile = "</script><script>alert(1)</script><!-- & -->";
+        page.frontmatter
+            .extra
+            .insert("note".to_string(), json!(hostile));
+        page.frontmatter.title = "A </SCRIPT> title".to_string();
         let graph = make_test_graph();
         let config = Config::default();
+        let site_data = SiteData::new(&graph, &config).unwrap();
 
-        let html = render_page(&page, &graph, &config).unwrap();
-        assert!(html.contains("\"search\""));
-        assert!(html.contains("\"enabled\""));
+        let html = render_page(&page, &graph, &config, &site_data, &Theme::builtin()).unwrap();
+        let start = html.find("window.__PYOHWA_DATA__ = ").unwrap();
+        let script = &html[start..start + html[start..].find("</script>").unwrap()];
+        assert!(!script.contains('<'));
+        assert!(script.contains(r"\u003c/script\u003e\u003cscript\u003ealert(1)"));
+        // The escapes decode back to the original text
+        let data = embedded_data(&html);
+        assert_eq!(data["page"]["frontmatter"]["note"], json!(hostile));
+        assert_eq!(data["page"]["title"], json!("A </SCRIPT> title"));
     }
 }
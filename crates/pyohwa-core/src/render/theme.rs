@@ -0,0 +1,336 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::build::incremental::hash_content;
+use crate::config::ThemeConfig;
+use crate::error::{BuildError, ConfigError};
+use crate::render::embedded;
+use crate::render::engine::Templates;
+
+/// Project directory holding theme packages.
+const THEMES_DIR: &str = "themes";
+
+/// The theme that ships inside the binary.
+const DEFAULT_THEME: &str = "default";
+
+/// `theme.toml`: what a theme package is and which settings it accepts.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeManifest {
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    /// Settings a project may set under `[theme.settings]`
+    pub settings: BTreeMap<String, ThemeSetting>,
+}
+
+/// One setting declared in `theme.toml`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeSetting {
+    /// Used when the project does not set it; a project value must have the same type
+    pub default: toml::Value,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// A resolved theme: the project's `themes/<theme.name>/` package, with the
+/// embedded default filling in whatever the package does not provide.
+///
+/// ```text
+/// themes/<name>/
+/// ├── theme.toml          metadata and accepted settings
+/// ├── templates/*.html    page templates (see `Templates`)
+/// ├── static/             copied to the output before the project's static files
+/// └── dist/
+///     ├── theme.css       compiled stylesheet, written as assets/theme.css
+///     └── elm.min.js      compiled app, written as assets/elm.min.js
+/// ```
+#[derive(Debug)]
+pub struct Theme {
+    pub name: String,
+    /// The package directory, when the project has one
+    pub dir: Option<PathBuf>,
+    pub manifest: ThemeManifest,
+    pub css: Cow<'static, str>,
+    pub js: Cow<'static, str>,
+    pub templates: Templates,
+    /// The manifest's defaults overlaid with the project's `[theme.settings]`
+    pub settings: Map<String, Value>,
+    /// The project's `theme.custom_css`, linked after the theme's stylesheet
+    pub custom_css: Option<CustomCss>,
+}
+
+/// A project stylesheet written under a name derived from its contents, so it
+/// can be cached forever like the shared site data.
+#[derive(Debug)]
+pub struct CustomCss {
+    pub css: String,
+    /// Output path relative to the output directory, `assets/custom.<hash>.css`
+    pub path: String,
+}
+
+impl CustomCss {
+    /// Read `theme.custom_css`, relative to `project_root`.
+    fn load(project_root: &Path, config: &ThemeConfig) -> Result<Option<Self>, BuildError> {
+        let Some(file) = &config.custom_css else {
+            return Ok(None);
+        };
+        let path = project_root.join(file);
+        if !path.is_file() {
+            return Err(ConfigError::CustomCssNotFound { path }.into());
+        }
+        let css = std::fs::read_to_string(&path)?;
+        let path = format!("assets/custom.{}.css", &hash_content(&css)[..10]);
+        Ok(Some(Self { css, path }))
+    }
+}
+
+impl Theme {
+    /// The embedded default theme.
+    pub fn builtin() -> Self {
+        Self {
+            name: DEFAULT_THEME.to_string(),
+            dir: None,
+            manifest: ThemeManifest {
+                name: DEFAULT_THEME.to_string(),
+                ..Default::default()
+            },
+            css: Cow::Borrowed(embedded::THEME_CSS),
+            js: Cow::Borrowed(embedded::ELM_JS),
+            templates: Templates::builtin(),
+            settings: Map::new(),
+            custom_css: None,
+        }
+    }
+
+    /// Resolve `theme.name` to `themes/<name>/` under `project_root`.
+    ///
+    /// Only the default theme may be missing, in which case the embedded one is used.
+    pub fn load(project_root: &Path, config: &ThemeConfig) -> Result<Self, BuildError> {
+        let dir = project_root.join(THEMES_DIR).join(&config.name);
+        if !dir.is_dir() {
+            if config.name != DEFAULT_THEME {
+                return Err(ConfigError::ThemeNotFound {
+                    name: config.name.clone(),
+                    path: dir,
+                }
+                .into());
+            }
+            let mut theme = Self::builtin();
+            theme.check_settings(config)?;
+            theme.custom_css = CustomCss::load(project_root, config)?;
+            return Ok(theme);
+        }
+
+        let manifest_path = dir.join("theme.toml");
+        let manifest = if manifest_path.is_file() {
+            let content = std::fs::read_to_string(&manifest_path)?;
+            toml::from_str(&content).map_err(|e| ConfigError::InvalidThemeManifest {
+                path: manifest_path,
+                reason: e.to_string(),
+            })?
+        } else {
+            ThemeManifest {
+                name: config.name.clone(),
+                ..Default::default()
+            }
+        };
+
+        let read_or = |file: &str, fallback: &'static str| -> std::io::Result<Cow<'static, str>> {
+            let path = dir.join("dist").join(file);
+            Ok(if path.is_file() {
+                Cow::Owned(std::fs::read_to_string(path)?)
+            } else {
+                Cow::Borrowed(fallback)
+            })
+        };
+
+        let mut theme = Self {
+            name: config.name.clone(),
+            css: read_or("theme.css", embedded::THEME_CSS)?,
+            js: read_or("elm.min.js", embedded::ELM_JS)?,
+            templates: Templates::load(project_root, &dir.join("templates"))?,
+            dir: Some(dir),
+            manifest,
+            settings: Map::new(),
+            custom_css: CustomCss::load(project_root, config)?,
+        };
+        theme.settings = theme.check_settings(config)?;
+        Ok(theme)
+    }
+
+    /// Validate `[theme.settings]` against the manifest and merge them over its defaults.
+    fn check_settings(&self, config: &ThemeConfig) -> Result<Map<String, Value>, ConfigError> {
+        for (key, value) in &config.settings {
+            let Some(setting) = self.manifest.settings.get(key) else {
+                let accepted: Vec<_> = self.manifest.settings.keys().cloned().collect();
+                return Err(ConfigError::UnknownThemeSetting {
+                    theme: self.name.clone(),
+                    key: key.clone(),
+                    accepted: if accepted.is_empty() {
+                        "none".to_string()
+                    } else {
+                        accepted.join(", ")
+                    },
+                });
+            };
+            if value.type_str() != setting.default.type_str() {
+                return Err(ConfigError::InvalidThemeSetting {
+                    key: key.clone(),
+                    expected: setting.default.type_str().to_string(),
+                    found: value.type_str().to_string(),
+                });
+            }
+        }
+
+        Ok(self
+            .manifest
+            .settings
+            .iter()
+            .map(|(key, setting)| {
+                let value = config.settings.get(key).unwrap_or(&setting.default);
+                (key.clone(), json!(value))
+            })
+            .collect())
+    }
+
+    /// The theme's `static/` directory, if it has one.
+    pub fn static_dir(&self) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join("static"))
+            .filter(|dir| dir.is_dir())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(name: &str, settings: &str) -> ThemeConfig {
+        ThemeConfig {
+            name: name.to_string(),
+            settings: toml::from_str(settings).unwrap(),
+            ..Default::default()
+        }
+    }
+
+    fn write_theme(root: &Path) -> PathBuf {
+        let dir = root.join("themes/ocean");
+        std::fs::create_dir_all(dir.join("dist")).unwrap();
+        std::fs::write(
+            dir.join("theme.toml"),
+            r##"name = "Ocean"
+version = "1.0.0"
+
+[settings.accent]
+default = "#0af"
+description = "Accent color"
+
+[settings.wide]
+default = false
+"##,
+        )
+        .unwrap();
+        std::fs::write(dir.join("dist/theme.css"), "body { color: teal }").unwrap();
+        dir
+    }
+
+    #[test]
+    fn missing_pieces_fall_back_to_the_embedded_theme() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_theme(tmp.path());
+
+        let theme = Theme::load(tmp.path(), &config("ocean", "")).unwrap();
+        assert_eq!(theme.manifest.name, "Ocean");
+        assert_eq!(theme.css, "body { color: teal }");
+        assert_eq!(theme.js, embedded::ELM_JS);
+        assert!(theme.templates.contains("doc.html"));
+        assert!(theme.static_dir().is_none());
+    }
+
+    #[test]
+    fn default_theme_without_directory_is_embedded() {
+        let tmp = tempfile::tempdir().unwrap();
+        let theme = Theme::load(tmp.path(), &config("default", "")).unwrap();
+        assert!(theme.dir.is_none());
+        assert_eq!(theme.css, embedded::THEME_CSS);
+    }
+
+    #[test]
+    fn unknown_theme_fails() {
+        let tmp = tempfile::tempdir().unwrap();
+        let err = Theme::load(tmp.path(), &config("nope", "")).unwrap_err();
+        assert!(matches!(
+            err,
+            BuildError::Config(ConfigError::ThemeNotFound { ref name, .. }) if name == "nope"
+        ));
+    }
+
+    #[test]
+    fn settings_merge_over_manifest_defaults() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_theme(tmp.path());
+
+        let theme = Theme::load(tmp.path(), &config("ocean", "wide = true")).unwrap();
+        assert_eq!(theme.settings["accent"], json!("#0af"));
+        assert_eq!(theme.settings["wide"], json!(true));
+    }
+
+    #[test]
+    fn settings_are_checked_against_the_manifest() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_theme(tmp.path());
+
+        let err = Theme::load(tmp.path(), &config("ocean", "color = \"red\"")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "config error: theme 'ocean' does not accept setting 'color' (accepted: accent, wide)"
+        );
+
+        let err = Theme::load(tmp.path(), &config("ocean", "wide = \"yes\"")).unwrap_err();
+        assert!(matches!(
+            err,
+            BuildError::Config(ConfigError::InvalidThemeSetting { ref key, .. }) if key == "wide"
+        ));
+
+        let err = Theme::load(tmp.path(), &config("default", "wide = true")).unwrap_err();
+        assert!(err.to_string().contains("(accepted: none)"));
+    }
+
+    #[test]
+    fn custom_css_is_fingerprinted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = config("default", "");
+        config.custom_css = Some(PathBuf::from("styles/custom.css"));
+
+        let err = Theme::load(tmp.path(), &config).unwrap_err();
+        assert!(matches!(
+            err,
+            BuildError::Config(ConfigError::CustomCssNotFound { .. })
+        ));
+
+        std::fs::create_dir_all(tmp.path().join("styles")).unwrap();
+        std::fs::write(tmp.path().join("styles/custom.css"), "a { color: red }").unwrap();
+        let custom = Theme::load(tmp.path(), &config)
+            .unwrap()
+            .custom_css
+            .unwrap();
+        assert_eq!(custom.css, "a { color: red }");
+        assert!(custom.path.starts_with("assets/custom."));
+        assert!(custom.path.ends_with(".css"));
+
+        std::fs::write(tmp.path().join("styles/custom.css"), "a { color: blue }").unwrap();
+        let changed = Theme::load(tmp.path(), &config)
+            .unwrap()
+            .custom_css
+            .unwrap();
+        assert_ne!(changed.path, custom.path);
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
 use serde::Serialize;
@@ -26,6 +26,10 @@ pub struct SidebarGroup {
 pub struct SidebarItem {
     pub text: String,
     pub link: String,
+    /// Shown next to the link; filled from the page's `sidebar.badge` frontmatter
+    /// field unless the manual sidebar sets it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub badge: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, serde::Deserialize)]
@@ -67,7 +71,10 @@ pub fn build_graph_with_content_dir(
         })
         .collect();
 
-    let sidebar = build_sidebar(&pages, config);
+    let mut sidebar = build_sidebar(&pages, config);
+    if let Some(key) = &config.sidebar.badge {
+        assign_badges(&mut sidebar, &pages, key);
+    }
     let nav = build_nav(config);
 
     let ordered_paths = collect_sidebar_links(&sidebar);
@@ -116,6 +123,7 @@ fn auto_generate_sidebar(pages: &[Page]) -> Vec<SidebarGroup> {
                 .map(|p| SidebarItem {
                     text: p.frontmatter.title.clone(),
                     link: p.route.path().to_string(),
+                    badge: None,
                 })
                 .collect();
 
@@ -127,6 +135,25 @@ fn auto_generate_sidebar(pages: &[Page]) -> Vec<SidebarGroup> {
         .collect()
 }
 
+/// Badge each sidebar item whose page has the custom frontmatter field `key`;
+/// list values are joined with commas.
+fn assign_badges(sidebar: &mut [SidebarGroup], pages: &[Page], key: &str) {
+    let pages_by_path: HashMap<&str, &Page> =
+        pages.iter().map(|page| (page.route.path(), page)).collect();
+    for item in sidebar.iter_mut().flat_map(|group| group.items.iter_mut()) {
+        if item.badge.is_some() {
+            continue;
+        }
+        let values = pages_by_path
+            .get(item.link.as_str())
+            .map(|page| page.frontmatter.extra_values(key))
+            .unwrap_or_default();
+        if !values.is_empty() {
+            item.badge = Some(values.join(", "));
+        }
+    }
+}
+
 fn dir_display_name(dir: &str) -> String {
     if dir.is_empty() {
         return "Root".to_string();
@@ -215,6 +242,7 @@ fn find_route_by_path(pages: &[Page], path: &str) -> Option<Route> {
 }
 
 #[cfg(test)]
+#[allow(clippy::unnecessary_map_or, clippy::field_reassign_with_default)]
 mod tests {
     use super::*;
     use crate::content::frontmatter::Frontmatter;
@@ -320,6 +348,7 @@ mod tests {
             items: vec![SidebarItem {
                 text: "Custom Item".to_string(),
                 link: "/custom".to_string(),
+                badge: None,
             }],
         }];
 
@@ -357,4 +386,34 @@ mod tests {
         assert_eq!(graph.sidebar[0].items[1].text, "M Middle");
         assert_eq!(graph.sidebar[0].items[2].text, "Z Last");
     }
+
+    #[test]
+    fn test_sidebar_badges_from_custom_frontmatter() {
+        let mut rendered = vec![
+            make_rendered("content/guide/a.md", "A", Some(1)),
+            make_rendered("content/guide/b.md", "B", Some(2)),
+            make_rendered("content/guide/c.md", "C", Some(3)),
+        ];
+        rendered[0]
+            .frontmatter
+            .extra
+            .insert("status".to_string(), serde_json::json!("beta"));
+        rendered[1]
+            .frontmatter
+            .extra
+            .insert("status".to_string(), serde_json::json!(["new", 2]));
+
+        let mut config = Config::default();
+        let graph = build_graph(&rendered, &config);
+        assert!(graph.sidebar[0].items.iter().all(|i| i.badge.is_none()));
+
+        config.sidebar.badge = Some("status".to_string());
+        let graph = build_graph(&rendered, &config);
+        let badges: Vec<_> = graph.sidebar[0]
+            .items
+            .iter()
+            .map(|i| i.badge.as_deref())
+            .collect();
+        assert_eq!(badges, vec![Some("beta"), Some("new, 2"), None]);
+    }
 }
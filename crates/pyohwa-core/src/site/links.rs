@@ -0,0 +1,401 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::render::template::escape_html;
+use crate::site::graph::SiteGraph;
+
+/// `href="..."` and `src='...'` attributes in rendered page HTML. Code blocks are
+/// escaped by then, so their quotes never match.
+static LINK_ATTR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\b(href|src)=(?:"([^"]*)"|'([^']*)')"#).unwrap());
+/// `https:`, `mailto:`, `data:` ... or a protocol-relative `//host`.
+static EXTERNAL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:[a-zA-Z][a-zA-Z0-9+.-]*:|//)").unwrap());
+
+/// An internal link whose target does not exist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokenLink {
+    /// Source file of the linking page, relative to the content directory
+    pub source: PathBuf,
+    /// The link target as written
+    pub target: String,
+    pub reason: BrokenLinkReason,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BrokenLinkReason {
+    /// No content file for a `.md` link, or no page or static file for a relative path
+    MissingTarget,
+    /// The target page exists but has no heading with this id
+    MissingAnchor(String),
+    /// The path climbs above the content directory
+    OutsideContent,
+}
+
+impl fmt::Display for BrokenLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match &self.reason {
+            BrokenLinkReason::MissingTarget => "target not found".to_string(),
+            BrokenLinkReason::MissingAnchor(anchor) => format!("no heading with id `{anchor}`"),
+            BrokenLinkReason::OutsideContent => "points outside the content directory".to_string(),
+        };
+        write!(
+            f,
+            "{}: broken link `{}`: {reason}",
+            self.source.display(),
+            self.target
+        )
+    }
+}
+
+/// What the link pass knows about one page.
+struct Target {
+    url: String,
+    anchors: BTreeSet<String>,
+}
+
+/// Rewrite internal links and images in every page through the route table and
+/// report the ones that point nowhere.
+///
+/// - `./installation.md#setup` (relative to the page's source file) or
+///   `/guide/installation.md` (relative to the content directory) becomes the
+///   target's route under `base_url`
+/// - other relative paths resolve the same way against pages, then files next to
+///   the pages in `content_dir` and then `static_dir` files, because a page is
+///   written one directory deeper than its source
+/// - `#anchor` fragments are checked against the target page's TOC ids
+///
+/// External URLs and absolute paths that are not `.md` files are left as written.
+pub fn resolve_links(
+    graph: &mut SiteGraph,
+    content_dir: &Path,
+    static_dir: &Path,
+    base_url: &str,
+) -> Vec<BrokenLink> {
+    let base = format!("{}/", base_url.trim_end_matches('/'));
+
+    let by_source: BTreeMap<PathBuf, Target> = graph
+        .pages
+        .iter()
+        .map(|page| {
+            let target = Target {
+                url: page.route.path().to_string(),
+                anchors: page.toc.iter().map(|item| item.id.clone()).collect(),
+            };
+            (page.route.source.clone(), target)
+        })
+        .collect();
+    let by_url: BTreeMap<&str, &Target> = by_source
+        .values()
+        .map(|target| (target.url.as_str(), target))
+        .collect();
+
+    let mut broken = Vec::new();
+    for page in &mut graph.pages {
+        let source = page.route.source.clone();
+        let own_anchors: BTreeSet<&str> = page.toc.iter().map(|item| item.id.as_str()).collect();
+        let mut report = |target: &str, reason| {
+            broken.push(BrokenLink {
+                source: source.clone(),
+                target: target.to_string(),
+                reason,
+            })
+        };
+
+        let html = LINK_ATTR_RE.replace_all(&page.html, |caps: &regex::Captures| {
+            let attr = &caps[1];
+            let (raw, quote) = match caps.get(2) {
+                Some(value) => (value.as_str(), '"'),
+                None => (&caps[3], '\''),
+            };
+            let link = Link::split(raw);
+
+            if EXTERNAL_RE.is_match(raw) {
+                return caps[0].to_string();
+            }
+
+            // `#anchor` on the same page
+            if link.path.is_empty() {
+                if let Some(fragment) = link.fragment {
+                    let anchor = percent_decode(fragment);
+                    if !anchor.is_empty() && !own_anchors.contains(anchor.as_str()) {
+                        report(raw, BrokenLinkReason::MissingAnchor(anchor));
+                    }
+                }
+                return caps[0].to_string();
+            }
+
+            let path = percent_decode(link.path);
+            let is_markdown = path.ends_with(".md");
+            if path.starts_with('/') && !is_markdown {
+                return caps[0].to_string();
+            }
+
+            let Some(resolved) = resolve_path(&source, &path) else {
+                report(raw, BrokenLinkReason::OutsideContent);
+                return caps[0].to_string();
+            };
+
+            let target = if is_markdown {
+                by_source.get(&resolved)
+            } else {
+                let url = format!("/{}", resolved.to_string_lossy().replace('\\', "/"));
+                by_url
+                    .get(url.as_str())
+                    .or_else(|| by_url.get(format!("{url}/").as_str()))
+                    .copied()
+            };
+
+            let href = match target {
+                Some(target) => {
+                    if let Some(fragment) = link.fragment {
+                        let anchor = percent_decode(fragment);
+                        if !anchor.is_empty() && !target.anchors.contains(&anchor) {
+                            report(raw, BrokenLinkReason::MissingAnchor(anchor));
+                        }
+                    }
+                    format!("{base}{}", target.url.trim_start_matches('/'))
+                }
+                None if !is_markdown
+                    && (content_dir.join(&resolved).is_file()
+                        || static_dir.join(&resolved).is_file()) =>
+                {
+                    format!("{base}{}", resolved.to_string_lossy().replace('\\', "/"))
+                }
+                None => {
+                    report(raw, BrokenLinkReason::MissingTarget);
+                    return caps[0].to_string();
+                }
+            };
+
+            let href = escape_html(&href).replace('\'', "&#39;");
+            format!("{attr}={quote}{href}{}{quote}", link.suffix)
+        });
+
+        if let std::borrow::Cow::Owned(html) = html {
+            page.html = html;
+        }
+    }
+
+    broken
+}
+
+/// A link target split into its path and the `?query` / `#fragment` that follow it.
+struct Link<'a> {
+    path: &'a str,
+    fragment: Option<&'a str>,
+    /// Everything after the path, kept verbatim when the path is rewritten
+    suffix: &'a str,
+}
+
+impl<'a> Link<'a> {
+    fn split(raw: &'a str) -> Self {
+        let end = raw.find(['?', '#']).unwrap_or(raw.len());
+        Self {
+            path: &raw[..end],
+            fragment: raw.split_once('#').map(|(_, fragment)| fragment),
+            suffix: &raw[end..],
+        }
+    }
+}
+
+/// Resolve `path` against the directory of `source` (or the content root for
+/// `/`-prefixed paths) into a normalized content-relative path.
+/// Returns `None` when `..` climbs above the content root.
+fn resolve_path(source: &Path, path: &str) -> Option<PathBuf> {
+    let (start, path) = match path.strip_prefix('/') {
+        Some(rest) => (Path::new(""), rest),
+        None => (source.parent().unwrap_or(Path::new("")), path),
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in start.join(path).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::ParentDir if !resolved.pop() => return None,
+            _ => {}
+        }
+    }
+    Some(resolved)
+}
+
+/// Decode `%XX` escapes; comrak percent-encodes non-ASCII link targets.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::frontmatter::Frontmatter;
+    use crate::content::page::{Page, TocItem};
+    use crate::site::route::resolve_route;
+
+    fn page(source: &str, html: &str, anchors: &[&str]) -> Page {
+        Page {
+            route: resolve_route(Path::new("content"), &Path::new("content").join(source)),
+            frontmatter: Frontmatter::default(),
+            html: html.to_string(),
+            toc: anchors
+                .iter()
+                .map(|id| TocItem {
+                    id: id.to_string(),
+                    text: id.to_string(),
+                    level: 2,
+                })
+                .collect(),
+            prev: None,
+            next: None,
+        }
+    }
+
+    fn graph(pages: Vec<Page>) -> SiteGraph {
+        SiteGraph {
+            pages,
+            sidebar: vec![],
+            nav: vec![],
+        }
+    }
+
+    #[test]
+    fn rewrites_relative_markdown_links_to_routes() {
+        let mut graph = graph(vec![
+            page(
+                "guide/intro.md",
+                r#"<a href="./installation.md#setup">a</a> <a href="../index.md">b</a> <a href="/guide/installation.md?x=1">c</a>"#,
+                &[],
+            ),
+            page("guide/installation.md", "", &["setup"]),
+            page("index.md", "", &[]),
+        ]);
+
+        let broken = resolve_links(
+            &mut graph,
+            Path::new("content"),
+            Path::new("static"),
+            "/docs",
+        );
+        assert!(broken.is_empty(), "{broken:?}");
+        assert_eq!(
+            graph.pages[0].html,
+            r#"<a href="/docs/guide/installation#setup">a</a> <a href="/docs/">b</a> <a href="/docs/guide/installation?x=1">c</a>"#
+        );
+    }
+
+    #[test]
+    fn reports_missing_pages_and_anchors() {
+        let mut graph = graph(vec![
+            page(
+                "guide/intro.md",
+                r##"<a href="./missing.md">a</a> <a href="./setup.md#nope">b</a> <a href="#here">c</a> <a href="#gone">d</a> <a href="../../up.md">e</a>"##,
+                &["here"],
+            ),
+            page("guide/setup.md", "", &["install"]),
+        ]);
+
+        let broken = resolve_links(&mut graph, Path::new("content"), Path::new("static"), "/");
+        let reasons: Vec<_> = broken.iter().map(|b| b.reason.clone()).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                BrokenLinkReason::MissingTarget,
+                BrokenLinkReason::MissingAnchor("nope".to_string()),
+                BrokenLinkReason::MissingAnchor("gone".to_string()),
+                BrokenLinkReason::OutsideContent,
+            ]
+        );
+        assert_eq!(
+            broken[0].to_string(),
+            "guide/intro.md: broken link `./missing.md`: target not found"
+        );
+        // Broken links are left as written
+        assert!(graph.pages[0].html.contains(r#"href="./missing.md""#));
+    }
+
+    #[test]
+    fn resolves_relative_images_against_static_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("guide")).unwrap();
+        std::fs::write(tmp.path().join("guide/diagram.png"), "png").unwrap();
+
+        let mut graph = graph(vec![page(
+            "guide/intro.md",
+            r#"<img src="diagram.png" alt=""> <img src="/logo.png"> <img src="https://example.com/a.png"> <img src="./none.png">"#,
+            &[],
+        )]);
+
+        let broken = resolve_links(&mut graph, Path::new("content"), tmp.path(), "/");
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].target, "./none.png");
+        assert!(graph.pages[0]
+            .html
+            .starts_with(r#"<img src="/guide/diagram.png" alt=""> <img src="/logo.png"> <img src="https://example.com/a.png">"#));
+    }
+
+    #[test]
+    fn resolves_files_next_to_pages_and_single_quotes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let content = tmp.path().join("content");
+        std::fs::create_dir_all(content.join("guide")).unwrap();
+        std::fs::write(content.join("guide/diagram.png"), "png").unwrap();
+
+        let mut graph = graph(vec![
+            page(
+                "guide/intro.md",
+                r#"<img src='./diagram.png'> <a href='./setup.md'>a</a> <a href='./gone.md'>b</a>"#,
+                &[],
+            ),
+            page("guide/setup.md", "", &[]),
+        ]);
+
+        let broken = resolve_links(&mut graph, &content, &tmp.path().join("static"), "/");
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].target, "./gone.md");
+        assert!(graph.pages[0]
+            .html
+            .starts_with(r#"<img src='/guide/diagram.png'> <a href='/guide/setup'>a</a>"#));
+    }
+
+    #[test]
+    fn decodes_percent_encoded_targets() {
+        let mut graph = graph(vec![
+            page(
+                "index.md",
+                r#"<a href="./%EC%84%A4%EC%B9%98.md#%EC%8B%9C%EC%9E%91">a</a>"#,
+                &[],
+            ),
+            page("설치.md", "", &["시작"]),
+        ]);
+
+        let broken = resolve_links(&mut graph, Path::new("content"), Path::new("static"), "/");
+        assert!(broken.is_empty(), "{broken:?}");
+        assert_eq!(
+            graph.pages[0].html,
+            r#"<a href="/설치#%EC%8B%9C%EC%9E%91">a</a>"#
+        );
+    }
+}
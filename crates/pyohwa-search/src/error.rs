@@ -4,4 +4,7 @@ use thiserror::Error;
 pub enum SearchError {
     #[error("search index serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
+
+    #[error("search index is {size} bytes, over the [search] max_index_size of {limit} bytes")]
+    TooLarge { size: u64, limit: u64 },
 }
@@ -1,33 +1,130 @@
-use crate::tokenizer::{strip_html, truncate_content};
-use crate::{PageData, SearchEntry};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::inverted::Field;
+use crate::tokenizer::{decode_html_entities, strip_html, tokenize, truncate_content};
+use crate::{PageData, SearchEntry, TocEntry};
+
+/// Opening heading tags with the ids the build gives them.
+static HEADING_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<h[1-6] id="([^"]*)">"#).unwrap());
+/// A `<pre>` block, i.e. highlighted or plain code.
+static PRE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<pre[\s>].*?</pre>").unwrap());
 
 /// Options for controlling search index generation.
 pub struct IndexOptions {
+    /// Characters of text kept per entry
     pub max_content_length: usize,
+    /// How much a match in each field counts, in `Field::ALL` order
+    pub field_weights: [f32; Field::COUNT],
+    /// Leave `<pre>` blocks, i.e. code, out of the indexed text
+    pub exclude_code: bool,
+    /// Tokens left out of the index, and of queries by the client, e.g. `the`.
+    /// Matched against whole tokens, so they must be normalized (see
+    /// `tokenizer::normalize`)
+    pub stop_words: BTreeSet<String>,
 }
 
 impl Default for IndexOptions {
     fn default() -> Self {
         Self {
             max_content_length: 5000,
+            field_weights: [4.0, 2.0, 2.0, 1.0],
+            exclude_code: false,
+            stop_words: BTreeSet::new(),
         }
     }
 }
 
-/// Extract indexable content from a page, stripping HTML and truncating.
-pub fn extract_indexable_content(page: &PageData, options: &IndexOptions) -> SearchEntry {
-    let plain_text = strip_html(&page.html);
-    let content = truncate_content(&plain_text, options.max_content_length);
-
-    SearchEntry {
-        id: page.url.clone(),
-        url: page.url.clone(),
-        title: page.title.clone(),
-        description: page.description.clone(),
-        content,
-        tags: page.tags.clone(),
-        date: page.date.clone(),
+/// Split a page into one entry per section, stripping HTML and truncating each
+/// section's text.
+///
+/// Sections start at the headings of the page's table of contents and link to
+/// their anchor. The first entry holds the text before the first heading and
+/// links to the page itself; a level-1 heading repeating the page title, as
+/// most pages start with, belongs to it.
+pub fn extract_indexable_content(page: &PageData, options: &IndexOptions) -> Vec<SearchEntry> {
+    let toc: HashMap<&str, &TocEntry> = page.toc.iter().map(|h| (h.id.as_str(), h)).collect();
+
+    // Each section's heading and where its HTML starts
+    let mut starts: Vec<(Option<&TocEntry>, usize)> = vec![(None, 0)];
+    for caps in HEADING_RE.captures_iter(&page.html) {
+        let id = decode_html_entities(&caps[1]);
+        let Some(&heading) = toc.get(id.as_str()) else {
+            continue;
+        };
+        if heading.level == 1 && heading.text == page.title {
+            continue;
+        }
+        starts.push((Some(heading), caps.get(0).map_or(0, |m| m.start())));
     }
+
+    let mut ancestors: Vec<&TocEntry> = Vec::new();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &(heading, start))| {
+            let end = starts.get(i + 1).map_or(page.html.len(), |&(_, end)| end);
+            let mut html = &page.html[start..end];
+            let mut breadcrumb = vec![page.title.clone()];
+            let (url, title) = match heading {
+                Some(heading) => {
+                    // The heading is the entry's title, not part of its text
+                    if let Some(close) = html.find("</h") {
+                        html = html[close..].split_once('>').map_or("", |(_, rest)| rest);
+                    }
+                    ancestors.retain(|a| a.level < heading.level);
+                    ancestors.push(heading);
+                    breadcrumb.extend(ancestors.iter().map(|a| a.text.clone()));
+                    (format!("{}#{}", page.url, heading.id), heading.text.clone())
+                }
+                None => (page.url.clone(), page.title.clone()),
+            };
+            let text = if options.exclude_code {
+                strip_html(&PRE_RE.replace_all(html, " "))
+            } else {
+                strip_html(html)
+            };
+            let content = truncate_content(&text, options.max_content_length);
+
+            SearchEntry {
+                id: url.clone(),
+                url,
+                title,
+                breadcrumb,
+                description: page.description.clone(),
+                content,
+                tags: page.tags.clone(),
+                date: page.date.clone(),
+                fields: page.fields.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Tokenize the searchable fields of an entry, in `Field::ALL` order, without
+/// stop words. The headings are the sections above the entry's own; the body
+/// is its text, and the page description for the entry at the top of the page.
+pub fn field_tokens(entry: &SearchEntry, options: &IndexOptions) -> [Vec<String>; Field::COUNT] {
+    let ancestors = &entry.breadcrumb[..entry.breadcrumb.len().saturating_sub(1)];
+    let body = if entry.breadcrumb.len() > 1 {
+        entry.content.clone()
+    } else {
+        format!("{} {}", entry.description, entry.content)
+    };
+    [
+        entry.title.clone(),
+        ancestors.join(" "),
+        entry.tags.join(" "),
+        body,
+    ]
+    .map(|text| {
+        let mut tokens = tokenize(&text);
+        tokens.retain(|token| !options.stop_words.contains(token));
+        tokens
+    })
 }
 
 #[cfg(test)]
@@ -43,13 +140,18 @@ mod tests {
             tags: vec!["test".to_string()],
             date: Some("2024-01-01".to_string()),
             draft: false,
+            fields: Default::default(),
+            toc: vec![],
+            boost: 1.0,
         }
     }
 
     #[test]
     fn test_basic_extraction() {
         let page = make_page("Hello", "<p>Hello <strong>World</strong></p>");
-        let entry = extract_indexable_content(&page, &IndexOptions::default());
+        let [entry] = &extract_indexable_content(&page, &IndexOptions::default())[..] else {
+            panic!("expected one entry");
+        };
         assert_eq!(entry.title, "Hello");
         assert_eq!(entry.content, "Hello World");
         assert_eq!(entry.url, "/hello");
@@ -61,9 +163,10 @@ mod tests {
         let page = make_page("Long", &long_html);
         let options = IndexOptions {
             max_content_length: 50,
+            ..Default::default()
         };
-        let entry = extract_indexable_content(&page, &options);
-        assert!(entry.content.len() <= 55); // 50 + "..."
+        let entries = extract_indexable_content(&page, &options);
+        assert!(entries[0].content.len() <= 55); // 50 + "..."
     }
 
     #[test]
@@ -76,8 +179,102 @@ mod tests {
             tags: vec![],
             date: None,
             draft: false,
+            fields: Default::default(),
+            toc: vec![],
+            boost: 1.0,
         };
-        let entry = extract_indexable_content(&page, &IndexOptions::default());
-        assert!(entry.description.is_empty());
+        let entries = extract_indexable_content(&page, &IndexOptions::default());
+        assert!(entries[0].description.is_empty());
+    }
+
+    fn heading(id: &str, text: &str, level: u8) -> TocEntry {
+        TocEntry {
+            id: id.to_string(),
+            text: text.to_string(),
+            level,
+        }
+    }
+
+    #[test]
+    fn test_sections_split_at_headings() {
+        let mut page = make_page(
+            "Guide",
+            "<h1 id=\"guide\">Guide</h1><p>Intro text.</p>\
+             <h2 id=\"install\">Install</h2><p>Run the installer.</p>\
+             <h3 id=\"linux\">Linux</h3><p>Use apt.</p>\
+             <h2 id=\"q&amp;a\">Q&amp;A</h2><p>Ask away.</p>",
+        );
+        page.toc = vec![
+            heading("guide", "Guide", 1),
+            heading("install", "Install", 2),
+            heading("linux", "Linux", 3),
+            heading("q&a", "Q&A", 2),
+        ];
+
+        let entries = extract_indexable_content(&page, &IndexOptions::default());
+        let summary: Vec<_> = entries
+            .iter()
+            .map(|e| (e.url.as_str(), e.breadcrumb.join(" > "), e.content.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("/guide", "Guide".to_string(), "Guide Intro text."),
+                (
+                    "/guide#install",
+                    "Guide > Install".to_string(),
+                    "Run the installer."
+                ),
+                (
+                    "/guide#linux",
+                    "Guide > Install > Linux".to_string(),
+                    "Use apt."
+                ),
+                ("/guide#q&a", "Guide > Q&A".to_string(), "Ask away."),
+            ]
+        );
+        assert_eq!(entries[2].title, "Linux");
+    }
+
+    #[test]
+    fn test_field_tokens() {
+        let mut page = make_page(
+            "설치 Guide",
+            "<p>소개</p><h2 id=\"setup\">Setup <code>CLI</code></h2><p>패키지를 설치합니다</p>",
+        );
+        page.tags = vec!["Beginner".to_string()];
+        page.toc = vec![heading("setup", "Setup CLI", 2)];
+        let options = IndexOptions::default();
+        let entries = extract_indexable_content(&page, &options);
+
+        let [title, headings, tags, body] = field_tokens(&entries[0], &options);
+        assert_eq!(title, ["설치", "guide"]);
+        assert!(headings.is_empty());
+        assert_eq!(tags, ["beginner"]);
+        assert_eq!(body, ["about", "설치", "guide", "소개"]);
+
+        let [title, headings, _, body] = field_tokens(&entries[1], &options);
+        assert_eq!(title, ["setup", "cli"]);
+        assert_eq!(headings, ["설치", "guide"]);
+        assert!(body.contains(&"키지".to_string()));
+        assert!(!body.contains(&"about".to_string()));
+    }
+
+    #[test]
+    fn test_exclude_code_and_stop_words() {
+        let page = make_page(
+            "Build",
+            "<p>Run the build.</p><pre class=\"highlight\"><code>cargo build</code></pre>",
+        );
+        let options = IndexOptions {
+            exclude_code: true,
+            stop_words: BTreeSet::from(["the".to_string()]),
+            ..Default::default()
+        };
+        let entries = extract_indexable_content(&page, &options);
+        assert_eq!(entries[0].content, "Run the build.");
+
+        let [_, _, _, body] = field_tokens(&entries[0], &options);
+        assert_eq!(body, ["about", "build", "run", "build"]);
     }
 }
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+/// A part of a page that terms are found in. Matches in some fields count
+/// for more than in others (see `IndexOptions::field_weights`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title = 0,
+    Headings = 1,
+    Tags = 2,
+    /// Description and text
+    Body = 3,
+}
+
+impl Field {
+    pub const COUNT: usize = 4;
+
+    /// All fields, in the order used by postings, weights and lengths.
+    pub const ALL: [Field; Field::COUNT] =
+        [Field::Title, Field::Headings, Field::Tags, Field::Body];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::Title => "title",
+            Field::Headings => "headings",
+            Field::Tags => "tags",
+            Field::Body => "body",
+        }
+    }
+}
+
+/// Where a term occurs: one field of one page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Posting {
+    /// Index into `SearchIndex::pages`
+    pub page: u32,
+    pub field: Field,
+    /// Token count of the field, which BM25 normalizes the term frequency by;
+    /// kept here so a shard of postings can be scored on its own
+    pub length: u32,
+    /// Token positions in the field; their count is the term frequency
+    pub positions: Vec<u32>,
+}
+
+/// Serialized as a flat array, `[page, field, length, position, ...]`, which
+/// keeps the index compact: most postings have one or two positions.
+impl Serialize for Posting {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(3 + self.positions.len()))?;
+        seq.serialize_element(&self.page)?;
+        seq.serialize_element(&(self.field as u32))?;
+        seq.serialize_element(&self.length)?;
+        for position in &self.positions {
+            seq.serialize_element(position)?;
+        }
+        seq.end()
+    }
+}
+
+/// Term to postings, with the field lengths BM25 normalizes term frequencies by.
+#[derive(Debug, Default)]
+pub struct InvertedIndex {
+    /// Sorted by term, so the client can walk the vocabulary for prefix and
+    /// typo-tolerant lookups
+    pub terms: BTreeMap<String, Vec<Posting>>,
+    /// Token count of each field, per page
+    pub lengths: Vec<[u32; Field::COUNT]>,
+}
+
+impl InvertedIndex {
+    /// Add the next page, given the tokens of each of its fields in `Field::ALL`
+    /// order. Returns the page's index.
+    pub fn add(&mut self, fields: &[Vec<String>; Field::COUNT]) -> u32 {
+        let page = self.lengths.len() as u32;
+        for (field, tokens) in Field::ALL.into_iter().zip(fields) {
+            let mut positions: BTreeMap<&str, Vec<u32>> = BTreeMap::new();
+            for (position, token) in tokens.iter().enumerate() {
+                positions.entry(token).or_default().push(position as u32);
+            }
+            for (term, positions) in positions {
+                self.terms
+                    .entry(term.to_string())
+                    .or_default()
+                    .push(Posting {
+                        page,
+                        field,
+                        length: tokens.len() as u32,
+                        positions,
+                    });
+            }
+        }
+        self.lengths
+            .push(fields.each_ref().map(|tokens| tokens.len() as u32));
+        page
+    }
+
+    /// Mean token count of each field across pages.
+    pub fn average_lengths(&self) -> [f32; Field::COUNT] {
+        let pages = self.lengths.len().max(1) as f32;
+        std::array::from_fn(|field| {
+            let total: u32 = self.lengths.iter().map(|lengths| lengths[field]).sum();
+            total as f32 / pages
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(text: &str) -> Vec<String> {
+        text.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn postings_record_page_field_and_positions() {
+        let mut index = InvertedIndex::default();
+        index.add(&[
+            tokens("intro"),
+            vec![],
+            tokens("guide"),
+            tokens("the intro to the guide"),
+        ]);
+        index.add(&[tokens("guide"), tokens("guide"), vec![], vec![]]);
+
+        assert_eq!(
+            index.terms["intro"],
+            [
+                Posting {
+                    page: 0,
+                    field: Field::Title,
+                    length: 1,
+                    positions: vec![0]
+                },
+                Posting {
+                    page: 0,
+                    field: Field::Body,
+                    length: 5,
+                    positions: vec![1]
+                }
+            ]
+        );
+        assert_eq!(index.terms["the"][0].positions, [0, 3]);
+        assert_eq!(index.terms["guide"].len(), 4);
+        assert_eq!(index.lengths, [[1, 0, 1, 5], [1, 1, 0, 0]]);
+        assert_eq!(index.average_lengths(), [1.0, 0.5, 0.5, 2.5]);
+    }
+
+    #[test]
+    fn postings_serialize_as_flat_arrays() {
+        let posting = Posting {
+            page: 7,
+            field: Field::Body,
+            length: 40,
+            positions: vec![2, 9],
+        };
+        assert_eq!(serde_json::to_string(&posting).unwrap(), "[7,3,40,2,9]");
+    }
+}
@@ -1,10 +1,16 @@
 pub mod error;
 pub mod indexer;
+pub mod inverted;
+pub mod shard;
 pub mod tokenizer;
 
+use std::collections::BTreeMap;
+
 use error::SearchError;
-use indexer::{extract_indexable_content, IndexOptions};
+use indexer::{extract_indexable_content, field_tokens, IndexOptions};
+use inverted::{Field, InvertedIndex, Posting};
 use serde::Serialize;
+use shard::IndexFile;
 
 /// Independent page data type to avoid circular dependency with pyohwa-core.
 /// pyohwa-core converts its own Page type into this before calling search APIs.
@@ -16,41 +22,105 @@ pub struct PageData {
     pub tags: Vec<String>,
     pub date: Option<String>,
     pub draft: bool,
+    /// Custom frontmatter fields the client can filter on, e.g. `status: [beta]`
+    pub fields: BTreeMap<String, Vec<String>>,
+    /// The page's headings, where its sections start
+    pub toc: Vec<TocEntry>,
+    /// Multiplies the scores of the page's entries, from `search_boost`
+    pub boost: f32,
 }
 
-/// The complete search index containing all searchable pages.
-#[derive(Debug, Serialize)]
+/// A heading of a page, mirroring pyohwa-core's `TocItem`.
+pub struct TocEntry {
+    /// The heading's `id` attribute in the page HTML
+    pub id: String,
+    pub text: String,
+    pub level: u8,
+}
+
+/// The complete search index: the searchable page sections and an inverted index
+/// over their fields, which the client ranks with BM25. Written out in shards
+/// the client loads as a query needs them (see `shard::shard_index`).
+///
+/// `weights` and `average_lengths` follow `fields`; a posting is
+/// `[page, field, length, positions...]` (see `inverted::Posting`).
+#[derive(Debug)]
 pub struct SearchIndex {
+    pub fields: [&'static str; Field::COUNT],
+    pub weights: [f32; Field::COUNT],
+    pub average_lengths: [f32; Field::COUNT],
+    /// One entry per page section, in page order
     pub pages: Vec<SearchEntry>,
+    pub terms: BTreeMap<String, Vec<Posting>>,
+    /// `key:value` of each filter field value, lowercased, to the entries with it
+    pub filters: BTreeMap<String, Vec<u32>>,
+    /// Score multipliers of the entries of boosted pages
+    pub boosts: BTreeMap<u32, f32>,
+    /// Tokens left out of the index, which the client drops from queries
+    pub stop_words: Vec<String>,
 }
 
-/// A single entry in the search index.
+/// A single entry in the search index: a page, or a section of one.
 #[derive(Debug, Clone, Serialize)]
 pub struct SearchEntry {
     pub id: String,
+    /// The page URL, with the section's `#anchor`
     pub url: String,
+    /// The section heading, or the page title
     pub title: String,
+    /// Page title, then the headings down to this section's own
+    pub breadcrumb: Vec<String>,
     pub description: String,
     pub content: String,
     pub tags: Vec<String>,
     pub date: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, Vec<String>>,
 }
 
 /// Build a search index from a collection of pages.
 /// Filters out draft pages and processes HTML content.
-pub fn build_search_index(pages: &[PageData]) -> SearchIndex {
-    let options = IndexOptions::default();
+pub fn build_search_index(pages: &[PageData], options: &IndexOptions) -> SearchIndex {
+    let mut inverted = InvertedIndex::default();
+    let mut filters: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+    let mut boosts = BTreeMap::new();
     let entries = pages
         .iter()
         .filter(|p| !p.draft)
-        .map(|p| extract_indexable_content(p, &options))
+        .flat_map(|p| {
+            extract_indexable_content(p, options)
+                .into_iter()
+                .map(|entry| (entry, p.boost))
+        })
+        .map(|(entry, boost)| {
+            let page = inverted.add(&field_tokens(&entry, options));
+            if boost != 1.0 {
+                boosts.insert(page, boost);
+            }
+            for (key, values) in &entry.fields {
+                for value in values {
+                    let filter = format!("{key}:{}", value.to_lowercase());
+                    filters.entry(filter).or_default().push(page);
+                }
+            }
+            entry
+        })
         .collect();
-    SearchIndex { pages: entries }
+    SearchIndex {
+        fields: Field::ALL.map(Field::name),
+        weights: options.field_weights,
+        average_lengths: inverted.average_lengths(),
+        pages: entries,
+        terms: inverted.terms,
+        filters,
+        boosts,
+        stop_words: options.stop_words.iter().cloned().collect(),
+    }
 }
 
-/// Serialize the search index to a JSON string.
-pub fn serialize_search_index(index: &SearchIndex) -> Result<String, SearchError> {
-    serde_json::to_string(index).map_err(SearchError::Serialization)
+/// Serialize the search index as a manifest and shards.
+pub fn serialize_search_index(index: &SearchIndex) -> Result<Vec<IndexFile>, SearchError> {
+    shard::shard_index(index)
 }
 
 #[cfg(test)]
@@ -66,13 +136,42 @@ mod tests {
             tags: vec!["docs".to_string()],
             date: Some("2024-01-01".to_string()),
             draft,
+            fields: BTreeMap::new(),
+            toc: vec![],
+            boost: 1.0,
+        }
+    }
+
+    /// The manifest, with the entries and terms of every shard merged back in.
+    fn serialize(index: &SearchIndex) -> serde_json::Value {
+        let files = serialize_search_index(index).unwrap();
+        let parse = |path: &str| -> serde_json::Value {
+            let file = files.iter().find(|file| file.path == path).unwrap();
+            serde_json::from_slice(&file.contents).unwrap()
+        };
+        let mut parsed = parse(shard::MANIFEST_PATH);
+        let mut pages = Vec::new();
+        for path in parsed["page_shards"].as_array().unwrap() {
+            pages.extend(parse(path.as_str().unwrap()).as_array().unwrap().clone());
+        }
+        let mut terms = serde_json::Map::new();
+        for path in parsed["term_shards"].as_object().unwrap().values() {
+            terms.extend(
+                parse(path.as_str().unwrap())["terms"]
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            );
         }
+        parsed["pages"] = pages.into();
+        parsed["terms"] = terms.into();
+        parsed
     }
 
     #[test]
     fn test_build_search_index_basic() {
         let pages = vec![make_page("Hello", false), make_page("World", false)];
-        let index = build_search_index(&pages);
+        let index = build_search_index(&pages, &IndexOptions::default());
         assert_eq!(index.pages.len(), 2);
         assert_eq!(index.pages[0].title, "Hello");
         assert_eq!(index.pages[1].title, "World");
@@ -85,7 +184,7 @@ mod tests {
             make_page("Draft", true),
             make_page("Another", false),
         ];
-        let index = build_search_index(&pages);
+        let index = build_search_index(&pages, &IndexOptions::default());
         assert_eq!(index.pages.len(), 2);
         assert!(index.pages.iter().all(|e| e.title != "Draft"));
     }
@@ -93,25 +192,23 @@ mod tests {
     #[test]
     fn test_build_search_index_empty() {
         let pages: Vec<PageData> = vec![];
-        let index = build_search_index(&pages);
+        let index = build_search_index(&pages, &IndexOptions::default());
         assert!(index.pages.is_empty());
     }
 
     #[test]
     fn test_serialize_produces_valid_json() {
         let pages = vec![make_page("Test", false)];
-        let index = build_search_index(&pages);
-        let json = serialize_search_index(&index).unwrap();
-        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let index = build_search_index(&pages, &IndexOptions::default());
+        let parsed = serialize(&index);
         assert!(parsed.get("pages").unwrap().is_array());
     }
 
     #[test]
     fn test_search_entry_schema() {
         let pages = vec![make_page("Schema Test", false)];
-        let index = build_search_index(&pages);
-        let json = serialize_search_index(&index).unwrap();
-        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let index = build_search_index(&pages, &IndexOptions::default());
+        let parsed = serialize(&index);
         let entry = &parsed["pages"][0];
         assert!(entry.get("id").is_some());
         assert!(entry.get("url").is_some());
@@ -120,5 +217,56 @@ mod tests {
         assert!(entry.get("content").is_some());
         assert!(entry.get("tags").is_some());
         assert!(entry.get("date").is_some());
+        assert!(entry.get("fields").is_none());
+        assert_eq!(
+            parsed["fields"],
+            serde_json::json!(["title", "headings", "tags", "body"])
+        );
+    }
+
+    #[test]
+    fn test_inverted_index_skips_drafts() {
+        let pages = vec![make_page("Hello", false), make_page("Draft", true)];
+        let index = build_search_index(&pages, &IndexOptions::default());
+        let parsed = serialize(&index);
+        // "Hello" in the title, and in the body as "About Hello" and "Hello content"
+        assert_eq!(
+            parsed["terms"]["hello"],
+            serde_json::json!([[0, 0, 1, 0], [0, 3, 4, 1, 2]])
+        );
+        assert!(parsed["terms"].get("draft").is_none());
+        assert_eq!(
+            parsed["average_lengths"],
+            serde_json::json!([1.0, 0.0, 1.0, 4.0])
+        );
+    }
+
+    #[test]
+    fn test_filter_fields_serialized() {
+        let mut page = make_page("Beta", false);
+        page.fields
+            .insert("status".to_string(), vec!["beta".to_string()]);
+        let index = build_search_index(&[page], &IndexOptions::default());
+        let parsed = serialize(&index);
+        assert_eq!(
+            parsed["pages"][0]["fields"],
+            serde_json::json!({ "status": ["beta"] })
+        );
+        assert_eq!(index.filters["status:beta"], [0]);
+    }
+
+    #[test]
+    fn test_boosts_and_stop_words_in_manifest() {
+        let mut boosted = make_page("Boosted", false);
+        boosted.boost = 2.0;
+        let options = IndexOptions {
+            stop_words: ["about".to_string()].into(),
+            ..Default::default()
+        };
+        let index = build_search_index(&[make_page("Plain", false), boosted], &options);
+        let parsed = serialize(&index);
+        assert_eq!(parsed["boosts"], serde_json::json!({ "1": 2.0 }));
+        assert_eq!(parsed["stop_words"], serde_json::json!(["about"]));
+        assert!(parsed["terms"].get("about").is_none());
     }
 }
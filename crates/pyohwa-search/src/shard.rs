@@ -0,0 +1,300 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::SearchError;
+use crate::inverted::{Field, Posting};
+use crate::SearchIndex;
+
+/// The file the client fetches first, relative to the output directory.
+pub const MANIFEST_PATH: &str = "search-index.json";
+
+/// Directory of the shards, relative to the output directory. Under `assets/`
+/// so it cannot collide with the output of a content page like `search.md`.
+pub const SHARD_DIR: &str = "assets/search";
+
+/// File name prefixes of the shards in `SHARD_DIR`.
+pub const SHARD_PREFIXES: [&str; 2] = ["terms-", "pages-"];
+
+/// Entries per page shard.
+pub const PAGE_SHARD_SIZE: usize = 100;
+
+/// A file of the sharded index.
+#[derive(Debug)]
+pub struct IndexFile {
+    /// Path relative to the output directory
+    pub path: String,
+    pub contents: Vec<u8>,
+}
+
+impl IndexFile {
+    /// The contents gzip-compressed, for a `.gz` sibling that servers can send
+    /// as is.
+    pub fn gzip(&self) -> std::io::Result<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&self.contents)?;
+        encoder.finish()
+    }
+
+    /// The contents brotli-compressed at the highest quality, for a `.br` sibling.
+    pub fn brotli(&self) -> std::io::Result<Vec<u8>> {
+        let mut encoder = brotli::CompressorWriter::new(Vec::new(), 4096, 11, 22);
+        encoder.write_all(&self.contents)?;
+        encoder.flush()?;
+        Ok(encoder.into_inner())
+    }
+}
+
+/// `search-index.json`: everything the client needs to know which shards to
+/// fetch for a query.
+#[derive(Serialize)]
+struct Manifest<'a> {
+    fields: [&'static str; Field::COUNT],
+    weights: [f32; Field::COUNT],
+    average_lengths: [f32; Field::COUNT],
+    /// Number of entries
+    count: usize,
+    /// Frontmatter fields usable as `key:value` filters
+    filters: Vec<&'a str>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    boosts: &'a BTreeMap<u32, f32>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    stop_words: &'a [String],
+    /// Shard key (see `shard_key`) to term shard
+    term_shards: BTreeMap<String, String>,
+    page_shard_size: usize,
+    /// Entries `i * page_shard_size ..` are in `page_shards[i]`
+    page_shards: Vec<String>,
+}
+
+/// The postings of the terms, and the entries of the filter values, with one
+/// shard key.
+#[derive(Default, Serialize)]
+struct TermShard<'a> {
+    terms: BTreeMap<&'a str, &'a [Posting]>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    filters: BTreeMap<&'a str, &'a [u32]>,
+}
+
+/// Split the index into a small manifest, term shards holding the postings of
+/// the terms with one shard key, and page shards of `PAGE_SHARD_SIZE` entries.
+///
+/// ```text
+/// search-index.json                     manifest
+/// assets/search/terms-in.<hash>.json    { "terms": { "install": [[0, 1, 2, 0], ...] } }
+/// assets/search/terms-u193.<hash>.json  Hangul terms from U+C100 to U+C1FF
+/// assets/search/pages-0.<hash>.json     entries 0-99
+/// ```
+///
+/// Shards are named after their contents, so they can be cached indefinitely.
+pub fn shard_index(index: &SearchIndex) -> Result<Vec<IndexFile>, SearchError> {
+    let mut term_shards: BTreeMap<String, TermShard> = BTreeMap::new();
+    for (term, postings) in &index.terms {
+        let shard = term_shards.entry(shard_key(term)).or_default();
+        shard.terms.insert(term, postings);
+    }
+    for (filter, pages) in &index.filters {
+        let shard = term_shards.entry(shard_key(filter)).or_default();
+        shard.filters.insert(filter, pages);
+    }
+
+    let mut files = Vec::new();
+    let mut add = |name: String, contents: Vec<u8>| {
+        let hash = format!("{:x}", Sha256::digest(&contents));
+        let path = format!("{SHARD_DIR}/{name}.{}.json", &hash[..10]);
+        files.push(IndexFile {
+            path: path.clone(),
+            contents,
+        });
+        path
+    };
+
+    let mut term_paths = BTreeMap::new();
+    for (key, shard) in &term_shards {
+        let path = add(format!("terms-{key}"), serde_json::to_vec(shard)?);
+        term_paths.insert(key.clone(), path);
+    }
+    let mut page_paths = Vec::new();
+    for (i, pages) in index.pages.chunks(PAGE_SHARD_SIZE).enumerate() {
+        page_paths.push(add(format!("pages-{i}"), serde_json::to_vec(pages)?));
+    }
+
+    let manifest = Manifest {
+        fields: index.fields,
+        weights: index.weights,
+        average_lengths: index.average_lengths,
+        count: index.pages.len(),
+        filters: index
+            .filters
+            .keys()
+            .filter_map(|filter| Some(filter.split_once(':')?.0))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect(),
+        boosts: &index.boosts,
+        stop_words: &index.stop_words,
+        term_shards: term_paths,
+        page_shard_size: PAGE_SHARD_SIZE,
+        page_shards: page_paths,
+    };
+    files.insert(
+        0,
+        IndexFile {
+            path: MANIFEST_PATH.to_string(),
+            contents: serde_json::to_vec(&manifest)?,
+        },
+    );
+    Ok(files)
+}
+
+/// Fail when the files together are larger than `limit` bytes.
+pub fn check_size(files: &[IndexFile], limit: u64) -> Result<(), SearchError> {
+    let size = files.iter().map(|file| file.contents.len() as u64).sum();
+    if size > limit {
+        return Err(SearchError::TooLarge { size, limit });
+    }
+    Ok(())
+}
+
+/// The shard a term goes in: its first two characters when they are ASCII
+/// letters or digits, or just the first, so common initials like `s` are split
+/// over up to 36 shards. Terms starting with another character go by `u` and the
+/// character's code point divided by 256, so the characters of a script are
+/// spread over a few dozen shards rather than one each.
+///
+/// Every term a query word of two or more characters is a prefix of is in the
+/// word's own shard, so prefix matching needs only that one.
+pub fn shard_key(term: &str) -> String {
+    let mut chars = term.chars();
+    match (chars.next(), chars.next()) {
+        (Some(a), Some(b)) if a.is_ascii_alphanumeric() && b.is_ascii_alphanumeric() => {
+            format!("{a}{b}")
+        }
+        (Some(a), _) if a.is_ascii_alphanumeric() => a.to_string(),
+        (Some(a), _) => format!("u{}", a as u32 / 256),
+        (None, _) => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_search_index, PageData};
+
+    fn page(url: &str, title: &str, html: &str) -> PageData {
+        PageData {
+            url: url.to_string(),
+            title: title.to_string(),
+            description: String::new(),
+            html: html.to_string(),
+            tags: vec![],
+            date: None,
+            draft: false,
+            fields: BTreeMap::from([("status".to_string(), vec!["Beta".to_string()])]),
+            toc: vec![],
+            boost: 1.0,
+        }
+    }
+
+    fn json(file: &IndexFile) -> serde_json::Value {
+        serde_json::from_slice(&file.contents).unwrap()
+    }
+
+    #[test]
+    fn test_shard_key() {
+        assert_eq!(shard_key("install"), "in");
+        assert_eq!(shard_key("2024"), "20");
+        assert_eq!(shard_key("a"), "a");
+        assert_eq!(shard_key("x:y"), "x");
+        // U+C124
+        assert_eq!(shard_key("설치"), "u193");
+        assert_eq!(shard_key("status:beta"), "st");
+    }
+
+    #[test]
+    fn test_manifest_points_to_shards() {
+        let pages: Vec<_> = (0..150)
+            .map(|i| page(&format!("/p{i}"), "Install", "<p>설치</p>"))
+            .collect();
+        let files = shard_index(&build_search_index(&pages, &Default::default())).unwrap();
+
+        let manifest = json(&files[0]);
+        assert_eq!(files[0].path, "search-index.json");
+        assert_eq!(manifest["count"], 150);
+        assert_eq!(manifest["filters"], serde_json::json!(["status"]));
+        assert_eq!(manifest["page_shards"].as_array().unwrap().len(), 2);
+
+        let shard_path = manifest["term_shards"]["in"].as_str().unwrap();
+        assert!(shard_path.starts_with("assets/search/terms-in."));
+        let shard = json(files.iter().find(|f| f.path == shard_path).unwrap());
+        assert_eq!(shard["terms"]["install"].as_array().unwrap().len(), 150);
+
+        let hangul = manifest["term_shards"]["u193"].as_str().unwrap();
+        let shard = json(files.iter().find(|f| f.path == hangul).unwrap());
+        assert!(shard["terms"].get("설치").is_some());
+
+        let filters = manifest["term_shards"]["st"].as_str().unwrap();
+        let shard = json(files.iter().find(|f| f.path == filters).unwrap());
+        assert_eq!(
+            shard["filters"]["status:beta"].as_array().unwrap().len(),
+            150
+        );
+
+        let pages_path = manifest["page_shards"][1].as_str().unwrap();
+        let pages = json(files.iter().find(|f| f.path == pages_path).unwrap());
+        assert_eq!(pages.as_array().unwrap().len(), 50);
+        assert_eq!(pages[0]["url"], "/p100");
+    }
+
+    #[test]
+    fn test_size_budget() {
+        let files = shard_index(&build_search_index(
+            &[page("/", "Home", "")],
+            &Default::default(),
+        ))
+        .unwrap();
+        let size: u64 = files.iter().map(|f| f.contents.len() as u64).sum();
+        check_size(&files, size).unwrap();
+        let err = check_size(&files, size - 1).unwrap_err();
+        assert!(matches!(err, SearchError::TooLarge { limit, .. } if limit == size - 1));
+    }
+
+    #[test]
+    fn test_compressed_siblings() {
+        let file = IndexFile {
+            path: MANIFEST_PATH.to_string(),
+            contents: b"{}".repeat(1000),
+        };
+        let gz = file.gzip().unwrap();
+        assert_eq!(&gz[..2], [0x1f, 0x8b]);
+        assert!(gz.len() < file.contents.len());
+
+        let br = file.brotli().unwrap();
+        assert!(br.len() < file.contents.len());
+        let mut decoded = Vec::new();
+        brotli::BrotliDecompress(&mut br.as_slice(), &mut decoded).unwrap();
+        assert_eq!(decoded, file.contents);
+    }
+
+    #[test]
+    fn test_common_initials_are_split() {
+        let words = ["search", "setup", "server", "static", "style", "syntax"];
+        let pages: Vec<_> = words
+            .iter()
+            .map(|word| page(&format!("/{word}"), word, ""))
+            .collect();
+        let files = shard_index(&build_search_index(&pages, &Default::default())).unwrap();
+        let manifest = json(&files[0]);
+        let keys: Vec<_> = manifest["term_shards"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .filter(|key| key.starts_with('s'))
+            .collect();
+        assert_eq!(keys, ["se", "st", "sy"]);
+    }
+}
@@ -1,28 +1,125 @@
+use std::sync::LazyLock;
+
 use regex::Regex;
+use unicode_normalization::UnicodeNormalization;
+
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+static WHITESPACE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+").unwrap());
 
 /// Remove HTML tags from a string, decode common entities, and normalize whitespace.
 pub fn strip_html(html: &str) -> String {
-    let tag_re = Regex::new(r"<[^>]*>").unwrap();
-    let stripped = tag_re.replace_all(html, " ");
+    let stripped = TAG_RE.replace_all(html, " ");
     let decoded = decode_html_entities(&stripped);
     collapse_whitespace(&decoded)
 }
 
-/// Truncate text at a word boundary, appending "..." if truncated.
+/// Truncate text to at most `max_chars` characters, appending "..." if truncated.
+///
+/// Words separated by spaces are not cut. Text without spaces between words,
+/// such as Japanese or Chinese, may be cut after any character.
 pub fn truncate_content(text: &str, max_chars: usize) -> String {
-    if text.len() <= max_chars {
+    let Some((end, next)) = text.char_indices().nth(max_chars) else {
         return text.to_string();
-    }
+    };
 
-    let truncated = &text[..max_chars];
+    let truncated = &text[..end];
+    let last = truncated.chars().next_back();
+    if next.is_whitespace() || last.is_some_and(|c| script(c) == Script::Cjk) {
+        return format!("{}...", truncated.trim_end());
+    }
     // Find the last space to avoid cutting words
-    match truncated.rfind(' ') {
-        Some(pos) => format!("{}...", &truncated[..pos]),
+    match truncated.rfind(char::is_whitespace) {
+        Some(pos) => format!("{}...", truncated[..pos].trim_end()),
         None => format!("{truncated}..."),
     }
 }
 
-fn decode_html_entities(s: &str) -> String {
+/// Fold text for matching: NFKC turns full-width Latin letters and digits into
+/// ASCII, half-width katakana into full-width and composes Hangul jamo, then
+/// everything is lowercased.
+pub fn normalize(text: &str) -> String {
+    text.nfkc().collect::<String>().to_lowercase()
+}
+
+/// Split text into the search tokens the client matches queries against.
+///
+/// The text is normalized, then split into runs of one script at whitespace
+/// and punctuation:
+///
+/// - Words in alphabetic scripts are one token each: `Hello, world` gives
+///   `hello`, `world`.
+/// - Hangul and CJK runs give every pair of adjacent characters, because
+///   Korean attaches particles to words and Japanese and Chinese do not
+///   separate words at all: `설치를` gives `설치`, `치를`. A run of one
+///   character is its own token.
+///
+/// The client splits queries the same way and looks each token up in the
+/// inverted index. Tokens are returned in text order, so their index is their
+/// position.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let normalized = normalize(text);
+    let mut tokens = Vec::new();
+    let mut run: Vec<char> = Vec::new();
+    let mut run_script = Script::Separator;
+
+    for c in normalized.chars().chain([' ']) {
+        let script = script(c);
+        if script != run_script && !run.is_empty() {
+            push_run(&mut tokens, &run, run_script);
+            run.clear();
+        }
+        if script != Script::Separator {
+            run.push(c);
+        }
+        run_script = script;
+    }
+
+    tokens
+}
+
+fn push_run(tokens: &mut Vec<String>, run: &[char], script: Script) {
+    match script {
+        Script::Hangul | Script::Cjk if run.len() > 1 => {
+            tokens.extend(run.windows(2).map(|pair| pair.iter().collect()));
+        }
+        _ => tokens.push(run.iter().collect()),
+    }
+}
+
+/// How a character takes part in tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    /// Letters and digits of scripts that separate words with spaces
+    Word,
+    Hangul,
+    /// Han, Hiragana and Katakana
+    Cjk,
+    /// Whitespace, punctuation and symbols
+    Separator,
+}
+
+fn script(c: char) -> Script {
+    match c {
+        '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7A3}' => {
+            Script::Hangul
+        }
+        '\u{3040}'..='\u{30FF}'
+        | '\u{31F0}'..='\u{31FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}' => Script::Cjk,
+        // ASCII, general and CJK punctuation and symbols
+        _ if c.is_whitespace()
+            || c.is_ascii_punctuation()
+            || matches!(c, '\u{2000}'..='\u{206F}' | '\u{3000}'..='\u{303F}') =>
+        {
+            Script::Separator
+        }
+        _ => Script::Word,
+    }
+}
+
+pub(crate) fn decode_html_entities(s: &str) -> String {
     s.replace("&amp;", "&")
         .replace("&lt;", "<")
         .replace("&gt;", ">")
@@ -34,8 +131,7 @@ fn decode_html_entities(s: &str) -> String {
 }
 
 fn collapse_whitespace(s: &str) -> String {
-    let ws_re = Regex::new(r"\s+").unwrap();
-    ws_re.replace_all(s.trim(), " ").to_string()
+    WHITESPACE_RE.replace_all(s.trim(), " ").to_string()
 }
 
 #[cfg(test)]
@@ -92,7 +188,50 @@ mod tests {
     #[test]
     fn test_truncate_content() {
         let text = "Hello world this is a long text";
-        assert_eq!(truncate_content(text, 11), "Hello...");
+        assert_eq!(truncate_content(text, 11), "Hello world...");
+        assert_eq!(truncate_content(text, 13), "Hello world...");
         assert_eq!(truncate_content(text, 100), text);
     }
+
+    #[test]
+    fn test_truncate_multibyte_content() {
+        // Every Hangul syllable is three bytes; cutting by bytes would panic
+        let korean = "빠른 시작 안내서입니다";
+        assert_eq!(truncate_content(korean, 4), "빠른...");
+        assert_eq!(truncate_content(korean, 20), korean);
+
+        assert_eq!(
+            truncate_content("静的サイトジェネレーター", 5),
+            "静的サイト..."
+        );
+        assert_eq!(truncate_content("설치방법", 2), "설치...");
+    }
+
+    #[test]
+    fn test_normalize_width_and_case() {
+        assert_eq!(normalize("ＰＹＯＨＷＡ １２３"), "pyohwa 123");
+        assert_eq!(normalize("ｶﾀｶﾅ"), "カタカナ");
+        // Decomposed jamo compose into syllables
+        assert_eq!(normalize("\u{1112}\u{1161}\u{11AB}"), "한");
+    }
+
+    #[test]
+    fn test_tokenize_words() {
+        assert_eq!(
+            tokenize("Hello, World! hello-world v1.2"),
+            ["hello", "world", "hello", "world", "v1", "2"]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_hangul_bigrams() {
+        assert_eq!(tokenize("설치를 시작"), ["설치", "치를", "시작"]);
+        assert_eq!(tokenize("API를 호출"), ["api", "를", "호출"]);
+    }
+
+    #[test]
+    fn test_tokenize_cjk_bigrams() {
+        assert_eq!(tokenize("検索。"), ["検索"]);
+        assert_eq!(tokenize("静的サイト"), ["静的", "的サ", "サイ", "イト"]);
+    }
 }
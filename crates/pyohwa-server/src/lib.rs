@@ -5,16 +5,18 @@ mod watcher;
 
 pub use error::ServerError;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+use pyohwa_core::build::pipeline::BuildOptions;
 use tokio::sync::broadcast;
 
 pub struct DevServerConfig {
     pub port: u16,
     pub project_root: PathBuf,
     pub open: bool,
+    pub build_options: BuildOptions,
 }
 
 impl Default for DevServerConfig {
@@ -23,13 +25,14 @@ impl Default for DevServerConfig {
             port: 3000,
             project_root: PathBuf::from("."),
             open: false,
+            build_options: BuildOptions::default(),
         }
     }
 }
 
 /// Run the dev server with file watching and live reload.
 pub async fn run_dev_server(config: DevServerConfig) -> Result<(), ServerError> {
-    let project_root = if config.project_root == PathBuf::from(".") {
+    let project_root = if config.project_root == Path::new(".") {
         std::env::current_dir()?
     } else {
         std::fs::canonicalize(&config.project_root)?
@@ -37,7 +40,14 @@ pub async fn run_dev_server(config: DevServerConfig) -> Result<(), ServerError>
 
     // Initial build with live reload JS
     eprintln!("Building site...");
-    pyohwa_core::build::pipeline::build_dev(&project_root, config.port)?;
+    let report =
+        pyohwa_core::build::pipeline::build_dev(&project_root, config.port, &config.build_options)?;
+    for warning in &report.warnings {
+        eprintln!("warning: {warning}");
+    }
+    for link in &report.broken_links {
+        eprintln!("warning: {link}");
+    }
     eprintln!("Build complete.");
 
     // Broadcast channel for reload signals
@@ -51,9 +61,15 @@ pub async fn run_dev_server(config: DevServerConfig) -> Result<(), ServerError>
     let watcher_root = project_root.clone();
     let watcher_tx = reload_tx.clone();
     let ws_port = config.port;
+    let watcher_options = config.build_options.clone();
     let watcher_handle = tokio::task::spawn_blocking(move || {
-        if let Err(e) = watcher::start_watcher(watcher_root, ws_port, watcher_tx, shutdown_watcher)
-        {
+        if let Err(e) = watcher::start_watcher(
+            watcher_root,
+            ws_port,
+            watcher_options,
+            watcher_tx,
+            shutdown_watcher,
+        ) {
             eprintln!("Watcher error: {e}");
         }
     });
@@ -25,7 +25,12 @@ pub async fn start_server(
 
     let app = Router::new()
         .route(reload::WS_PATH, get(move |ws| ws_handler(ws, reload_tx)))
-        .fallback_service(ServeDir::new(&output_dir).append_index_html_on_directories(true));
+        .fallback_service(
+            ServeDir::new(&output_dir)
+                .append_index_html_on_directories(true)
+                .precompressed_br()
+                .precompressed_gzip(),
+        );
 
     let addr = format!("0.0.0.0:{port}");
     let listener = TcpListener::bind(&addr)
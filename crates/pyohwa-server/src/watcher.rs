@@ -1,13 +1,16 @@
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use notify::Watcher;
 use notify_debouncer_mini::{new_debouncer, DebouncedEventKind};
+use pyohwa_core::build::pipeline::BuildOptions;
 use tokio::sync::broadcast;
 
 /// Directories to watch for changes.
-const WATCH_DIRS: &[&str] = &["content", "static", "themes"];
+const WATCH_DIRS: &[&str] = &["content", "static", "themes", "syntaxes"];
 /// Also watch the config file.
 const WATCH_FILES: &[&str] = &["pyohwa.toml"];
 /// Directories to exclude (checked as path prefixes).
@@ -19,6 +22,7 @@ const EXCLUDE_DIRS: &[&str] = &["dist", ".pyohwa", "target"];
 pub fn start_watcher(
     project_root: PathBuf,
     ws_port: u16,
+    build_options: BuildOptions,
     reload_tx: broadcast::Sender<()>,
     shutdown: Arc<AtomicBool>,
 ) -> Result<(), crate::error::ServerError> {
@@ -49,6 +53,23 @@ pub fn start_watcher(
         }
     }
 
+    // `theme.custom_css` may live outside the watched directories
+    if let Some(custom_css) = pyohwa_core::config::load(&project_root)
+        .ok()
+        .and_then(|config| config.theme.custom_css)
+    {
+        let file = project_root.join(custom_css);
+        if file.exists() {
+            let _ = debouncer
+                .watcher()
+                .watch(&file, notify::RecursiveMode::NonRecursive);
+        }
+    }
+
+    // Included snippets may live anywhere in the project
+    let mut include_dirs = BTreeSet::new();
+    watch_include_dirs(debouncer.watcher(), &project_root, &mut include_dirs);
+
     eprintln!("Watching for changes...");
 
     loop {
@@ -68,13 +89,24 @@ pub fn start_watcher(
 
                 let start = Instant::now();
 
-                match pyohwa_core::build::pipeline::build_dev_incremental(&project_root, ws_port) {
-                    Ok(true) => {
+                match pyohwa_core::build::pipeline::build_dev_incremental(
+                    &project_root,
+                    ws_port,
+                    &build_options,
+                ) {
+                    Ok(Some(report)) => {
+                        for warning in &report.warnings {
+                            eprintln!("warning: {warning}");
+                        }
+                        for link in &report.broken_links {
+                            eprintln!("warning: {link}");
+                        }
+                        watch_include_dirs(debouncer.watcher(), &project_root, &mut include_dirs);
                         let elapsed = start.elapsed();
                         eprintln!("Rebuilt in {}ms", elapsed.as_millis());
                         let _ = reload_tx.send(());
                     }
-                    Ok(false) => {
+                    Ok(None) => {
                         // No actual content changes detected by manifest
                     }
                     Err(e) => {
@@ -93,6 +125,31 @@ pub fn start_watcher(
     Ok(())
 }
 
+/// Watch the directories of included files recorded in the build manifest that
+/// fall outside `WATCH_DIRS`. `watched` remembers directories already added.
+fn watch_include_dirs(
+    watcher: &mut dyn Watcher,
+    project_root: &Path,
+    watched: &mut BTreeSet<PathBuf>,
+) {
+    let manifest = pyohwa_core::build::incremental::load_manifest(project_root);
+    for include in manifest.includes.keys() {
+        if WATCH_DIRS.iter().any(|dir| include.starts_with(dir)) {
+            continue;
+        }
+        let Some(dir) = include.parent().map(|parent| project_root.join(parent)) else {
+            continue;
+        };
+        if !watched.contains(&dir)
+            && watcher
+                .watch(&dir, notify::RecursiveMode::NonRecursive)
+                .is_ok()
+        {
+            watched.insert(dir);
+        }
+    }
+}
+
 fn is_excluded(path: &Path, project_root: &Path) -> bool {
     let relative = path.strip_prefix(project_root).unwrap_or(path);
 
@@ -5337,21 +5337,146 @@ var $elm$core$Task$perform = F2(
 	});
 var $elm$browser$Browser$element = _Browser_element;
 var $elm$json$Json$Decode$decodeValue = _Json_run;
+var $elm$core$Dict$RBEmpty_elm_builtin = {$: -2};
+var $elm$core$Dict$empty = $elm$core$Dict$RBEmpty_elm_builtin;
+var $elm$core$Dict$Black = 1;
+var $elm$core$Dict$RBNode_elm_builtin = F5(
+	function (a, b, c, d, e) {
+		return {$: -1, a: a, b: b, c: c, d: d, e: e};
+	});
+var $elm$core$Dict$Red = 0;
+var $elm$core$Dict$balance = F5(
+	function (color, key, value, left, right) {
+		if ((right.$ === -1) && (!right.a)) {
+			var _v1 = right.a;
+			var rK = right.b;
+			var rV = right.c;
+			var rLeft = right.d;
+			var rRight = right.e;
+			if ((left.$ === -1) && (!left.a)) {
+				var _v3 = left.a;
+				var lK = left.b;
+				var lV = left.c;
+				var lLeft = left.d;
+				var lRight = left.e;
+				return A5(
+					$elm$core$Dict$RBNode_elm_builtin,
+					0,
+					key,
+					value,
+					A5($elm$core$Dict$RBNode_elm_builtin, 1, lK, lV, lLeft, lRight),
+					A5($elm$core$Dict$RBNode_elm_builtin, 1, rK, rV, rLeft, rRight));
+			} else {
+				return A5(
+					$elm$core$Dict$RBNode_elm_builtin,
+					color,
+					rK,
+					rV,
+					A5($elm$core$Dict$RBNode_elm_builtin, 0, key, value, left, rLeft),
+					rRight);
+			}
+		} else {
+			if ((((left.$ === -1) && (!left.a)) && (left.d.$ === -1)) && (!left.d.a)) {
+				var _v5 = left.a;
+				var lK = left.b;
+				var lV = left.c;
+				var _v6 = left.d;
+				var _v7 = _v6.a;
+				var llK = _v6.b;
+				var llV = _v6.c;
+				var llLeft = _v6.d;
+				var llRight = _v6.e;
+				var lRight = left.e;
+				return A5(
+					$elm$core$Dict$RBNode_elm_builtin,
+					0,
+					lK,
+					lV,
+					A5($elm$core$Dict$RBNode_elm_builtin, 1, llK, llV, llLeft, llRight),
+					A5($elm$core$Dict$RBNode_elm_builtin, 1, key, value, lRight, right));
+			} else {
+				return A5($elm$core$Dict$RBNode_elm_builtin, color, key, value, left, right);
+			}
+		}
+	});
+var $elm$core$Basics$compare = _Utils_compare;
+var $elm$core$Dict$insertHelp = F3(
+	function (key, value, dict) {
+		if (dict.$ === -2) {
+			return A5($elm$core$Dict$RBNode_elm_builtin, 0, key, value, $elm$core$Dict$RBEmpty_elm_builtin, $elm$core$Dict$RBEmpty_elm_builtin);
+		} else {
+			var nColor = dict.a;
+			var nKey = dict.b;
+			var nValue = dict.c;
+			var nLeft = dict.d;
+			var nRight = dict.e;
+			var _v1 = A2($elm$core$Basics$compare, key, nKey);
+			switch (_v1) {
+				case 0:
+					return A5(
+						$elm$core$Dict$balance,
+						nColor,
+						nKey,
+						nValue,
+						A3($elm$core$Dict$insertHelp, key, value, nLeft),
+						nRight);
+				case 1:
+					return A5($elm$core$Dict$RBNode_elm_builtin, nColor, nKey, value, nLeft, nRight);
+				default:
+					return A5(
+						$elm$core$Dict$balance,
+						nColor,
+						nKey,
+						nValue,
+						nLeft,
+						A3($elm$core$Dict$insertHelp, key, value, nRight));
+			}
+		}
+	});
+var $elm$core$Dict$insert = F3(
+	function (key, value, dict) {
+		var _v0 = A3($elm$core$Dict$insertHelp, key, value, dict);
+		if ((_v0.$ === -1) && (!_v0.a)) {
+			var _v1 = _v0.a;
+			var k = _v0.b;
+			var v = _v0.c;
+			var l = _v0.d;
+			var r = _v0.e;
+			return A5($elm$core$Dict$RBNode_elm_builtin, 1, k, v, l, r);
+		} else {
+			var x = _v0;
+			return x;
+		}
+	});
+var $elm$core$Dict$fromList = function (assocs) {
+	return A3(
+		$elm$core$List$foldl,
+		F2(
+			function (_v0, dict) {
+				var key = _v0.a;
+				var value = _v0.b;
+				return A3($elm$core$Dict$insert, key, value, dict);
+			}),
+		$elm$core$Dict$empty,
+		assocs);
+};
+var $elm$json$Json$Decode$value = _Json_decodeValue;
 var $author$project$Search$Search$Idle = {$: 0};
-var $author$project$Model$fallback = {aj: '', b6: 'one-dark', cn: _List_Nil, aq: $elm$core$Maybe$Nothing, as: '<p>Failed to load page data.</p>', at: '', au: 'doc', av: 'Error', aw: _List_Nil, ax: $elm$core$Maybe$Nothing, az: true, aA: $author$project$Search$Search$Idle, aB: false, aC: '', aD: _List_Nil, cI: _List_Nil, aF: false, aG: '/', aH: '', aI: 'Pyohwa'};
-var $author$project$Flags$Flags = F4(
-	function (page, site, theme, search) {
-		return {cz: page, cG: search, cJ: site, cP: theme};
+var $author$project$Flags$defaultLabels = {aq: 'Next', dD: 'On this page', dE: 'Previous'};
+var $author$project$Model$fallback = {aj: '', dB: $elm$core$Maybe$Nothing, b6: 'one-dark', dC: $author$project$Flags$defaultLabels, cn: _List_Nil, aq: $elm$core$Maybe$Nothing, as: '<p>Failed to load page data.</p>', at: '', dH: $elm$core$Dict$empty, au: 'doc', av: 'Error', aw: _List_Nil, ax: $elm$core$Maybe$Nothing, az: true, aA: $author$project$Search$Search$Idle, aB: false, aC: '', aD: _List_Nil, cI: _List_Nil, aF: false, aG: '/', aH: '', aI: 'Pyohwa'};
+var $author$project$Flags$Flags = F5(
+	function (page, site, theme, search, preferences) {
+		return {cz: page, dA: preferences, cG: search, cJ: site, cP: theme};
 	});
 var $elm$json$Json$Decode$field = _Json_decodeField;
-var $elm$json$Json$Decode$map4 = _Json_map4;
 var $elm$json$Json$Decode$oneOf = _Json_oneOf;
-var $author$project$Flags$PageData = F5(
-	function (title, description, content, toc, layout) {
-		return {bV: content, aW: description, cd: layout, aM: title, cR: toc};
+var $author$project$Flags$PageData = F6(
+	function (title, description, content, toc, layout, frontmatter) {
+		return {bV: content, aW: description, dF: frontmatter, cd: layout, aM: title, cR: toc};
 	});
 var $elm$json$Json$Decode$list = _Json_decodeList;
 var $elm$json$Json$Decode$map5 = _Json_map5;
+var $elm$json$Json$Decode$map6 = _Json_map6;
 var $elm$json$Json$Decode$string = _Json_decodeString;
 var $author$project$Flags$TocItem = F3(
 	function (id, text, level) {
@@ -5365,8 +5490,15 @@ var $author$project$Flags$tocItemDecoder = A4(
 	A2($elm$json$Json$Decode$field, 'id', $elm$json$Json$Decode$string),
 	A2($elm$json$Json$Decode$field, 'text', $elm$json$Json$Decode$string),
 	A2($elm$json$Json$Decode$field, 'level', $elm$json$Json$Decode$int));
-var $author$project$Flags$pageDecoder = A6(
-	$elm$json$Json$Decode$map5,
+var $elm$json$Json$Decode$keyValuePairs = _Json_decodeKeyValuePairs;
+var $elm$json$Json$Decode$dict = function (decoder) {
+	return A2(
+		$elm$json$Json$Decode$map,
+		$elm$core$Dict$fromList,
+		$elm$json$Json$Decode$keyValuePairs(decoder));
+};
+var $author$project$Flags$pageDecoder = A7(
+	$elm$json$Json$Decode$map6,
 	$author$project$Flags$PageData,
 	A2($elm$json$Json$Decode$field, 'title', $elm$json$Json$Decode$string),
 	A2($elm$json$Json$Decode$field, 'description', $elm$json$Json$Decode$string),
@@ -5380,6 +5512,15 @@ var $author$project$Flags$pageDecoder = A6(
 			[
 				A2($elm$json$Json$Decode$field, 'layout', $elm$json$Json$Decode$string),
 				$elm$json$Json$Decode$succeed('doc')
+			])),
+	$elm$json$Json$Decode$oneOf(
+		_List_fromArray(
+			[
+				A2(
+				$elm$json$Json$Decode$field,
+				'frontmatter',
+				$elm$json$Json$Decode$dict($elm$json$Json$Decode$value)),
+				$elm$json$Json$Decode$succeed($elm$core$Dict$empty)
 			])));
 var $author$project$Flags$SearchData = function (enabled) {
 	return {aY: enabled};
@@ -5389,10 +5530,20 @@ var $author$project$Flags$searchDecoder = A2(
 	$elm$json$Json$Decode$map,
 	$author$project$Flags$SearchData,
 	A2($elm$json$Json$Decode$field, 'enabled', $elm$json$Json$Decode$bool));
-var $author$project$Flags$SiteData = F5(
-	function (title, description, base, nav, sidebar) {
-		return {bP: base, aW: description, cn: nav, cI: sidebar, aM: title};
+var $author$project$Flags$SiteData = F6(
+	function (title, description, base, nav, sidebar, labels) {
+		return {bP: base, aW: description, dC: labels, cn: nav, cI: sidebar, aM: title};
 	});
+var $author$project$Flags$Labels = F3(
+	function (onThisPage, previous, next) {
+		return {aq: next, dD: onThisPage, dE: previous};
+	});
+var $author$project$Flags$labelsDecoder = A4(
+	$elm$json$Json$Decode$map3,
+	$author$project$Flags$Labels,
+	A2($elm$json$Json$Decode$field, 'onThisPage', $elm$json$Json$Decode$string),
+	A2($elm$json$Json$Decode$field, 'previous', $elm$json$Json$Decode$string),
+	A2($elm$json$Json$Decode$field, 'next', $elm$json$Json$Decode$string));
 var $author$project$Flags$NavItem = F3(
 	function (text, link, active) {
 		return {aN: active, an: link, ah: text};
@@ -5412,15 +5563,26 @@ var $author$project$Flags$SidebarGroup = F2(
 	function (text, items) {
 		return {cc: items, ah: text};
 	});
-var $author$project$Flags$SidebarItem = F3(
-	function (text, link, active) {
-		return {aN: active, an: link, ah: text};
+var $author$project$Flags$SidebarItem = F4(
+	function (text, link, badge, active) {
+		return {aN: active, dG: badge, an: link, ah: text};
 	});
-var $author$project$Flags$sidebarItemDecoder = A4(
-	$elm$json$Json$Decode$map3,
+var $elm$json$Json$Decode$map4 = _Json_map4;
+var $elm$json$Json$Decode$maybe = function (decoder) {
+	return $elm$json$Json$Decode$oneOf(
+		_List_fromArray(
+			[
+				A2($elm$json$Json$Decode$map, $elm$core$Maybe$Just, decoder),
+				$elm$json$Json$Decode$succeed($elm$core$Maybe$Nothing)
+			]));
+};
+var $author$project$Flags$sidebarItemDecoder = A5(
+	$elm$json$Json$Decode$map4,
 	$author$project$Flags$SidebarItem,
 	A2($elm$json$Json$Decode$field, 'text', $elm$json$Json$Decode$string),
 	A2($elm$json$Json$Decode$field, 'link', $elm$json$Json$Decode$string),
+	$elm$json$Json$Decode$maybe(
+		A2($elm$json$Json$Decode$field, 'badge', $elm$json$Json$Decode$string)),
 	$elm$json$Json$Decode$oneOf(
 		_List_fromArray(
 			[
@@ -5435,8 +5597,8 @@ var $author$project$Flags$sidebarGroupDecoder = A3(
 		$elm$json$Json$Decode$field,
 		'items',
 		$elm$json$Json$Decode$list($author$project$Flags$sidebarItemDecoder)));
-var $author$project$Flags$siteDecoder = A6(
-	$elm$json$Json$Decode$map5,
+var $author$project$Flags$siteDecoder = A7(
+	$elm$json$Json$Decode$map6,
 	$author$project$Flags$SiteData,
 	A2($elm$json$Json$Decode$field, 'title', $elm$json$Json$Decode$string),
 	A2($elm$json$Json$Decode$field, 'description', $elm$json$Json$Decode$string),
@@ -5448,7 +5610,13 @@ var $author$project$Flags$siteDecoder = A6(
 	A2(
 		$elm$json$Json$Decode$field,
 		'sidebar',
-		$elm$json$Json$Decode$list($author$project$Flags$sidebarGroupDecoder)));
+		$elm$json$Json$Decode$list($author$project$Flags$sidebarGroupDecoder)),
+	$elm$json$Json$Decode$oneOf(
+		_List_fromArray(
+			[
+				A2($elm$json$Json$Decode$field, 'labels', $author$project$Flags$labelsDecoder),
+				$elm$json$Json$Decode$succeed($author$project$Flags$defaultLabels)
+			])));
 var $author$project$Flags$ThemeData = function (highlightTheme) {
 	return {b6: highlightTheme};
 };
@@ -5456,8 +5624,32 @@ var $author$project$Flags$themeDecoder = A2(
 	$elm$json$Json$Decode$map,
 	$author$project$Flags$ThemeData,
 	A2($elm$json$Json$Decode$field, 'highlightTheme', $elm$json$Json$Decode$string));
-var $author$project$Flags$decoder = A5(
-	$elm$json$Json$Decode$map4,
+var $author$project$Flags$Preferences = function (codeGroupTab) {
+	return {dB: codeGroupTab};
+};
+var $elm$json$Json$Decode$null = _Json_decodeNull;
+var $elm$json$Json$Decode$nullable = function (decoder) {
+	return $elm$json$Json$Decode$oneOf(
+		_List_fromArray(
+			[
+				$elm$json$Json$Decode$null($elm$core$Maybe$Nothing),
+				A2($elm$json$Json$Decode$map, $elm$core$Maybe$Just, decoder)
+			]));
+};
+var $author$project$Flags$preferencesDecoder = A2(
+	$elm$json$Json$Decode$map,
+	$author$project$Flags$Preferences,
+	$elm$json$Json$Decode$oneOf(
+		_List_fromArray(
+			[
+				A2(
+				$elm$json$Json$Decode$field,
+				'codeGroupTab',
+				$elm$json$Json$Decode$nullable($elm$json$Json$Decode$string)),
+				$elm$json$Json$Decode$succeed($elm$core$Maybe$Nothing)
+			])));
+var $author$project$Flags$decoder = A6(
+	$elm$json$Json$Decode$map5,
 	$author$project$Flags$Flags,
 	A2($elm$json$Json$Decode$field, 'page', $author$project$Flags$pageDecoder),
 	A2($elm$json$Json$Decode$field, 'site', $author$project$Flags$siteDecoder),
@@ -5468,6 +5660,13 @@ var $author$project$Flags$decoder = A5(
 				A2($elm$json$Json$Decode$field, 'search', $author$project$Flags$searchDecoder),
 				$elm$json$Json$Decode$succeed(
 				{aY: true})
+			])),
+	$elm$json$Json$Decode$oneOf(
+		_List_fromArray(
+			[
+				A2($elm$json$Json$Decode$field, 'preferences', $author$project$Flags$preferencesDecoder),
+				$elm$json$Json$Decode$succeed(
+				{dB: $elm$core$Maybe$Nothing})
 			])));
 var $author$project$Flags$PrevNextLink = F2(
 	function (title, link) {
@@ -5507,7 +5706,9 @@ var $author$project$Model$fromFlags = F3(
 	function (flags, prev, next) {
 		return {
 			aj: '',
+			dB: flags.dA.dB,
 			b6: flags.cP.b6,
+			dC: flags.cJ.dC,
 			cn: A2(
 				$elm$core$List$map,
 				function (item) {
@@ -5517,6 +5718,7 @@ var $author$project$Model$fromFlags = F3(
 			aq: next,
 			as: flags.cz.bV,
 			at: flags.cz.aW,
+			dH: flags.cz.dF,
 			au: flags.cz.cd,
 			av: flags.cz.aM,
 			aw: A2(
@@ -5538,7 +5740,7 @@ var $author$project$Model$fromFlags = F3(
 						cc: A2(
 							$elm$core$List$map,
 							function (item) {
-								return {aN: item.aN, an: item.an, ah: item.ah};
+								return {aN: item.aN, dG: item.dG, an: item.an, ah: item.ah};
 							},
 							group.cc),
 						ah: group.ah
@@ -5571,9 +5773,9 @@ var $author$project$Msg$OnScroll = function (a) {
 	return {$: 3, a: a};
 };
 var $elm$core$Platform$Sub$batch = _Platform_batch;
-var $author$project$Msg$NoOp = {$: 9};
+var $author$project$Msg$NoOp = {$: 12};
 var $author$project$Msg$OnKeyDown = function (a) {
-	return {$: 8, a: a};
+	return {$: 10, a: a};
 };
 var $author$project$Msg$OpenSearch = {$: 4};
 var $author$project$Main$keyDecoder = A4(
@@ -5594,8 +5796,6 @@ var $elm$browser$Browser$Events$State = F2(
 	function (subs, pids) {
 		return {bh: pids, bz: subs};
 	});
-var $elm$core$Dict$RBEmpty_elm_builtin = {$: -2};
-var $elm$core$Dict$empty = $elm$core$Dict$RBEmpty_elm_builtin;
 var $elm$browser$Browser$Events$init = $elm$core$Task$succeed(
 	A2($elm$browser$Browser$Events$State, _List_Nil, $elm$core$Dict$empty));
 var $elm$browser$Browser$Events$nodeToKey = function (node) {
@@ -5614,150 +5814,29 @@ var $elm$browser$Browser$Events$addKey = function (sub) {
 			name),
 		sub);
 };
-var $elm$core$Dict$Black = 1;
-var $elm$core$Dict$RBNode_elm_builtin = F5(
-	function (a, b, c, d, e) {
-		return {$: -1, a: a, b: b, c: c, d: d, e: e};
-	});
-var $elm$core$Dict$Red = 0;
-var $elm$core$Dict$balance = F5(
-	function (color, key, value, left, right) {
-		if ((right.$ === -1) && (!right.a)) {
-			var _v1 = right.a;
-			var rK = right.b;
-			var rV = right.c;
-			var rLeft = right.d;
-			var rRight = right.e;
-			if ((left.$ === -1) && (!left.a)) {
-				var _v3 = left.a;
-				var lK = left.b;
-				var lV = left.c;
-				var lLeft = left.d;
-				var lRight = left.e;
-				return A5(
-					$elm$core$Dict$RBNode_elm_builtin,
-					0,
+var $elm$core$Process$kill = _Scheduler_kill;
+var $elm$core$Dict$foldl = F3(
+	function (func, acc, dict) {
+		foldl:
+		while (true) {
+			if (dict.$ === -2) {
+				return acc;
+			} else {
+				var key = dict.b;
+				var value = dict.c;
+				var left = dict.d;
+				var right = dict.e;
+				var $temp$func = func,
+					$temp$acc = A3(
+					func,
 					key,
 					value,
-					A5($elm$core$Dict$RBNode_elm_builtin, 1, lK, lV, lLeft, lRight),
-					A5($elm$core$Dict$RBNode_elm_builtin, 1, rK, rV, rLeft, rRight));
-			} else {
-				return A5(
-					$elm$core$Dict$RBNode_elm_builtin,
-					color,
-					rK,
-					rV,
-					A5($elm$core$Dict$RBNode_elm_builtin, 0, key, value, left, rLeft),
-					rRight);
-			}
-		} else {
-			if ((((left.$ === -1) && (!left.a)) && (left.d.$ === -1)) && (!left.d.a)) {
-				var _v5 = left.a;
-				var lK = left.b;
-				var lV = left.c;
-				var _v6 = left.d;
-				var _v7 = _v6.a;
-				var llK = _v6.b;
-				var llV = _v6.c;
-				var llLeft = _v6.d;
-				var llRight = _v6.e;
-				var lRight = left.e;
-				return A5(
-					$elm$core$Dict$RBNode_elm_builtin,
-					0,
-					lK,
-					lV,
-					A5($elm$core$Dict$RBNode_elm_builtin, 1, llK, llV, llLeft, llRight),
-					A5($elm$core$Dict$RBNode_elm_builtin, 1, key, value, lRight, right));
-			} else {
-				return A5($elm$core$Dict$RBNode_elm_builtin, color, key, value, left, right);
-			}
-		}
-	});
-var $elm$core$Basics$compare = _Utils_compare;
-var $elm$core$Dict$insertHelp = F3(
-	function (key, value, dict) {
-		if (dict.$ === -2) {
-			return A5($elm$core$Dict$RBNode_elm_builtin, 0, key, value, $elm$core$Dict$RBEmpty_elm_builtin, $elm$core$Dict$RBEmpty_elm_builtin);
-		} else {
-			var nColor = dict.a;
-			var nKey = dict.b;
-			var nValue = dict.c;
-			var nLeft = dict.d;
-			var nRight = dict.e;
-			var _v1 = A2($elm$core$Basics$compare, key, nKey);
-			switch (_v1) {
-				case 0:
-					return A5(
-						$elm$core$Dict$balance,
-						nColor,
-						nKey,
-						nValue,
-						A3($elm$core$Dict$insertHelp, key, value, nLeft),
-						nRight);
-				case 1:
-					return A5($elm$core$Dict$RBNode_elm_builtin, nColor, nKey, value, nLeft, nRight);
-				default:
-					return A5(
-						$elm$core$Dict$balance,
-						nColor,
-						nKey,
-						nValue,
-						nLeft,
-						A3($elm$core$Dict$insertHelp, key, value, nRight));
-			}
-		}
-	});
-var $elm$core$Dict$insert = F3(
-	function (key, value, dict) {
-		var _v0 = A3($elm$core$Dict$insertHelp, key, value, dict);
-		if ((_v0.$ === -1) && (!_v0.a)) {
-			var _v1 = _v0.a;
-			var k = _v0.b;
-			var v = _v0.c;
-			var l = _v0.d;
-			var r = _v0.e;
-			return A5($elm$core$Dict$RBNode_elm_builtin, 1, k, v, l, r);
-		} else {
-			var x = _v0;
-			return x;
-		}
-	});
-var $elm$core$Dict$fromList = function (assocs) {
-	return A3(
-		$elm$core$List$foldl,
-		F2(
-			function (_v0, dict) {
-				var key = _v0.a;
-				var value = _v0.b;
-				return A3($elm$core$Dict$insert, key, value, dict);
-			}),
-		$elm$core$Dict$empty,
-		assocs);
-};
-var $elm$core$Process$kill = _Scheduler_kill;
-var $elm$core$Dict$foldl = F3(
-	function (func, acc, dict) {
-		foldl:
-		while (true) {
-			if (dict.$ === -2) {
-				return acc;
-			} else {
-				var key = dict.b;
-				var value = dict.c;
-				var left = dict.d;
-				var right = dict.e;
-				var $temp$func = func,
-					$temp$acc = A3(
-					func,
-					key,
-					value,
-					A3($elm$core$Dict$foldl, func, acc, left)),
-					$temp$dict = right;
-				func = $temp$func;
-				acc = $temp$acc;
-				dict = $temp$dict;
-				continue foldl;
+					A3($elm$core$Dict$foldl, func, acc, left)),
+					$temp$dict = right;
+				func = $temp$func;
+				acc = $temp$acc;
+				dict = $temp$dict;
+				continue foldl;
 			}
 		}
 	});
@@ -6663,35 +6742,386 @@ var $elm$http$Http$get = function (r) {
 	return $elm$http$Http$request(
 		{bQ: $elm$http$Http$emptyBody, b0: r.b0, a3: _List_Nil, ci: 'GET', cQ: $elm$core$Maybe$Nothing, bD: $elm$core$Maybe$Nothing, cU: r.cU});
 };
-var $author$project$Search$Search$SearchEntry = F6(
-	function (id, url, title, description, content, tags) {
-		return {bV: content, aW: description, b7: id, bB: tags, aM: title, cU: url};
+var $author$project$Search$Search$SearchEntry = F8(
+	function (id, url, title, breadcrumb, description, content, tags, fields) {
+		return {dS: breadcrumb, bV: content, aW: description, dI: fields, b7: id, bB: tags, aM: title, cU: url};
+	});
+var $author$project$Search$Search$andMap = $elm$json$Json$Decode$map2($elm$core$Basics$apR);
+var $elm$json$Json$Decode$array = _Json_decodeArray;
+var $author$project$Search$Search$searchEntryDecoder = A2(
+	$author$project$Search$Search$andMap,
+	$elm$json$Json$Decode$oneOf(
+		_List_fromArray(
+			[
+				A2(
+				$elm$json$Json$Decode$field,
+				'fields',
+				$elm$json$Json$Decode$dict(
+					$elm$json$Json$Decode$list($elm$json$Json$Decode$string))),
+				$elm$json$Json$Decode$succeed($elm$core$Dict$empty)
+			])),
+	A2(
+		$author$project$Search$Search$andMap,
+		A2(
+			$elm$json$Json$Decode$field,
+			'tags',
+			$elm$json$Json$Decode$list($elm$json$Json$Decode$string)),
+		A2(
+			$author$project$Search$Search$andMap,
+			A2($elm$json$Json$Decode$field, 'content', $elm$json$Json$Decode$string),
+			A2(
+				$author$project$Search$Search$andMap,
+				A2($elm$json$Json$Decode$field, 'description', $elm$json$Json$Decode$string),
+				A2(
+					$author$project$Search$Search$andMap,
+					A2(
+						$elm$json$Json$Decode$field,
+						'breadcrumb',
+						$elm$json$Json$Decode$list($elm$json$Json$Decode$string)),
+					A2(
+						$author$project$Search$Search$andMap,
+						A2($elm$json$Json$Decode$field, 'title', $elm$json$Json$Decode$string),
+						A2(
+							$author$project$Search$Search$andMap,
+							A2($elm$json$Json$Decode$field, 'url', $elm$json$Json$Decode$string),
+							A2(
+								$author$project$Search$Search$andMap,
+								A2($elm$json$Json$Decode$field, 'id', $elm$json$Json$Decode$string),
+								$elm$json$Json$Decode$succeed($author$project$Search$Search$SearchEntry)))))))));
+var $author$project$Search$Search$SearchIndex = function (count) {
+	return function (weights) {
+		return function (averageLengths) {
+			return function (filterKeys) {
+				return function (boosts) {
+					return function (stopWords) {
+						return function (termShards) {
+							return function (pageShardSize) {
+								return function (pageShards) {
+									return function (pages) {
+										return function (terms) {
+											return function (filters) {
+												return function (loaded) {
+													return function (requested) {
+														return {dN: averageLengths, d2: boosts, dT: count, dU: filterKeys, dY: filters, dZ: loaded, dW: pageShardSize, dX: pageShards, dK: pages, d0: requested, d3: stopWords, dV: termShards, dL: terms, dM: weights};
+													};
+												};
+											};
+										};
+									};
+								};
+							};
+						};
+					};
+				};
+			};
+		};
+	};
+};
+var $elm$core$Set$Set_elm_builtin = $elm$core$Basics$identity;
+var $elm$core$Set$empty = $elm$core$Dict$empty;
+var $elm$core$Set$insert = F2(
+	function (key, _v0) {
+		var dict = _v0;
+		return A3($elm$core$Dict$insert, key, 0, dict);
+	});
+var $elm$core$Set$fromList = function (list) {
+	return A3($elm$core$List$foldl, $elm$core$Set$insert, $elm$core$Set$empty, list);
+};
+var $author$project$Search$Search$boostsDecoder = A2(
+	$elm$json$Json$Decode$map,
+	A2(
+		$elm$core$Basics$composeR,
+		$elm$core$List$filterMap(
+			function (_v0) {
+				var page = _v0.a;
+				var boost = _v0.b;
+				return A2(
+					$elm$core$Maybe$map,
+					function (number) {
+						return _Utils_Tuple2(number, boost);
+					},
+					$elm$core$String$toInt(page));
+			}),
+		$elm$core$Dict$fromList),
+	$elm$json$Json$Decode$keyValuePairs($elm$json$Json$Decode$float));
+var $author$project$Search$Search$optionalField = F3(
+	function (name, empty, decoder) {
+		return $elm$json$Json$Decode$oneOf(
+			_List_fromArray(
+				[
+					A2($elm$json$Json$Decode$field, name, decoder),
+					$elm$json$Json$Decode$succeed(empty)
+				]));
 	});
-var $elm$json$Json$Decode$map6 = _Json_map6;
-var $author$project$Search$Search$searchEntryDecoder = A7(
-	$elm$json$Json$Decode$map6,
-	$author$project$Search$Search$SearchEntry,
-	A2($elm$json$Json$Decode$field, 'id', $elm$json$Json$Decode$string),
-	A2($elm$json$Json$Decode$field, 'url', $elm$json$Json$Decode$string),
-	A2($elm$json$Json$Decode$field, 'title', $elm$json$Json$Decode$string),
-	A2($elm$json$Json$Decode$field, 'description', $elm$json$Json$Decode$string),
-	A2($elm$json$Json$Decode$field, 'content', $elm$json$Json$Decode$string),
+var $author$project$Search$Search$searchIndexDecoder = A2(
+	$author$project$Search$Search$andMap,
+	$elm$json$Json$Decode$succeed($elm$core$Set$empty),
+	A2(
+		$author$project$Search$Search$andMap,
+		$elm$json$Json$Decode$succeed($elm$core$Set$empty),
+		A2(
+			$author$project$Search$Search$andMap,
+			$elm$json$Json$Decode$succeed($elm$core$Dict$empty),
+			A2(
+				$author$project$Search$Search$andMap,
+				$elm$json$Json$Decode$succeed($elm$core$Dict$empty),
+				A2(
+					$author$project$Search$Search$andMap,
+					$elm$json$Json$Decode$succeed($elm$core$Dict$empty),
+					A2(
+						$author$project$Search$Search$andMap,
+						A2(
+							$elm$json$Json$Decode$field,
+							'page_shards',
+							$elm$json$Json$Decode$array($elm$json$Json$Decode$string)),
+						A2(
+							$author$project$Search$Search$andMap,
+							A2($elm$json$Json$Decode$field, 'page_shard_size', $elm$json$Json$Decode$int),
+							A2(
+								$author$project$Search$Search$andMap,
+								A2(
+									$elm$json$Json$Decode$field,
+									'term_shards',
+									$elm$json$Json$Decode$dict($elm$json$Json$Decode$string)),
+								A2(
+									$author$project$Search$Search$andMap,
+									A3(
+										$author$project$Search$Search$optionalField,
+										'stop_words',
+										_List_Nil,
+										$elm$json$Json$Decode$list($elm$json$Json$Decode$string)),
+									A2(
+										$author$project$Search$Search$andMap,
+										A3($author$project$Search$Search$optionalField, 'boosts', $elm$core$Dict$empty, $author$project$Search$Search$boostsDecoder),
+										A2(
+											$author$project$Search$Search$andMap,
+											A2(
+												$elm$json$Json$Decode$field,
+												'filters',
+												$elm$json$Json$Decode$list($elm$json$Json$Decode$string)),
+											A2(
+												$author$project$Search$Search$andMap,
+												A2(
+													$elm$json$Json$Decode$field,
+													'average_lengths',
+													$elm$json$Json$Decode$array($elm$json$Json$Decode$float)),
+												A2(
+													$author$project$Search$Search$andMap,
+													A2(
+														$elm$json$Json$Decode$field,
+														'weights',
+														$elm$json$Json$Decode$array($elm$json$Json$Decode$float)),
+													A2(
+														$author$project$Search$Search$andMap,
+														A2($elm$json$Json$Decode$field, 'count', $elm$json$Json$Decode$int),
+														$elm$json$Json$Decode$succeed($author$project$Search$Search$SearchIndex)))))))))))))));
+var $elm$json$Json$Decode$andThen = _Json_andThen;
+var $author$project$Search$Search$Posting = F4(
+	function (page, field, length, positions) {
+		return {dP: field, d1: length, cz: page, dQ: positions};
+	});
+var $elm$json$Json$Decode$fail = _Json_fail;
+var $author$project$Search$Search$postingDecoder = A2(
+	$elm$json$Json$Decode$andThen,
+	function (values) {
+		if ((values.b && values.b.b) && values.b.b.b) {
+			var page = values.a;
+			var _v1 = values.b;
+			var field = _v1.a;
+			var _v2 = _v1.b;
+			var length = _v2.a;
+			var positions = _v2.b;
+			return $elm$json$Json$Decode$succeed(
+				A4($author$project$Search$Search$Posting, page, field, length, positions));
+		} else {
+			return $elm$json$Json$Decode$fail('a posting needs a page, a field and a length');
+		}
+	},
+	$elm$json$Json$Decode$list($elm$json$Json$Decode$int));
+var $author$project$Search$Search$TermShard = F2(
+	function (terms, filters) {
+		return {dY: filters, dL: terms};
+	});
+var $author$project$Search$Search$termShardDecoder = A3(
+	$elm$json$Json$Decode$map2,
+	$author$project$Search$Search$TermShard,
 	A2(
 		$elm$json$Json$Decode$field,
-		'tags',
-		$elm$json$Json$Decode$list($elm$json$Json$Decode$string)));
-var $author$project$Search$Search$searchIndexDecoder = A2(
-	$elm$json$Json$Decode$field,
-	'pages',
-	$elm$json$Json$Decode$list($author$project$Search$Search$searchEntryDecoder));
+		'terms',
+		$elm$json$Json$Decode$dict(
+			$elm$json$Json$Decode$list($author$project$Search$Search$postingDecoder))),
+	A3(
+		$author$project$Search$Search$optionalField,
+		'filters',
+		$elm$core$Dict$empty,
+		$elm$json$Json$Decode$dict(
+			$elm$json$Json$Decode$list($elm$json$Json$Decode$int))));
+var $author$project$Search$Search$pageShardDecoder = $elm$json$Json$Decode$list($author$project$Search$Search$searchEntryDecoder);
+var $author$project$Update$siteUrl = F2(
+	function (base, path) {
+		return A2($elm$core$String$endsWith, '/', base) ? _Utils_ap(base, path) : _Utils_ap(base, '/' + path);
+	});
 var $author$project$Update$fetchSearchIndex = function (base) {
-	var url = A2($elm$core$String$endsWith, '/', base) ? (base + 'search-index.json') : (base + '/search-index.json');
 	return $elm$http$Http$get(
 		{
 			b0: A2($elm$http$Http$expectJson, $author$project$Msg$GotSearchIndex, $author$project$Search$Search$searchIndexDecoder),
-			cU: url
+			cU: A2($author$project$Update$siteUrl, base, 'search-index.json')
 		});
 };
+var $author$project$Msg$GotPageShard = F2(
+	function (a, b) {
+		return {$: 9, a: a, b: b};
+	});
+var $author$project$Msg$GotTermShard = F2(
+	function (a, b) {
+		return {$: 8, a: a, b: b};
+	});
+var $author$project$Update$fetchShard = F2(
+	function (base, _v0) {
+		var shard = _v0.a;
+		var path = _v0.b;
+		if (!shard.$) {
+			var key = shard.a;
+			return $elm$http$Http$get(
+				{
+					b0: A2(
+						$elm$http$Http$expectJson,
+						$author$project$Msg$GotTermShard(key),
+						$author$project$Search$Search$termShardDecoder),
+					cU: A2($author$project$Update$siteUrl, base, path)
+				});
+		} else {
+			var number = shard.a;
+			return $elm$http$Http$get(
+				{
+					b0: A2(
+						$elm$http$Http$expectJson,
+						$author$project$Msg$GotPageShard(number),
+						$author$project$Search$Search$pageShardDecoder),
+					cU: A2($author$project$Update$siteUrl, base, path)
+				});
+		}
+	});
+var $author$project$Search$Search$Pages = function (a) {
+	return {$: 1, a: a};
+};
+var $author$project$Search$Search$Terms = function (a) {
+	return {$: 0, a: a};
+};
+var $author$project$Search$Search$filterTerm = function (_v0) {
+	var key = _v0.a;
+	var value = _v0.b;
+	return key + (':' + value);
+};
+var $elm$core$Dict$member = F2(
+	function (key, dict) {
+		var _v0 = A2($elm$core$Dict$get, key, dict);
+		if (!_v0.$) {
+			return true;
+		} else {
+			return false;
+		}
+	});
+var $elm$core$Set$member = F2(
+	function (key, _v0) {
+		var dict = _v0;
+		return A2($elm$core$Dict$member, key, dict);
+	});
+var $author$project$Search$Search$shardId = function (shard) {
+	if (!shard.$) {
+		var key = shard.a;
+		return 'terms-' + key;
+	} else {
+		var number = shard.a;
+		return 'pages-' + $elm$core$String$fromInt(number);
+	}
+};
+var $elm$core$String$cons = _String_cons;
+var $elm$core$String$fromChar = function (_char) {
+	return A2($elm$core$String$cons, _char, '');
+};
+var $author$project$Search$Search$shardKey = function (term) {
+	var _v0 = $elm$core$String$uncons(term);
+	if (!_v0.$) {
+		var _v1 = _v0.a;
+		var c = _v1.a;
+		var rest = _v1.b;
+		if ($elm$core$Char$isAlphaNum(c)) {
+			var _v2 = $elm$core$String$uncons(rest);
+			if (!_v2.$) {
+				var _v3 = _v2.a;
+				var d = _v3.a;
+				return $elm$core$Char$isAlphaNum(d) ? $elm$core$String$fromList(
+					_List_fromArray(
+						[c, d])) : $elm$core$String$fromChar(c);
+			} else {
+				return $elm$core$String$fromChar(c);
+			}
+		} else {
+			return 'u' + $elm$core$String$fromInt(
+				($elm$core$Char$toCode(c) / 256) | 0);
+		}
+	} else {
+		return '';
+	}
+};
+var $author$project$Search$Search$requestShards = F2(
+	function (shards, index) {
+		return _Utils_update(
+			index,
+			{
+				d0: A3(
+					$elm$core$List$foldl,
+					function (_v0) {
+						var shard = _v0.a;
+						return $elm$core$Set$insert(
+							$author$project$Search$Search$shardId(shard));
+					},
+					index.d0,
+					shards)
+			});
+	});
+var $author$project$Search$Search$addTermShard = F3(
+	function (key, shard, index) {
+		return _Utils_update(
+			index,
+			{
+				dY: A2($elm$core$Dict$union, shard.dY, index.dY),
+				dZ: A2(
+					$elm$core$Set$insert,
+					$author$project$Search$Search$shardId(
+						$author$project$Search$Search$Terms(key)),
+					index.dZ),
+				dL: A2($elm$core$Dict$union, shard.dL, index.dL)
+			});
+	});
+var $author$project$Search$Search$addPageShard = F3(
+	function (number, entries, index) {
+		return _Utils_update(
+			index,
+			{
+				dZ: A2(
+					$elm$core$Set$insert,
+					$author$project$Search$Search$shardId(
+						$author$project$Search$Search$Pages(number)),
+					index.dZ),
+				dK: A3(
+					$elm$core$List$foldl,
+					function (_v0) {
+						var page = _v0.a;
+						var entry = _v0.b;
+						return A2($elm$core$Dict$insert, page, entry);
+					},
+					index.dK,
+					A2(
+						$elm$core$List$indexedMap,
+						F2(
+							function (i, entry) {
+								return _Utils_Tuple2((number * index.dW) + i, entry);
+							}),
+						entries))
+			});
+	});
 var $elm$core$List$any = F2(
 	function (isOkay, list) {
 		any:
@@ -6772,27 +7202,6 @@ var $author$project$Search$Search$extractContext = F2(
 				_Utils_ap(snippet, suffix));
 		}
 	});
-var $author$project$Search$Search$matchEntry = F2(
-	function (lowerQuery, entry) {
-		var lowerTitle = $elm$core$String$toLower(entry.aM);
-		var titleMatch = A2($elm$core$String$contains, lowerQuery, lowerTitle);
-		var lowerTags = A2($elm$core$List$map, $elm$core$String$toLower, entry.bB);
-		var tagMatch = A2(
-			$elm$core$List$any,
-			$elm$core$String$contains(lowerQuery),
-			lowerTags);
-		var lowerDesc = $elm$core$String$toLower(entry.aW);
-		var lowerContent = $elm$core$String$toLower(entry.bV);
-		var descMatch = A2($elm$core$String$contains, lowerQuery, lowerDesc);
-		var contentMatch = A2($elm$core$String$contains, lowerQuery, lowerContent);
-		return (titleMatch || (descMatch || (contentMatch || tagMatch))) ? $elm$core$Maybe$Just(
-			{
-				aW: entry.aW,
-				ch: A2($author$project$Search$Search$extractContext, lowerQuery, entry.bV),
-				aM: entry.aM,
-				cU: entry.cU
-			}) : $elm$core$Maybe$Nothing;
-	});
 var $elm$core$List$takeReverse = F3(
 	function (n, list, kept) {
 		takeReverse:
@@ -6915,36 +7324,658 @@ var $elm$core$List$takeFast = F3(
 				[x]);
 		}
 	});
-var $elm$core$List$take = F2(
-	function (n, list) {
-		return A3($elm$core$List$takeFast, 0, n, list);
-	});
-var $author$project$Search$Search$filterResults = F2(
-	function (query, entries) {
-		if ($elm$core$String$length(query) < 2) {
+var $elm$core$List$take = F2(
+	function (n, list) {
+		return A3($elm$core$List$takeFast, 0, n, list);
+	});
+var $elm$core$List$filter = F2(
+	function (isGood, list) {
+		return A3(
+			$elm$core$List$foldr,
+			F2(
+				function (x, xs) {
+					return isGood(x) ? A2($elm$core$List$cons, x, xs) : xs;
+				}),
+			_List_Nil,
+			list);
+	});
+var $elm$core$Basics$composeL = F3(
+	function (g, f, x) {
+		return g(
+			f(x));
+	});
+var $elm$core$List$all = F2(
+	function (isOkay, list) {
+		return !A2(
+			$elm$core$List$any,
+			A2($elm$core$Basics$composeL, $elm$core$Basics$not, isOkay),
+			list);
+	});
+var $author$project$Search$Search$matchesFilters = F3(
+	function (index, filters, page) {
+		return A2(
+			$elm$core$List$all,
+			function (filter) {
+				return A2(
+					$elm$core$List$member,
+					page,
+					A2(
+						$elm$core$Maybe$withDefault,
+						_List_Nil,
+						A2(
+							$elm$core$Dict$get,
+							$author$project$Search$Search$filterTerm(filter),
+							index.dY)));
+			},
+			filters);
+	});
+var $elm$core$List$append = F2(
+	function (xs, ys) {
+		if (!ys.b) {
+			return xs;
+		} else {
+			return A3($elm$core$List$foldr, $elm$core$List$cons, ys, xs);
+		}
+	});
+var $elm$core$List$concat = function (lists) {
+	return A3($elm$core$List$foldr, $elm$core$List$append, _List_Nil, lists);
+};
+var $elm$core$List$concatMap = F2(
+	function (f, list) {
+		return $elm$core$List$concat(
+			A2($elm$core$List$map, f, list));
+	});
+var $elm$core$List$member = F2(
+	function (x, xs) {
+		return A2(
+			$elm$core$List$any,
+			function (a) {
+				return _Utils_eq(a, x);
+			},
+			xs);
+	});
+var $elm$core$String$words = _String_words;
+var $author$project$Search$Search$parseQuery = F2(
+	function (index, query) {
+		var words = $elm$core$String$words(query);
+		var toFilter = function (word) {
+			var _v0 = A2($elm$core$String$split, ':', word);
+			if ((_v0.b && _v0.b.b) && (!_v0.b.b.b)) {
+				var key = _v0.a;
+				var _v1 = _v0.b;
+				var value = _v1.a;
+				return ((value !== '') && A2($elm$core$List$member, key, index.dU)) ? $elm$core$Maybe$Just(
+					_Utils_Tuple2(
+						key,
+						$elm$core$String$toLower(value))) : $elm$core$Maybe$Nothing;
+			} else {
+				return $elm$core$Maybe$Nothing;
+			}
+		};
+		return _Utils_Tuple2(
+			A2(
+				$elm$core$String$join,
+				' ',
+				A2(
+					$elm$core$List$filter,
+					function (word) {
+						return _Utils_eq(
+							toFilter(word),
+							$elm$core$Maybe$Nothing);
+					},
+					words)),
+			A2($elm$core$List$filterMap, toFilter, words));
+	});
+var $elm$core$String$trim = _String_trim;
+var $elm$core$Char$fromCode = _Char_fromCode;
+var $author$project$Search$Tokenizer$foldWidth = function (c) {
+	var code = $elm$core$Char$toCode(c);
+	return ((code >= 65281) && (code <= 65374)) ? $elm$core$Char$fromCode(code - 65248) : ((code === 12288) ? _Utils_chr(' ') : c);
+};
+var $elm$core$String$map = _String_map;
+var $author$project$Search$Tokenizer$normalize = function (text) {
+	return $elm$core$String$toLower(
+		A2($elm$core$String$map, $author$project$Search$Tokenizer$foldWidth, text));
+};
+var $elm$core$String$fromList = _String_fromList;
+var $elm$core$List$drop = F2(
+	function (n, list) {
+		drop:
+		while (true) {
+			if (n <= 0) {
+				return list;
+			} else {
+				if (!list.b) {
+					return list;
+				} else {
+					var x = list.a;
+					var xs = list.b;
+					var $temp$n = n - 1,
+						$temp$list = xs;
+					n = $temp$n;
+					list = $temp$list;
+					continue drop;
+				}
+			}
+		}
+	});
+var $author$project$Search$Tokenizer$runTokens = function (_v0) {
+	var s = _v0.a;
+	var run = _v0.b;
+	var _v1 = _Utils_Tuple2(s, run);
+	if (!_v1.a) {
+		return _List_fromArray(
+			[
+				$elm$core$String$fromList(run)
+			]);
+	} else {
+		if (_v1.b.b && (!_v1.b.b.b)) {
+			return _List_fromArray(
+				[
+					$elm$core$String$fromList(run)
+				]);
+		} else {
+			return A3(
+				$elm$core$List$map2,
+				F2(
+					function (a, b) {
+						return $elm$core$String$fromList(
+							_List_fromArray(
+								[a, b]));
+					}),
+				run,
+				A2($elm$core$List$drop, 1, run));
+		}
+	}
+};
+var $author$project$Search$Tokenizer$script = function (c) {
+	var code = $elm$core$Char$toCode(c);
+	var between = F2(
+		function (low, high) {
+			return (_Utils_cmp(code, low) > -1) && (_Utils_cmp(code, high) < 1);
+		});
+	return (A2(between, 4352, 4607) || (A2(between, 12592, 12687) || A2(between, 44032, 55203))) ? 1 : ((A2(between, 12352, 12543) || (A2(between, 12784, 12799) || (A2(between, 13312, 19903) || (A2(between, 19968, 40959) || A2(between, 63744, 64255))))) ? 2 : (((code <= 32) || (A2(
+		$elm$core$List$member,
+		code,
+		_List_fromArray(
+			[133, 160, 5760])) || (A2(between, 33, 47) || (A2(between, 58, 64) || (A2(between, 91, 96) || (A2(between, 123, 126) || (A2(between, 8192, 8303) || A2(between, 12288, 12351)))))))) ? 3 : 0));
+};
+var $author$project$Search$Tokenizer$runs = function (chars) {
+	var step = F2(
+		function (c, acc) {
+			var s = $author$project$Search$Tokenizer$script(c);
+			if (acc.b) {
+				var _v1 = acc.a;
+				var runScript = _v1.a;
+				var run = _v1.b;
+				var rest = acc.b;
+				return _Utils_eq(runScript, s) ? A2(
+					$elm$core$List$cons,
+					_Utils_Tuple2(
+						runScript,
+						A2($elm$core$List$cons, c, run)),
+					rest) : A2(
+					$elm$core$List$cons,
+					_Utils_Tuple2(
+						s,
+						_List_fromArray(
+							[c])),
+					acc);
+			} else {
+				return _List_fromArray(
+					[
+						_Utils_Tuple2(
+						s,
+						_List_fromArray(
+							[c]))
+					]);
+			}
+		});
+	return A2(
+		$elm$core$List$map,
+		function (_v2) {
+			var s = _v2.a;
+			var run = _v2.b;
+			return _Utils_Tuple2(
+				s,
+				$elm$core$List$reverse(run));
+		},
+		A2(
+			$elm$core$List$filter,
+			function (_v0) {
+				var s = _v0.a;
+				return s !== 3;
+			},
+			$elm$core$List$reverse(
+				A3($elm$core$List$foldl, step, _List_Nil, chars))));
+};
+var $elm$core$String$foldr = _String_foldr;
+var $elm$core$String$toList = function (string) {
+	return A3($elm$core$String$foldr, $elm$core$List$cons, _List_Nil, string);
+};
+var $author$project$Search$Tokenizer$isSignificant = function (token) {
+	var _v0 = $elm$core$String$toList(token);
+	if (!_v0.b) {
+		return false;
+	} else {
+		if (!_v0.b.b) {
+			var c = _v0.a;
+			return _Utils_eq(
+				$author$project$Search$Tokenizer$script(c),
+				1) || _Utils_eq(
+				$author$project$Search$Tokenizer$script(c),
+				2);
+		} else {
+			return true;
+		}
+	}
+};
+var $author$project$Search$Tokenizer$tokenize = function (text) {
+	return A2(
+		$elm$core$List$concatMap,
+		$author$project$Search$Tokenizer$runTokens,
+		$author$project$Search$Tokenizer$runs(
+			$elm$core$String$toList(
+				$author$project$Search$Tokenizer$normalize(text))));
+};
+var $author$project$Search$Search$searchTerms = F2(
+	function (index, text) {
+		return A2(
+			$elm$core$List$filter,
+			function (term) {
+				return !A2($elm$core$List$member, term, index.d3);
+			},
+			$author$project$Search$Tokenizer$tokenize(text));
+	});
+var $author$project$Search$Search$unique = function (items) {
+	return A3(
+		$elm$core$List$foldl,
+		F2(
+			function (item, acc) {
+				return A2($elm$core$List$member, item, acc) ? acc : _Utils_ap(
+					acc,
+					_List_fromArray(
+						[item]));
+			}),
+		_List_Nil,
+		items);
+};
+var $elm$core$List$sortBy = _List_sortBy;
+var $author$project$Search$Search$intersect = F2(
+	function (next, matches) {
+		return A6(
+			$elm$core$Dict$merge,
+			F3(
+				function (_v0, _v1, result) {
+					return result;
+				}),
+			F4(
+				function (page, a, c, result) {
+					return A3(
+						$elm$core$Dict$insert,
+						page,
+						{
+							dR: a.dR + c.dR,
+							dL: _Utils_ap(a.dL, c.dL)
+						},
+						result);
+				}),
+			F3(
+				function (_v2, _v3, result) {
+					return result;
+				}),
+			matches,
+			next,
+			$elm$core$Dict$empty);
+	});
+var $elm$core$Basics$abs = function (n) {
+	return (n < 0) ? (-n) : n;
+};
+var $elm$core$List$map3 = _List_map3;
+var $author$project$Search$Search$editDistance = F2(
+	function (source, target) {
+		var targetChars = $elm$core$String$toList(target);
+		var nextRow = F2(
+			function (_char, _v0) {
+				var i = _v0.a;
+				var previous = _v0.b;
+				var step = F2(
+					function (_v2, _v3) {
+						var targetChar = _v2.a;
+						var diagonal = _v2.b;
+						var up = _v2.c;
+						var left = _v3.a;
+						var cells = _v3.b;
+						var cost = _Utils_eq(_char, targetChar) ? 0 : 1;
+						var distance = A2(
+							$elm$core$Basics$min,
+							A2($elm$core$Basics$min, up + 1, left + 1),
+							diagonal + cost);
+						return _Utils_Tuple2(
+							distance,
+							A2($elm$core$List$cons, distance, cells));
+					});
+				var _v1 = A3(
+					$elm$core$List$foldl,
+					step,
+					_Utils_Tuple2(
+						i,
+						_List_fromArray(
+							[i])),
+					A4(
+						$elm$core$List$map3,
+						F3(
+							function (c, d, u) {
+								return _Utils_Tuple3(c, d, u);
+							}),
+						targetChars,
+						previous,
+						A2($elm$core$List$drop, 1, previous)));
+				var row = _v1.b;
+				return _Utils_Tuple2(
+					i + 1,
+					$elm$core$List$reverse(row));
+			});
+		return A2(
+			$elm$core$Maybe$withDefault,
+			0,
+			$elm$core$List$head(
+				$elm$core$List$reverse(
+					$elm$core$Tuple$second(
+						A3(
+							$elm$core$List$foldl,
+							nextRow,
+							_Utils_Tuple2(
+								1,
+								A2(
+									$elm$core$List$range,
+									0,
+									$elm$core$List$length(targetChars))),
+							$elm$core$String$toList(source))))));
+	});
+var $author$project$Search$Search$prefixFactor = 0.7;
+var $author$project$Search$Search$typoBudget = function (term) {
+	return ($elm$core$String$length(term) < 4) ? 0 : (($elm$core$String$length(term) < 8) ? 1 : 2);
+};
+var $author$project$Search$Search$typoFactor = 0.5;
+var $author$project$Search$Search$expand = F2(
+	function (index, queryTerm) {
+		var prefixed = (!$author$project$Search$Tokenizer$isSignificant(queryTerm)) ? _List_Nil : A3(
+			$elm$core$Dict$foldl,
+			F3(
+				function (term, postings, acc) {
+					return ((!_Utils_eq(term, queryTerm)) && A2($elm$core$String$startsWith, queryTerm, term)) ? A2(
+						$elm$core$List$cons,
+						_Utils_Tuple3(term, $author$project$Search$Search$prefixFactor, postings),
+						acc) : acc;
+				}),
+			_List_Nil,
+			index.dL);
+		var maxEdits = $author$project$Search$Search$typoBudget(queryTerm);
+		var nearby = function (term) {
+			return (_Utils_cmp(
+				$elm$core$Basics$abs(
+					$elm$core$String$length(term) - $elm$core$String$length(queryTerm)),
+				maxEdits) < 1) && (_Utils_cmp(
+				A2($author$project$Search$Search$editDistance, queryTerm, term),
+				maxEdits) < 1);
+		};
+		var exact = function () {
+			var _v0 = A2($elm$core$Dict$get, queryTerm, index.dL);
+			if (!_v0.$) {
+				var postings = _v0.a;
+				return _List_fromArray(
+					[
+						_Utils_Tuple3(queryTerm, 1, postings)
+					]);
+			} else {
+				return _List_Nil;
+			}
+		}();
+		return ($elm$core$List$isEmpty(exact) && ($elm$core$List$isEmpty(prefixed) && (maxEdits > 0))) ? A3(
+			$elm$core$Dict$foldl,
+			F3(
+				function (term, postings, acc) {
+					return nearby(term) ? A2(
+						$elm$core$List$cons,
+						_Utils_Tuple3(term, $author$project$Search$Search$typoFactor, postings),
+						acc) : acc;
+				}),
+			_List_Nil,
+			index.dL) : _Utils_ap(exact, prefixed);
+	});
+var $elm$core$Basics$e = _Basics_e;
+var $elm$core$Dict$sizeHelp = F2(
+	function (n, dict) {
+		sizeHelp:
+		while (true) {
+			if (dict.$ === -2) {
+				return n;
+			} else {
+				var left = dict.d;
+				var right = dict.e;
+				var $temp$n = A2($elm$core$Dict$sizeHelp, n + 1, right),
+					$temp$dict = left;
+				n = $temp$n;
+				dict = $temp$dict;
+				continue sizeHelp;
+			}
+		}
+	});
+var $elm$core$Dict$size = function (dict) {
+	return A2($elm$core$Dict$sizeHelp, 0, dict);
+};
+var $elm$core$Set$size = function (_v0) {
+	var dict = _v0;
+	return $elm$core$Dict$size(dict);
+};
+var $author$project$Search$Search$idf = F2(
+	function (index, postings) {
+		var n = index.dT;
+		var df = $elm$core$Set$size(
+			$elm$core$Set$fromList(
+				A2(
+					$elm$core$List$map,
+					function ($) {
+						return $.cz;
+					},
+					postings)));
+		return A2($elm$core$Basics$logBase, $elm$core$Basics$e, 1 + (((n - df) + 0.5) / (df + 0.5)));
+	});
+var $author$project$Search$Search$b = 0.75;
+var $author$project$Search$Search$k1 = 1.2;
+var $elm$core$Array$bitMask = 4294967295 >>> (32 - $elm$core$Array$shiftStep);
+var $elm$core$Elm$JsArray$unsafeGet = _JsArray_unsafeGet;
+var $elm$core$Array$getHelp = F3(
+	function (shift, index, tree) {
+		getHelp:
+		while (true) {
+			var pos = $elm$core$Array$bitMask & (index >>> shift);
+			var _v0 = A2($elm$core$Elm$JsArray$unsafeGet, pos, tree);
+			if (!_v0.$) {
+				var subTree = _v0.a;
+				var $temp$shift = shift - $elm$core$Array$shiftStep,
+					$temp$index = index,
+					$temp$tree = subTree;
+				shift = $temp$shift;
+				index = $temp$index;
+				tree = $temp$tree;
+				continue getHelp;
+			} else {
+				var values = _v0.a;
+				return A2($elm$core$Elm$JsArray$unsafeGet, $elm$core$Array$bitMask & index, values);
+			}
+		}
+	});
+var $elm$core$Array$tailIndex = function (len) {
+	return (len >>> 5) << 5;
+};
+var $elm$core$Array$get = F2(
+	function (index, _v0) {
+		var len = _v0.a;
+		var startShift = _v0.b;
+		var tree = _v0.c;
+		var tail = _v0.d;
+		return ((index < 0) || (_Utils_cmp(index, len) > -1)) ? $elm$core$Maybe$Nothing : ((_Utils_cmp(
+			index,
+			$elm$core$Array$tailIndex(len)) > -1) ? $elm$core$Maybe$Just(
+			A2($elm$core$Elm$JsArray$unsafeGet, $elm$core$Array$bitMask & index, tail)) : $elm$core$Maybe$Just(
+			A3($elm$core$Array$getHelp, startShift, index, tree)));
+	});
+var $author$project$Search$Search$fieldScore = F2(
+	function (index, posting) {
+		var weight = A2(
+			$elm$core$Maybe$withDefault,
+			1,
+			A2($elm$core$Array$get, posting.dP, index.dM));
+		var tf = $elm$core$List$length(posting.dQ);
+		var average = A2(
+			$elm$core$Maybe$withDefault,
+			0,
+			A2($elm$core$Array$get, posting.dP, index.dN));
+		var length = posting.d1;
+		var norm = (average > 0) ? ((1 - $author$project$Search$Search$b) + (($author$project$Search$Search$b * length) / average)) : 1;
+		return (((weight * tf) * ($author$project$Search$Search$k1 + 1)) / (tf + ($author$project$Search$Search$k1 * norm)));
+	});
+var $author$project$Search$Search$scorePostings = F2(
+	function (index, postings) {
+		return A3(
+			$elm$core$List$foldl,
+			F2(
+				function (posting, scores) {
+					return A3(
+						$elm$core$Dict$update,
+						posting.cz,
+						function (score) {
+							return $elm$core$Maybe$Just(
+								A2($elm$core$Maybe$withDefault, 0, score) + A2($author$project$Search$Search$fieldScore, index, posting));
+						},
+						scores);
+				}),
+			$elm$core$Dict$empty,
+			postings);
+	});
+var $author$project$Search$Search$termMatches = F2(
+	function (index, queryTerm) {
+		var addExpansion = F2(
+			function (_v0, best) {
+				var term = _v0.a;
+				var factor = _v0.b;
+				var postings = _v0.c;
+				var weight = factor * A2($author$project$Search$Search$idf, index, postings);
+				var keepBest = F3(
+					function (page, score, matches) {
+						var _v1 = A2($elm$core$Dict$get, page, matches);
+						if (!_v1.$) {
+							var match = _v1.a;
+							return (_Utils_cmp(match.dR, weight * score) > -1) ? matches : A3(
+								$elm$core$Dict$insert,
+								page,
+								{
+									dR: weight * score,
+									dL: _List_fromArray(
+										[term])
+								},
+								matches);
+						} else {
+							return A3(
+								$elm$core$Dict$insert,
+								page,
+								{
+									dR: weight * score,
+									dL: _List_fromArray(
+										[term])
+								},
+								matches);
+						}
+					});
+				return A3(
+					$elm$core$Dict$foldl,
+					keepBest,
+					best,
+					A2($author$project$Search$Search$scorePostings, index, postings));
+			});
+		return A3(
+			$elm$core$List$foldl,
+			addExpansion,
+			$elm$core$Dict$empty,
+			A2($author$project$Search$Search$expand, index, queryTerm));
+	});
+var $elm$core$Dict$map = F2(
+	function (func, dict) {
+		if (dict.$ === -2) {
+			return $elm$core$Dict$RBEmpty_elm_builtin;
+		} else {
+			var color = dict.a;
+			var key = dict.b;
+			var value = dict.c;
+			var left = dict.d;
+			var right = dict.e;
+			return A5(
+				$elm$core$Dict$RBNode_elm_builtin,
+				color,
+				key,
+				A2(func, key, value),
+				A2($elm$core$Dict$map, func, left),
+				A2($elm$core$Dict$map, func, right));
+		}
+	});
+var $author$project$Search$Search$rank = F2(
+	function (index, queryTerms) {
+		var _v0 = A2(
+			$elm$core$List$map,
+			$author$project$Search$Search$termMatches(index),
+			queryTerms);
+		if (!_v0.b) {
 			return _List_Nil;
 		} else {
-			var lowerQuery = $elm$core$String$toLower(query);
+			var first = _v0.a;
+			var rest = _v0.b;
 			return A2(
-				$elm$core$List$take,
-				10,
-				A2(
-					$elm$core$List$filterMap,
-					$author$project$Search$Search$matchEntry(lowerQuery),
-					entries));
+				$elm$core$List$sortBy,
+				function (_v1) {
+					var match = _v1.b;
+					return -match.dR;
+				},
+				$elm$core$Dict$toList(
+					A2(
+						$elm$core$Dict$map,
+						F2(
+							function (page, match) {
+								return _Utils_update(
+									match,
+									{
+										dR: match.dR * A2(
+											$elm$core$Maybe$withDefault,
+											1,
+											A2($elm$core$Dict$get, page, index.d2))
+									});
+							}),
+						A3($elm$core$List$foldl, $author$project$Search$Search$intersect, first, rest))));
 		}
 	});
-var $elm$core$List$filter = F2(
-	function (isGood, list) {
-		return A3(
-			$elm$core$List$foldr,
-			F2(
-				function (x, xs) {
-					return isGood(x) ? A2($elm$core$List$cons, x, xs) : xs;
-				}),
-			_List_Nil,
-			list);
-	});
+var $author$project$Search$Search$toResult = function (_v0) {
+	var entry = _v0.a;
+	var match = _v0.b;
+	var lowerContent = $elm$core$String$toLower(entry.bV);
+	var contextTerm = A2(
+		$elm$core$Maybe$withDefault,
+		'',
+		$elm$core$List$head(
+			A2(
+				$elm$core$List$filter,
+				function (term) {
+					return A2($elm$core$String$contains, term, lowerContent);
+				},
+				match.dL)));
+	return {
+		dS: entry.dS,
+		aW: entry.aW,
+		ch: A2($author$project$Search$Search$extractContext, contextTerm, entry.bV),
+		aM: entry.aM,
+		cU: entry.cU
+	};
+};
 var $elm$core$List$head = function (list) {
 	if (list.b) {
 		var x = list.a;
@@ -6954,6 +7985,156 @@ var $elm$core$List$head = function (list) {
 		return $elm$core$Maybe$Nothing;
 	}
 };
+var $elm$core$Maybe$andThen = F2(
+	function (callback, maybeValue) {
+		if (!maybeValue.$) {
+			var value = maybeValue.a;
+			return callback(value);
+		} else {
+			return $elm$core$Maybe$Nothing;
+		}
+	});
+var $author$project$Search$Search$topMatches = F2(
+	function (query, index) {
+		var _v0 = A2($author$project$Search$Search$parseQuery, index, query);
+		var text = _v0.a;
+		var filters = _v0.b;
+		var queryTerms = $author$project$Search$Search$unique(
+			A2($author$project$Search$Search$searchTerms, index, text));
+		return ($elm$core$List$isEmpty(filters) && (!A2($elm$core$List$any, $author$project$Search$Tokenizer$isSignificant, queryTerms))) ? _List_Nil : A2(
+			$elm$core$List$take,
+			10,
+			A2(
+				$elm$core$List$filter,
+				A2(
+					$elm$core$Basics$composeR,
+					$elm$core$Tuple$first,
+					A2($author$project$Search$Search$matchesFilters, index, filters)),
+				$elm$core$List$isEmpty(queryTerms) ? A2(
+					$elm$core$List$map,
+					function (page) {
+						return _Utils_Tuple2(
+							page,
+							{dR: 0, dL: _List_Nil});
+					},
+					A2(
+						$elm$core$Maybe$withDefault,
+						_List_Nil,
+						A2(
+							$elm$core$Maybe$andThen,
+							function (filter) {
+								return A2(
+									$elm$core$Dict$get,
+									$author$project$Search$Search$filterTerm(filter),
+									index.dY);
+							},
+							$elm$core$List$head(filters)))) : A2($author$project$Search$Search$rank, index, queryTerms)));
+	});
+var $author$project$Search$Search$filterResults = F2(
+	function (query, index) {
+		return A2(
+			$elm$core$List$map,
+			$author$project$Search$Search$toResult,
+			A2(
+				$elm$core$List$filterMap,
+				function (_v0) {
+					var page = _v0.a;
+					var match = _v0.b;
+					return A2(
+						$elm$core$Maybe$map,
+						function (entry) {
+							return _Utils_Tuple2(entry, match);
+						},
+						A2($elm$core$Dict$get, page, index.dK));
+				},
+				A2($author$project$Search$Search$topMatches, query, index)));
+	});
+var $author$project$Search$Search$missingShards = F2(
+	function (query, index) {
+		var notRequested = function (_v3) {
+			var shard = _v3.a;
+			return !A2(
+				$elm$core$Set$member,
+				$author$project$Search$Search$shardId(shard),
+				index.d0);
+		};
+		var notLoaded = function (_v2) {
+			var shard = _v2.a;
+			return !A2(
+				$elm$core$Set$member,
+				$author$project$Search$Search$shardId(shard),
+				index.dZ);
+		};
+		var _v0 = A2($author$project$Search$Search$parseQuery, index, query);
+		var text = _v0.a;
+		var filters = _v0.b;
+		var termShards = A2(
+			$elm$core$List$filter,
+			notLoaded,
+			A2(
+				$elm$core$List$filterMap,
+				function (key) {
+					return A2(
+						$elm$core$Maybe$map,
+						function (path) {
+							return _Utils_Tuple2(
+								$author$project$Search$Search$Terms(key),
+								path);
+						},
+						A2($elm$core$Dict$get, key, index.dV));
+				},
+				$author$project$Search$Search$unique(
+					A2(
+						$elm$core$List$map,
+						$author$project$Search$Search$shardKey,
+						_Utils_ap(
+							A2($author$project$Search$Search$searchTerms, index, text),
+							A2($elm$core$List$map, $author$project$Search$Search$filterTerm, filters))))));
+		return $elm$core$List$isEmpty(termShards) ? A2(
+			$elm$core$List$filter,
+			notRequested,
+			A2(
+				$elm$core$List$filter,
+				notLoaded,
+				A2(
+					$elm$core$List$filterMap,
+					function (number) {
+						return A2(
+							$elm$core$Maybe$map,
+							function (path) {
+								return _Utils_Tuple2(
+									$author$project$Search$Search$Pages(number),
+									path);
+							},
+							A2($elm$core$Array$get, number, index.dX));
+					},
+					$author$project$Search$Search$unique(
+						A2(
+							$elm$core$List$map,
+							function (_v1) {
+								var page = _v1.a;
+								return (page / index.dW) | 0;
+							},
+							A2($author$project$Search$Search$topMatches, query, index)))))) : A2($elm$core$List$filter, notRequested, termShards);
+	});
+var $author$project$Update$runSearch = F3(
+	function (query, index, model) {
+		var shards = A2($author$project$Search$Search$missingShards, query, index);
+		return _Utils_Tuple2(
+			_Utils_update(
+				model,
+				{
+					aA: $author$project$Search$Search$Loaded(
+						A2($author$project$Search$Search$requestShards, shards, index)),
+					aC: query,
+					aD: A2($author$project$Search$Search$filterResults, query, index)
+				}),
+			$elm$core$Platform$Cmd$batch(
+				A2(
+					$elm$core$List$map,
+					$author$project$Update$fetchShard(model.aG),
+					shards)));
+	});
 var $elm$core$Maybe$map = F2(
 	function (f, maybe) {
 		if (!maybe.$) {
@@ -7004,6 +8185,7 @@ var $author$project$Update$findActiveHeading = F2(
 var $elm$core$Basics$not = _Basics_not;
 var $elm$json$Json$Encode$string = _Json_wrap;
 var $author$project$Ports$scrollToElement = _Platform_outgoingPort('scrollToElement', $elm$json$Json$Encode$string);
+var $author$project$Ports$selectCodeGroupTab = _Platform_outgoingPort('selectCodeGroupTab', $elm$json$Json$Encode$string);
 var $author$project$Update$update = F2(
 	function (msg, model) {
 		switch (msg.$) {
@@ -7058,33 +8240,72 @@ var $author$project$Update$update = F2(
 					$elm$core$Platform$Cmd$none);
 			case 6:
 				var query = msg.a;
-				var results = function () {
-					var _v2 = model.aA;
-					if (_v2.$ === 2) {
-						var entries = _v2.a;
-						return A2($author$project$Search$Search$filterResults, query, entries);
-					} else {
-						return _List_Nil;
-					}
-				}();
-				return _Utils_Tuple2(
-					_Utils_update(
-						model,
-						{aC: query, aD: results}),
-					$elm$core$Platform$Cmd$none);
+				var _v2 = model.aA;
+				if (_v2.$ === 2) {
+					var index = _v2.a;
+					return A3($author$project$Update$runSearch, query, index, model);
+				} else {
+					return _Utils_Tuple2(
+						_Utils_update(
+							model,
+							{aC: query, aD: _List_Nil}),
+						$elm$core$Platform$Cmd$none);
+				}
 			case 7:
 				var result = msg.a;
 				if (!result.$) {
-					var entries = result.a;
-					var results = A2($author$project$Search$Search$filterResults, model.aC, entries);
+					var index = result.a;
+					return A3($author$project$Update$runSearch, model.aC, index, model);
+				} else {
+					return _Utils_Tuple2(
+						_Utils_update(
+							model,
+							{
+								aA: $author$project$Search$Search$Error('Failed to load search index')
+							}),
+						$elm$core$Platform$Cmd$none);
+				}
+			case 8:
+				var key = msg.a;
+				var result = msg.b;
+				var _v3 = _Utils_Tuple2(result, model.aA);
+				if (!_v3.a.$) {
+					if (_v3.b.$ === 2) {
+						var shard = _v3.a.a;
+						var index = _v3.b.a;
+						return A3(
+							$author$project$Update$runSearch,
+							model.aC,
+							A3($author$project$Search$Search$addTermShard, key, shard, index),
+							model);
+					} else {
+						return _Utils_Tuple2(model, $elm$core$Platform$Cmd$none);
+					}
+				} else {
 					return _Utils_Tuple2(
 						_Utils_update(
 							model,
 							{
-								aA: $author$project$Search$Search$Loaded(entries),
-								aD: results
+								aA: $author$project$Search$Search$Error('Failed to load search index')
 							}),
 						$elm$core$Platform$Cmd$none);
+				}
+			case 9:
+				var number = msg.a;
+				var result = msg.b;
+				var _v4 = _Utils_Tuple2(result, model.aA);
+				if (!_v4.a.$) {
+					if (_v4.b.$ === 2) {
+						var entries = _v4.a.a;
+						var index = _v4.b.a;
+						return A3(
+							$author$project$Update$runSearch,
+							model.aC,
+							A3($author$project$Search$Search$addPageShard, number, entries, index),
+							model);
+					} else {
+						return _Utils_Tuple2(model, $elm$core$Platform$Cmd$none);
+					}
 				} else {
 					return _Utils_Tuple2(
 						_Utils_update(
@@ -7094,18 +8315,26 @@ var $author$project$Update$update = F2(
 							}),
 						$elm$core$Platform$Cmd$none);
 				}
-			case 8:
+			case 10:
 				var key = msg.a;
 				return (key === 'Escape') ? _Utils_Tuple2(
 					_Utils_update(
 						model,
 						{aB: false, aC: '', aD: _List_Nil}),
 					$elm$core$Platform$Cmd$none) : _Utils_Tuple2(model, $elm$core$Platform$Cmd$none);
+			case 11:
+				var label = msg.a;
+				return _Utils_Tuple2(
+					_Utils_update(
+						model,
+						{
+							dB: $elm$core$Maybe$Just(label)
+						}),
+					$author$project$Ports$selectCodeGroupTab(label));
 			default:
 				return _Utils_Tuple2(model, $elm$core$Platform$Cmd$none);
 		}
 	});
-var $elm$json$Json$Decode$value = _Json_decodeValue;
 var $elm$html$Html$Attributes$stringProperty = F2(
 	function (key, string) {
 		return A2(
@@ -7225,16 +8454,40 @@ var $elm$html$Html$Attributes$href = function (url) {
 		'href',
 		_VirtualDom_noJavaScriptUri(url));
 };
+var $author$project$Search$Modal$viewBreadcrumb = function (breadcrumb) {
+	var _v0 = A2(
+		$elm$core$List$take,
+		$elm$core$List$length(breadcrumb) - 1,
+		breadcrumb);
+	if (!_v0.b) {
+		return $elm$html$Html$text('');
+	} else {
+		var parents = _v0;
+		return A2(
+			$elm$html$Html$div,
+			_List_fromArray(
+				[
+					$elm$html$Html$Attributes$class('pyohwa-search-result-breadcrumb')
+				]),
+			_List_fromArray(
+				[
+					$elm$html$Html$text(
+					A2($elm$core$String$join, ' › ', parents))
+				]));
+	}
+};
 var $author$project$Search$Modal$viewResult = function (result) {
 	return A2(
 		$elm$html$Html$a,
 		_List_fromArray(
 			[
 				$elm$html$Html$Attributes$class('pyohwa-search-result'),
-				$elm$html$Html$Attributes$href(result.cU)
+				$elm$html$Html$Attributes$href(result.cU),
+				$elm$html$Html$Events$onClick($author$project$Msg$CloseSearch)
 			]),
 		_List_fromArray(
 			[
+				$author$project$Search$Modal$viewBreadcrumb(result.dS),
 				A2(
 				$elm$html$Html$div,
 				_List_fromArray(
@@ -7475,80 +8728,82 @@ var $elm$virtual_dom$VirtualDom$property = F2(
 	});
 var $elm$html$Html$Attributes$property = $elm$virtual_dom$VirtualDom$property;
 var $elm$html$Html$footer = _VirtualDom_node('footer');
-var $author$project$Theme$Footer$viewNext = function (maybeNext) {
-	if (!maybeNext.$) {
-		var link = maybeNext.a;
-		return A2(
-			$elm$html$Html$a,
-			_List_fromArray(
-				[
-					$elm$html$Html$Attributes$class('pyohwa-footer-link pyohwa-footer-next'),
-					$elm$html$Html$Attributes$href(link.an)
-				]),
-			_List_fromArray(
-				[
-					A2(
-					$elm$html$Html$span,
-					_List_fromArray(
-						[
-							$elm$html$Html$Attributes$class('pyohwa-footer-label')
-						]),
-					_List_fromArray(
-						[
-							$elm$html$Html$text('Next')
-						])),
-					A2(
-					$elm$html$Html$span,
-					_List_fromArray(
-						[
-							$elm$html$Html$Attributes$class('pyohwa-footer-title')
-						]),
-					_List_fromArray(
-						[
-							$elm$html$Html$text(link.aM)
-						]))
-				]));
-	} else {
-		return A2($elm$html$Html$div, _List_Nil, _List_Nil);
-	}
-};
-var $author$project$Theme$Footer$viewPrev = function (maybePrev) {
-	if (!maybePrev.$) {
-		var link = maybePrev.a;
-		return A2(
-			$elm$html$Html$a,
-			_List_fromArray(
-				[
-					$elm$html$Html$Attributes$class('pyohwa-footer-link pyohwa-footer-prev'),
-					$elm$html$Html$Attributes$href(link.an)
-				]),
-			_List_fromArray(
-				[
-					A2(
-					$elm$html$Html$span,
-					_List_fromArray(
-						[
-							$elm$html$Html$Attributes$class('pyohwa-footer-label')
-						]),
-					_List_fromArray(
-						[
-							$elm$html$Html$text('Previous')
-						])),
-					A2(
-					$elm$html$Html$span,
-					_List_fromArray(
-						[
-							$elm$html$Html$Attributes$class('pyohwa-footer-title')
-						]),
-					_List_fromArray(
-						[
-							$elm$html$Html$text(link.aM)
-						]))
-				]));
-	} else {
-		return A2($elm$html$Html$div, _List_Nil, _List_Nil);
-	}
-};
+var $author$project$Theme$Footer$viewNext = F2(
+	function (label, maybeNext) {
+		if (!maybeNext.$) {
+			var link = maybeNext.a;
+			return A2(
+				$elm$html$Html$a,
+				_List_fromArray(
+					[
+						$elm$html$Html$Attributes$class('pyohwa-footer-link pyohwa-footer-next'),
+						$elm$html$Html$Attributes$href(link.an)
+					]),
+				_List_fromArray(
+					[
+						A2(
+						$elm$html$Html$span,
+						_List_fromArray(
+							[
+								$elm$html$Html$Attributes$class('pyohwa-footer-label')
+							]),
+						_List_fromArray(
+							[
+								$elm$html$Html$text(label)
+							])),
+						A2(
+						$elm$html$Html$span,
+						_List_fromArray(
+							[
+								$elm$html$Html$Attributes$class('pyohwa-footer-title')
+							]),
+						_List_fromArray(
+							[
+								$elm$html$Html$text(link.aM)
+							]))
+					]));
+		} else {
+			return A2($elm$html$Html$div, _List_Nil, _List_Nil);
+		}
+	});
+var $author$project$Theme$Footer$viewPrev = F2(
+	function (label, maybePrev) {
+		if (!maybePrev.$) {
+			var link = maybePrev.a;
+			return A2(
+				$elm$html$Html$a,
+				_List_fromArray(
+					[
+						$elm$html$Html$Attributes$class('pyohwa-footer-link pyohwa-footer-prev'),
+						$elm$html$Html$Attributes$href(link.an)
+					]),
+				_List_fromArray(
+					[
+						A2(
+						$elm$html$Html$span,
+						_List_fromArray(
+							[
+								$elm$html$Html$Attributes$class('pyohwa-footer-label')
+							]),
+						_List_fromArray(
+							[
+								$elm$html$Html$text(label)
+							])),
+						A2(
+						$elm$html$Html$span,
+						_List_fromArray(
+							[
+								$elm$html$Html$Attributes$class('pyohwa-footer-title')
+							]),
+						_List_fromArray(
+							[
+								$elm$html$Html$text(link.aM)
+							]))
+					]));
+		} else {
+			return A2($elm$html$Html$div, _List_Nil, _List_Nil);
+		}
+	});
 var $author$project$Theme$Footer$view = function (model) {
 	return A2(
 		$elm$html$Html$footer,
@@ -7566,8 +8821,8 @@ var $author$project$Theme$Footer$view = function (model) {
 					]),
 				_List_fromArray(
 					[
-						$author$project$Theme$Footer$viewPrev(model.ax),
-						$author$project$Theme$Footer$viewNext(model.aq)
+						A2($author$project$Theme$Footer$viewPrev, model.dC.dE, model.ax),
+						A2($author$project$Theme$Footer$viewNext, model.dC.aq, model.aq)
 					]))
 			]));
 };
@@ -7596,10 +8851,31 @@ var $author$project$Theme$Sidebar$viewItem = function (item) {
 					])),
 				$elm$html$Html$Attributes$href(item.an)
 			]),
-		_List_fromArray(
-			[
-				$elm$html$Html$text(item.ah)
-			]));
+		function () {
+			var _v0 = item.dG;
+			if (!_v0.$) {
+				var badge = _v0.a;
+				return _List_fromArray(
+					[
+						$elm$html$Html$text(item.ah + ' '),
+						A2(
+						$elm$html$Html$span,
+						_List_fromArray(
+							[
+								$elm$html$Html$Attributes$class('pyohwa-sidebar-badge')
+							]),
+						_List_fromArray(
+							[
+								$elm$html$Html$text(badge)
+							]))
+					]);
+			} else {
+				return _List_fromArray(
+					[
+						$elm$html$Html$text(item.ah)
+					]);
+			}
+		}());
 };
 var $author$project$Theme$Sidebar$viewGroup = function (group) {
 	return A2(
@@ -7704,7 +8980,7 @@ var $author$project$Theme$Toc$view = function (model) {
 					]),
 				_List_fromArray(
 					[
-						$elm$html$Html$text('On this page')
+						$elm$html$Html$text(model.dC.dD)
 					])),
 				A2(
 				$elm$html$Html$div,
@@ -7715,6 +8991,32 @@ var $author$project$Theme$Toc$view = function (model) {
 					model.aw))
 			]));
 };
+var $author$project$Msg$SelectCodeGroupTab = function (a) {
+	return {$: 11, a: a};
+};
+var $author$project$Theme$Layout$codeGroupTabDecoder = A2(
+	$elm$json$Json$Decode$map,
+	$author$project$Msg$SelectCodeGroupTab,
+	A2(
+		$elm$json$Json$Decode$at,
+		_List_fromArray(
+			['target', 'dataset', 'codeGroupTab']),
+		$elm$json$Json$Decode$string));
+var $author$project$Theme$Layout$viewProse = function (model) {
+	return A2(
+		$elm$html$Html$div,
+		_List_fromArray(
+			[
+				$elm$html$Html$Attributes$class('pyohwa-prose'),
+				$elm$html$Html$Attributes$id('content'),
+				A2(
+				$elm$html$Html$Attributes$property,
+				'innerHTML',
+				$elm$json$Json$Encode$string(model.as)),
+				A2($elm$html$Html$Events$on, 'click', $author$project$Theme$Layout$codeGroupTabDecoder)
+			]),
+		_List_Nil);
+};
 var $author$project$Theme$Layout$viewDocLayout = function (model) {
 	return A2(
 		$elm$html$Html$div,
@@ -7733,18 +9035,7 @@ var $author$project$Theme$Layout$viewDocLayout = function (model) {
 					]),
 				_List_fromArray(
 					[
-						A2(
-						$elm$html$Html$div,
-						_List_fromArray(
-							[
-								$elm$html$Html$Attributes$class('pyohwa-prose'),
-								$elm$html$Html$Attributes$id('content'),
-								A2(
-								$elm$html$Html$Attributes$property,
-								'innerHTML',
-								$elm$json$Json$Encode$string(model.as))
-							]),
-						_List_Nil),
+						$author$project$Theme$Layout$viewProse(model),
 						$author$project$Theme$Footer$view(model)
 					])),
 				$author$project$Theme$Toc$view(model)
@@ -7767,18 +9058,7 @@ var $author$project$Theme$Layout$viewHomeLayout = function (model) {
 					]),
 				_List_fromArray(
 					[
-						A2(
-						$elm$html$Html$div,
-						_List_fromArray(
-							[
-								$elm$html$Html$Attributes$class('pyohwa-prose'),
-								$elm$html$Html$Attributes$id('content'),
-								A2(
-								$elm$html$Html$Attributes$property,
-								'innerHTML',
-								$elm$json$Json$Encode$string(model.as))
-							]),
-						_List_Nil)
+						$author$project$Theme$Layout$viewProse(model)
 					]))
 			]));
 };
@@ -7799,18 +9079,7 @@ var $author$project$Theme$Layout$viewPageLayout = function (model) {
 					]),
 				_List_fromArray(
 					[
-						A2(
-						$elm$html$Html$div,
-						_List_fromArray(
-							[
-								$elm$html$Html$Attributes$class('pyohwa-prose'),
-								$elm$html$Html$Attributes$id('content'),
-								A2(
-								$elm$html$Html$Attributes$property,
-								'innerHTML',
-								$elm$json$Json$Encode$string(model.as))
-							]),
-						_List_Nil),
+						$author$project$Theme$Layout$viewProse(model),
 						$author$project$Theme$Footer$view(model)
 					]))
 			]));
@@ -7831,7 +9100,8 @@ var $author$project$Theme$Layout$view = function (model) {
 		$elm$html$Html$div,
 		_List_fromArray(
 			[
-				$elm$html$Html$Attributes$class('pyohwa-layout')
+				$elm$html$Html$Attributes$class('pyohwa-layout'),
+				A2($elm$html$Html$Attributes$attribute, 'data-layout', model.au)
 			]),
 		_List_fromArray(
 			[
@@ -1,5 +1,6 @@
-module Flags exposing (Flags, PrevNextLink, SearchData, decoder, prevNextLinkDecoder)
+module Flags exposing (Flags, Labels, Preferences, PrevNextLink, SearchData, decoder, defaultLabels, prevNextLinkDecoder)
 
+import Dict exposing (Dict)
 import Json.Decode as Decode exposing (Decoder)
 
 
@@ -8,6 +9,7 @@ type alias Flags =
     , site : SiteData
     , theme : ThemeData
     , search : SearchData
+    , preferences : Preferences
     }
 
 
@@ -17,6 +19,7 @@ type alias PageData =
     , content : String
     , toc : List TocItem
     , layout : String
+    , frontmatter : Dict String Decode.Value
     }
 
 
@@ -33,6 +36,24 @@ type alias SiteData =
     , base : String
     , nav : List NavItem
     , sidebar : List SidebarGroup
+    , labels : Labels
+    }
+
+
+{-| Theme text in the site's language, from the shared site data.
+-}
+type alias Labels =
+    { onThisPage : String
+    , previous : String
+    , next : String
+    }
+
+
+defaultLabels : Labels
+defaultLabels =
+    { onThisPage = "On this page"
+    , previous = "Previous"
+    , next = "Next"
     }
 
 
@@ -52,6 +73,7 @@ type alias SidebarGroup =
 type alias SidebarItem =
     { text : String
     , link : String
+    , badge : Maybe String
     , active : Bool
     }
 
@@ -66,6 +88,13 @@ type alias SearchData =
     }
 
 
+{-| Reader choices restored from localStorage by the page bootstrap script.
+-}
+type alias Preferences =
+    { codeGroupTab : Maybe String
+    }
+
+
 type alias PrevNextLink =
     { title : String
     , link : String
@@ -78,7 +107,7 @@ type alias PrevNextLink =
 
 decoder : Decoder Flags
 decoder =
-    Decode.map4 Flags
+    Decode.map5 Flags
         (Decode.field "page" pageDecoder)
         (Decode.field "site" siteDecoder)
         (Decode.field "theme" themeDecoder)
@@ -87,11 +116,16 @@ decoder =
             , Decode.succeed { enabled = True }
             ]
         )
+        (Decode.oneOf
+            [ Decode.field "preferences" preferencesDecoder
+            , Decode.succeed { codeGroupTab = Nothing }
+            ]
+        )
 
 
 pageDecoder : Decoder PageData
 pageDecoder =
-    Decode.map5 PageData
+    Decode.map6 PageData
         (Decode.field "title" Decode.string)
         (Decode.field "description" Decode.string)
         (Decode.field "content" Decode.string)
@@ -101,6 +135,11 @@ pageDecoder =
             , Decode.succeed "doc"
             ]
         )
+        (Decode.oneOf
+            [ Decode.field "frontmatter" (Decode.dict Decode.value)
+            , Decode.succeed Dict.empty
+            ]
+        )
 
 
 tocItemDecoder : Decoder TocItem
@@ -113,12 +152,25 @@ tocItemDecoder =
 
 siteDecoder : Decoder SiteData
 siteDecoder =
-    Decode.map5 SiteData
+    Decode.map6 SiteData
         (Decode.field "title" Decode.string)
         (Decode.field "description" Decode.string)
         (Decode.field "base" Decode.string)
         (Decode.field "nav" (Decode.list navItemDecoder))
         (Decode.field "sidebar" (Decode.list sidebarGroupDecoder))
+        (Decode.oneOf
+            [ Decode.field "labels" labelsDecoder
+            , Decode.succeed defaultLabels
+            ]
+        )
+
+
+labelsDecoder : Decoder Labels
+labelsDecoder =
+    Decode.map3 Labels
+        (Decode.field "onThisPage" Decode.string)
+        (Decode.field "previous" Decode.string)
+        (Decode.field "next" Decode.string)
 
 
 navItemDecoder : Decoder NavItem
@@ -142,9 +194,10 @@ sidebarGroupDecoder =
 
 sidebarItemDecoder : Decoder SidebarItem
 sidebarItemDecoder =
-    Decode.map3 SidebarItem
+    Decode.map4 SidebarItem
         (Decode.field "text" Decode.string)
         (Decode.field "link" Decode.string)
+        (Decode.maybe (Decode.field "badge" Decode.string))
         (Decode.oneOf
             [ Decode.field "active" Decode.bool
             , Decode.succeed False
@@ -164,6 +217,16 @@ searchDecoder =
         (Decode.field "enabled" Decode.bool)
 
 
+preferencesDecoder : Decoder Preferences
+preferencesDecoder =
+    Decode.map Preferences
+        (Decode.oneOf
+            [ Decode.field "codeGroupTab" (Decode.nullable Decode.string)
+            , Decode.succeed Nothing
+            ]
+        )
+
+
 prevNextLinkDecoder : Decoder PrevNextLink
 prevNextLinkDecoder =
     Decode.map2 PrevNextLink
@@ -1,6 +1,8 @@
 module Model exposing (Model, NavItemModel, SidebarGroupModel, SidebarItemModel, TocItemModel, fallback, fromFlags)
 
-import Flags exposing (Flags, PrevNextLink)
+import Dict exposing (Dict)
+import Flags exposing (Flags, Labels, PrevNextLink)
+import Json.Decode as Decode
 import Search.Search exposing (SearchResult, SearchState(..))
 
 
@@ -10,11 +12,13 @@ type alias Model =
     , pageContent : String
     , pageToc : List TocItemModel
     , pageLayout : String
+    , pageFrontmatter : Dict String Decode.Value
     , siteTitle : String
     , siteDescription : String
     , siteBase : String
     , nav : List NavItemModel
     , sidebar : List SidebarGroupModel
+    , labels : Labels
     , highlightTheme : String
     , prev : Maybe PrevNextLink
     , next : Maybe PrevNextLink
@@ -25,6 +29,7 @@ type alias Model =
     , searchResults : List SearchResult
     , searchIndex : SearchState
     , searchEnabled : Bool
+    , codeGroupTab : Maybe String
     }
 
 
@@ -51,6 +56,7 @@ type alias SidebarGroupModel =
 type alias SidebarItemModel =
     { text : String
     , link : String
+    , badge : Maybe String
     , active : Bool
     }
 
@@ -70,6 +76,7 @@ fromFlags flags prev next =
             )
             flags.page.toc
     , pageLayout = flags.page.layout
+    , pageFrontmatter = flags.page.frontmatter
     , siteTitle = flags.site.title
     , siteDescription = flags.site.description
     , siteBase = flags.site.base
@@ -91,6 +98,7 @@ fromFlags flags prev next =
                         (\item ->
                             { text = item.text
                             , link = item.link
+                            , badge = item.badge
                             , active = item.active
                             }
                         )
@@ -98,6 +106,7 @@ fromFlags flags prev next =
                 }
             )
             flags.site.sidebar
+    , labels = flags.site.labels
     , highlightTheme = flags.theme.highlightTheme
     , prev = prev
     , next = next
@@ -108,6 +117,7 @@ fromFlags flags prev next =
     , searchResults = []
     , searchIndex = Idle
     , searchEnabled = flags.search.enabled
+    , codeGroupTab = flags.preferences.codeGroupTab
     }
 
 
@@ -118,11 +128,13 @@ fallback =
     , pageContent = "<p>Failed to load page data.</p>"
     , pageToc = []
     , pageLayout = "doc"
+    , pageFrontmatter = Dict.empty
     , siteTitle = "Pyohwa"
     , siteDescription = ""
     , siteBase = "/"
     , nav = []
     , sidebar = []
+    , labels = Flags.defaultLabels
     , highlightTheme = "one-dark"
     , prev = Nothing
     , next = Nothing
@@ -133,4 +145,5 @@ fallback =
     , searchResults = []
     , searchIndex = Idle
     , searchEnabled = True
+    , codeGroupTab = Nothing
     }
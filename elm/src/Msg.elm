@@ -1,7 +1,7 @@
 module Msg exposing (Msg(..))
 
 import Http
-import Search.Search exposing (SearchEntry)
+import Search.Search exposing (SearchEntry, SearchIndex, TermShard)
 
 
 type Msg
@@ -12,6 +12,9 @@ type Msg
     | OpenSearch
     | CloseSearch
     | SearchInput String
-    | GotSearchIndex (Result Http.Error (List SearchEntry))
+    | GotSearchIndex (Result Http.Error SearchIndex)
+    | GotTermShard String (Result Http.Error TermShard)
+    | GotPageShard Int (Result Http.Error (List SearchEntry))
     | OnKeyDown String
+    | SelectCodeGroupTab String
     | NoOp
@@ -1,7 +1,12 @@
-port module Ports exposing (onScroll, scrollToElement)
+port module Ports exposing (onScroll, scrollToElement, selectCodeGroupTab)
 
 
 port scrollToElement : String -> Cmd msg
 
 
 port onScroll : (Float -> msg) -> Sub msg
+
+
+{-| Activate the tab with this label in every code group on the page and remember it.
+-}
+port selectCodeGroupTab : String -> Cmd msg
@@ -58,13 +58,29 @@ viewResult result =
     a
         [ class "pyohwa-search-result"
         , href result.url
+
+        -- Sections of the current page only scroll, so close the modal too
+        , onClick CloseSearch
         ]
-        [ div [ class "pyohwa-search-result-title" ] [ text result.title ]
+        [ viewBreadcrumb result.breadcrumb
+        , div [ class "pyohwa-search-result-title" ] [ text result.title ]
         , div [ class "pyohwa-search-result-desc" ] [ text result.description ]
         , div [ class "pyohwa-search-result-context" ] [ text result.matchContext ]
         ]
 
 
+{-| The page and sections above the result's own, e.g. `Guide › Install`.
+-}
+viewBreadcrumb : List String -> Html Msg
+viewBreadcrumb breadcrumb =
+    case List.take (List.length breadcrumb - 1) breadcrumb of
+        [] ->
+            text ""
+
+        parents ->
+            div [ class "pyohwa-search-result-breadcrumb" ] [ text (String.join " › " parents) ]
+
+
 viewFooter : Html Msg
 viewFooter =
     div [ class "pyohwa-search-footer" ]
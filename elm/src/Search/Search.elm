@@ -1,85 +1,594 @@
-module Search.Search exposing (SearchEntry, SearchResult, SearchState(..), extractContext, filterResults, searchEntryDecoder, searchIndexDecoder)
+module Search.Search exposing (Posting, SearchEntry, SearchIndex, SearchResult, SearchState(..), Shard(..), TermShard, addPageShard, addTermShard, extractContext, filterResults, missingShards, pageShardDecoder, requestShards, searchEntryDecoder, searchIndexDecoder, shardKey, termShardDecoder)
 
+import Array exposing (Array)
+import Dict exposing (Dict)
 import Json.Decode as Decode exposing (Decoder)
+import Search.Tokenizer as Tokenizer
+import Set exposing (Set)
 
 
 type SearchState
     = Idle
     | Loading
-    | Loaded (List SearchEntry)
+    | Loaded SearchIndex
     | Error String
 
 
+{-| The index manifest and the shards loaded so far. Term shards hold the
+postings of the terms with one `shardKey`, and the entries with each filter
+value; page shards hold `pageShardSize` entries each. `weights` and
+`averageLengths` are per field, in the order of the postings' field numbers.
+`boosts` multiply the scores of some entries, from their page's `search_boost`,
+and `stopWords` are left out of the index and of queries.
+-}
+type alias SearchIndex =
+    { count : Int
+    , weights : Array Float
+    , averageLengths : Array Float
+    , filterKeys : List String
+    , boosts : Dict Int Float
+    , stopWords : List String
+    , termShards : Dict String String
+    , pageShardSize : Int
+    , pageShards : Array String
+    , pages : Dict Int SearchEntry
+    , terms : Dict String (List Posting)
+    , filters : Dict String (List Int)
+    , loaded : Set String
+    , requested : Set String
+    }
+
+
+{-| A page, or a section of one: `url` has the section's anchor and
+`breadcrumb` is the page title followed by the headings down to `title`.
+-}
 type alias SearchEntry =
     { id : String
     , url : String
     , title : String
+    , breadcrumb : List String
     , description : String
     , content : String
     , tags : List String
+    , fields : Dict String (List String)
+    }
+
+
+{-| The positions of a term in one field of one page, and the field's length.
+-}
+type alias Posting =
+    { page : Int
+    , field : Int
+    , length : Int
+    , positions : List Int
+    }
+
+
+{-| The term shard with a key, or the page shard with a number.
+-}
+type Shard
+    = Terms String
+    | Pages Int
+
+
+type alias TermShard =
+    { terms : Dict String (List Posting)
+    , filters : Dict String (List Int)
     }
 
 
 type alias SearchResult =
     { url : String
     , title : String
+    , breadcrumb : List String
     , description : String
     , matchContext : String
     }
 
 
-filterResults : String -> List SearchEntry -> List SearchResult
-filterResults query entries =
-    if String.length query < 2 then
+{-| A page's score for the query so far, and the index terms it matched.
+-}
+type alias Match =
+    { score : Float
+    , terms : List String
+    }
+
+
+{-| Rank the pages that match every word of the query with BM25. Words like
+`status:beta`, whose key is one of the `[search] filters` fields in the index,
+keep only the pages with that value. Only the loaded shards are searched; see
+`missingShards`.
+-}
+filterResults : String -> SearchIndex -> List SearchResult
+filterResults query index =
+    topMatches query index
+        |> List.filterMap
+            (\( page, match ) ->
+                Dict.get page index.pages
+                    |> Maybe.map (\entry -> ( entry, match ))
+            )
+        |> List.map toResult
+
+
+topMatches : String -> SearchIndex -> List ( Int, Match )
+topMatches query index =
+    let
+        ( text, filters ) =
+            parseQuery index query
+
+        queryTerms =
+            unique (searchTerms index text)
+    in
+    if List.isEmpty filters && not (List.any Tokenizer.isSignificant queryTerms) then
         []
 
     else
-        let
-            lowerQuery =
-                String.toLower query
-        in
-        entries
-            |> List.filterMap (matchEntry lowerQuery)
+        (if List.isEmpty queryTerms then
+            List.head filters
+                |> Maybe.andThen (\filter -> Dict.get (filterTerm filter) index.filters)
+                |> Maybe.withDefault []
+                |> List.map (\page -> ( page, { score = 0, terms = [] } ))
+
+         else
+            rank index queryTerms
+        )
+            |> List.filter (Tuple.first >> matchesFilters index filters)
             |> List.take 10
 
 
-matchEntry : String -> SearchEntry -> Maybe SearchResult
-matchEntry lowerQuery entry =
+
+-- SHARDS
+
+
+{-| The shards a query still needs, with their paths: first the term shards of
+its words and filters, then, once those are loaded, the page shards of the
+best matches. Shards already requested are left out.
+-}
+missingShards : String -> SearchIndex -> List ( Shard, String )
+missingShards query index =
     let
-        lowerTitle =
-            String.toLower entry.title
+        ( text, filters ) =
+            parseQuery index query
+
+        notLoaded ( shard, _ ) =
+            not (Set.member (shardId shard) index.loaded)
+
+        notRequested ( shard, _ ) =
+            not (Set.member (shardId shard) index.requested)
+
+        termShards =
+            (searchTerms index text ++ List.map filterTerm filters)
+                |> List.map shardKey
+                |> unique
+                |> List.filterMap
+                    (\key ->
+                        Dict.get key index.termShards
+                            |> Maybe.map (\path -> ( Terms key, path ))
+                    )
+                |> List.filter notLoaded
+    in
+    if List.isEmpty termShards then
+        topMatches query index
+            |> List.map (\( page, _ ) -> page // index.pageShardSize)
+            |> unique
+            |> List.filterMap
+                (\number ->
+                    Array.get number index.pageShards
+                        |> Maybe.map (\path -> ( Pages number, path ))
+                )
+            |> List.filter notLoaded
+            |> List.filter notRequested
+
+    else
+        List.filter notRequested termShards
 
-        lowerDesc =
-            String.toLower entry.description
 
+{-| Note shards as requested, so they are fetched once.
+-}
+requestShards : List ( Shard, String ) -> SearchIndex -> SearchIndex
+requestShards shards index =
+    { index
+        | requested =
+            List.foldl (\( shard, _ ) -> Set.insert (shardId shard)) index.requested shards
+    }
+
+
+addTermShard : String -> TermShard -> SearchIndex -> SearchIndex
+addTermShard key shard index =
+    { index
+        | terms = Dict.union shard.terms index.terms
+        , filters = Dict.union shard.filters index.filters
+        , loaded = Set.insert (shardId (Terms key)) index.loaded
+    }
+
+
+addPageShard : Int -> List SearchEntry -> SearchIndex -> SearchIndex
+addPageShard number entries index =
+    { index
+        | pages =
+            List.foldl (\( page, entry ) -> Dict.insert page entry)
+                index.pages
+                (List.indexedMap (\i entry -> ( number * index.pageShardSize + i, entry )) entries)
+        , loaded = Set.insert (shardId (Pages number)) index.loaded
+    }
+
+
+shardId : Shard -> String
+shardId shard =
+    case shard of
+        Terms key ->
+            "terms-" ++ key
+
+        Pages number ->
+            "pages-" ++ String.fromInt number
+
+
+{-| The term shard a term is in: its first two characters when they are ASCII
+letters or digits, or just the first; otherwise `u` and the first character's
+code divided by 256. Mirrors `pyohwa_search::shard::shard_key`.
+-}
+shardKey : String -> String
+shardKey term =
+    case String.uncons term of
+        Just ( c, rest ) ->
+            if Char.isAlphaNum c then
+                case String.uncons rest of
+                    Just ( d, _ ) ->
+                        if Char.isAlphaNum d then
+                            String.fromList [ c, d ]
+
+                        else
+                            String.fromChar c
+
+                    Nothing ->
+                        String.fromChar c
+
+            else
+                "u" ++ String.fromInt (Char.toCode c // 256)
+
+        Nothing ->
+            ""
+
+
+{-| The tokens of a query that are in the index: all but the stop words.
+-}
+searchTerms : SearchIndex -> String -> List String
+searchTerms index text =
+    List.filter (\term -> not (List.member term index.stopWords)) (Tokenizer.tokenize text)
+
+
+parseQuery : SearchIndex -> String -> ( String, List ( String, String ) )
+parseQuery index query =
+    let
+        toFilter word =
+            case String.split ":" word of
+                [ key, value ] ->
+                    if value /= "" && List.member key index.filterKeys then
+                        Just ( key, String.toLower value )
+
+                    else
+                        Nothing
+
+                _ ->
+                    Nothing
+
+        words =
+            String.words query
+    in
+    ( words
+        |> List.filter (\word -> toFilter word == Nothing)
+        |> String.join " "
+    , List.filterMap toFilter words
+    )
+
+
+matchesFilters : SearchIndex -> List ( String, String ) -> Int -> Bool
+matchesFilters index filters page =
+    List.all
+        (\filter ->
+            Dict.get (filterTerm filter) index.filters
+                |> Maybe.withDefault []
+                |> List.member page
+        )
+        filters
+
+
+{-| The key of a filter's entries in the index, `key:value`.
+-}
+filterTerm : ( String, String ) -> String
+filterTerm ( key, value ) =
+    key ++ ":" ++ value
+
+
+toResult : ( SearchEntry, Match ) -> SearchResult
+toResult ( entry, match ) =
+    let
         lowerContent =
             String.toLower entry.content
 
-        lowerTags =
-            List.map String.toLower entry.tags
+        contextTerm =
+            match.terms
+                |> List.filter (\term -> String.contains term lowerContent)
+                |> List.head
+                |> Maybe.withDefault ""
+    in
+    { url = entry.url
+    , title = entry.title
+    , breadcrumb = entry.breadcrumb
+    , description = entry.description
+    , matchContext = extractContext contextTerm entry.content
+    }
 
-        titleMatch =
-            String.contains lowerQuery lowerTitle
 
-        descMatch =
-            String.contains lowerQuery lowerDesc
 
-        contentMatch =
-            String.contains lowerQuery lowerContent
+-- RANKING
 
-        tagMatch =
-            List.any (String.contains lowerQuery) lowerTags
+
+{-| BM25 parameters: term frequency saturation and length normalization.
+-}
+k1 : Float
+k1 =
+    1.2
+
+
+b : Float
+b =
+    0.75
+
+
+{-| How much a term counts when the query word is only its prefix, or a typo of it.
+-}
+prefixFactor : Float
+prefixFactor =
+    0.7
+
+
+typoFactor : Float
+typoFactor =
+    0.5
+
+
+{-| Pages that match every query term, best first.
+-}
+rank : SearchIndex -> List String -> List ( Int, Match )
+rank index queryTerms =
+    case List.map (termMatches index) queryTerms of
+        [] ->
+            []
+
+        first :: rest ->
+            List.foldl intersect first rest
+                |> Dict.map
+                    (\page match ->
+                        { match | score = match.score * Maybe.withDefault 1 (Dict.get page index.boosts) }
+                    )
+                |> Dict.toList
+                |> List.sortBy (\( _, match ) -> negate match.score)
+
+
+intersect : Dict Int Match -> Dict Int Match -> Dict Int Match
+intersect next matches =
+    Dict.merge
+        (\_ _ result -> result)
+        (\page a c result ->
+            Dict.insert page { score = a.score + c.score, terms = a.terms ++ c.terms } result
+        )
+        (\_ _ result -> result)
+        matches
+        next
+        Dict.empty
+
+
+{-| Score every page matching one query term, through its best expansion.
+-}
+termMatches : SearchIndex -> String -> Dict Int Match
+termMatches index queryTerm =
+    let
+        addExpansion ( term, factor, postings ) best =
+            let
+                weight =
+                    factor * idf index postings
+
+                keepBest page score matches =
+                    case Dict.get page matches of
+                        Just match ->
+                            if match.score >= weight * score then
+                                matches
+
+                            else
+                                Dict.insert page { score = weight * score, terms = [ term ] } matches
+
+                        Nothing ->
+                            Dict.insert page { score = weight * score, terms = [ term ] } matches
+            in
+            Dict.foldl keepBest best (scorePostings index postings)
+    in
+    List.foldl addExpansion Dict.empty (expand index queryTerm)
+
+
+{-| The index terms a query term stands for, with how much a match counts: the
+term itself and the longer terms it is a prefix of, as the last word is often
+still being typed; or, when there are none, the terms a typo or two away. Only
+the loaded terms are considered, which for prefixes are all of them: a term's
+prefixes are in its shard.
+-}
+expand : SearchIndex -> String -> List ( String, Float, List Posting )
+expand index queryTerm =
+    let
+        exact =
+            case Dict.get queryTerm index.terms of
+                Just postings ->
+                    [ ( queryTerm, 1, postings ) ]
+
+                Nothing ->
+                    []
+
+        prefixed =
+            if not (Tokenizer.isSignificant queryTerm) then
+                []
+
+            else
+                Dict.foldl
+                    (\term postings acc ->
+                        if term /= queryTerm && String.startsWith queryTerm term then
+                            ( term, prefixFactor, postings ) :: acc
+
+                        else
+                            acc
+                    )
+                    []
+                    index.terms
+
+        maxEdits =
+            typoBudget queryTerm
+
+        nearby term =
+            abs (String.length term - String.length queryTerm)
+                <= maxEdits
+                && editDistance queryTerm term
+                <= maxEdits
     in
-    if titleMatch || descMatch || contentMatch || tagMatch then
-        Just
-            { url = entry.url
-            , title = entry.title
-            , description = entry.description
-            , matchContext = extractContext lowerQuery entry.content
-            }
+    if List.isEmpty exact && List.isEmpty prefixed && maxEdits > 0 then
+        Dict.foldl
+            (\term postings acc ->
+                if nearby term then
+                    ( term, typoFactor, postings ) :: acc
+
+                else
+                    acc
+            )
+            []
+            index.terms
 
     else
-        Nothing
+        exact ++ prefixed
+
+
+{-| Short words have too many neighbours to guess a typo.
+-}
+typoBudget : String -> Int
+typoBudget term =
+    if String.length term < 4 then
+        0
+
+    else if String.length term < 8 then
+        1
+
+    else
+        2
+
+
+{-| Inverse document frequency: rare terms count for more.
+-}
+idf : SearchIndex -> List Posting -> Float
+idf index postings =
+    let
+        n =
+            toFloat index.count
+
+        df =
+            postings
+                |> List.map .page
+                |> Set.fromList
+                |> Set.size
+                |> toFloat
+    in
+    logBase e (1 + (n - df + 0.5) / (df + 0.5))
+
+
+{-| Sum the weighted, length-normalized term frequencies of each page's fields.
+-}
+scorePostings : SearchIndex -> List Posting -> Dict Int Float
+scorePostings index postings =
+    List.foldl
+        (\posting scores ->
+            Dict.update posting.page
+                (\score -> Just (Maybe.withDefault 0 score + fieldScore index posting))
+                scores
+        )
+        Dict.empty
+        postings
+
+
+fieldScore : SearchIndex -> Posting -> Float
+fieldScore index posting =
+    let
+        tf =
+            toFloat (List.length posting.positions)
+
+        weight =
+            Array.get posting.field index.weights
+                |> Maybe.withDefault 1
+
+        average =
+            Array.get posting.field index.averageLengths
+                |> Maybe.withDefault 0
+
+        length =
+            toFloat posting.length
+
+        norm =
+            if average > 0 then
+                1 - b + b * length / average
+
+            else
+                1
+    in
+    weight * tf * (k1 + 1) / (tf + k1 * norm)
+
+
+editDistance : String -> String -> Int
+editDistance source target =
+    let
+        targetChars =
+            String.toList target
+
+        nextRow char ( i, previous ) =
+            let
+                step ( targetChar, diagonal, up ) ( left, cells ) =
+                    let
+                        cost =
+                            if char == targetChar then
+                                0
+
+                            else
+                                1
+
+                        distance =
+                            min (min (up + 1) (left + 1)) (diagonal + cost)
+                    in
+                    ( distance, distance :: cells )
+
+                ( _, row ) =
+                    List.foldl step
+                        ( i, [ i ] )
+                        (List.map3 (\c d u -> ( c, d, u )) targetChars previous (List.drop 1 previous))
+            in
+            ( i + 1, List.reverse row )
+    in
+    List.foldl nextRow ( 1, List.range 0 (List.length targetChars) ) (String.toList source)
+        |> Tuple.second
+        |> List.reverse
+        |> List.head
+        |> Maybe.withDefault 0
+
+
+unique : List a -> List a
+unique items =
+    List.foldl
+        (\item acc ->
+            if List.member item acc then
+                acc
+
+            else
+                acc ++ [ item ]
+        )
+        []
+        items
+
+
+
+-- CONTEXT
 
 
 extractContext : String -> String -> String
@@ -146,15 +655,92 @@ findIndexHelper needle haystack offset =
 
 searchEntryDecoder : Decoder SearchEntry
 searchEntryDecoder =
-    Decode.map6 SearchEntry
-        (Decode.field "id" Decode.string)
-        (Decode.field "url" Decode.string)
-        (Decode.field "title" Decode.string)
-        (Decode.field "description" Decode.string)
-        (Decode.field "content" Decode.string)
-        (Decode.field "tags" (Decode.list Decode.string))
-
-
-searchIndexDecoder : Decoder (List SearchEntry)
+    Decode.succeed SearchEntry
+        |> andMap (Decode.field "id" Decode.string)
+        |> andMap (Decode.field "url" Decode.string)
+        |> andMap (Decode.field "title" Decode.string)
+        |> andMap (Decode.field "breadcrumb" (Decode.list Decode.string))
+        |> andMap (Decode.field "description" Decode.string)
+        |> andMap (Decode.field "content" Decode.string)
+        |> andMap (Decode.field "tags" (Decode.list Decode.string))
+        |> andMap
+            (Decode.oneOf
+                [ Decode.field "fields" (Decode.dict (Decode.list Decode.string))
+                , Decode.succeed Dict.empty
+                ]
+            )
+
+
+andMap : Decoder a -> Decoder (a -> b) -> Decoder b
+andMap =
+    Decode.map2 (|>)
+
+
+{-| Postings are flat arrays, `[page, field, length, position, ...]`.
+-}
+postingDecoder : Decoder Posting
+postingDecoder =
+    Decode.list Decode.int
+        |> Decode.andThen
+            (\values ->
+                case values of
+                    page :: field :: length :: positions ->
+                        Decode.succeed (Posting page field length positions)
+
+                    _ ->
+                        Decode.fail "a posting needs a page, a field and a length"
+            )
+
+
+{-| The manifest, `search-index.json`; the shards it lists are loaded later.
+-}
+searchIndexDecoder : Decoder SearchIndex
 searchIndexDecoder =
-    Decode.field "pages" (Decode.list searchEntryDecoder)
+    Decode.succeed SearchIndex
+        |> andMap (Decode.field "count" Decode.int)
+        |> andMap (Decode.field "weights" (Decode.array Decode.float))
+        |> andMap (Decode.field "average_lengths" (Decode.array Decode.float))
+        |> andMap (Decode.field "filters" (Decode.list Decode.string))
+        |> andMap (optionalField "boosts" Dict.empty boostsDecoder)
+        |> andMap (optionalField "stop_words" [] (Decode.list Decode.string))
+        |> andMap (Decode.field "term_shards" (Decode.dict Decode.string))
+        |> andMap (Decode.field "page_shard_size" Decode.int)
+        |> andMap (Decode.field "page_shards" (Decode.array Decode.string))
+        |> andMap (Decode.succeed Dict.empty)
+        |> andMap (Decode.succeed Dict.empty)
+        |> andMap (Decode.succeed Dict.empty)
+        |> andMap (Decode.succeed Set.empty)
+        |> andMap (Decode.succeed Set.empty)
+
+
+{-| Boosts are an object keyed by entry number.
+-}
+boostsDecoder : Decoder (Dict Int Float)
+boostsDecoder =
+    Decode.keyValuePairs Decode.float
+        |> Decode.map
+            (List.filterMap (\( page, boost ) -> Maybe.map (\number -> ( number, boost )) (String.toInt page))
+                >> Dict.fromList
+            )
+
+
+{-| A field that is left out when empty.
+-}
+optionalField : String -> a -> Decoder a -> Decoder a
+optionalField name empty decoder =
+    Decode.oneOf
+        [ Decode.field name decoder
+        , Decode.succeed empty
+        ]
+
+
+termShardDecoder : Decoder TermShard
+termShardDecoder =
+    Decode.map2 TermShard
+        (Decode.field "terms" (Decode.dict (Decode.list postingDecoder)))
+        (optionalField "filters" Dict.empty (Decode.dict (Decode.list Decode.int)))
+
+
+pageShardDecoder : Decoder (List SearchEntry)
+pageShardDecoder =
+    Decode.list searchEntryDecoder
@@ -0,0 +1,144 @@
+module Search.Tokenizer exposing (isSignificant, normalize, tokenize)
+
+{-| Splits search queries into the tokens of the search index. Mirrors
+`pyohwa_search::tokenizer::tokenize`, so a page matches a query when it has
+every token of the query.
+-}
+
+
+type Script
+    = Word
+    | Hangul
+    | Cjk
+    | Separator
+
+
+{-| Fold full-width Latin letters, digits and spaces to ASCII and lowercase.
+The index is folded with NFKC, which also covers half-width katakana; queries
+are typed, so the full-width forms are the ones that matter here.
+-}
+normalize : String -> String
+normalize text =
+    text
+        |> String.map foldWidth
+        |> String.toLower
+
+
+foldWidth : Char -> Char
+foldWidth c =
+    let
+        code =
+            Char.toCode c
+    in
+    if code >= 0xFF01 && code <= 0xFF5E then
+        Char.fromCode (code - 0xFEE0)
+
+    else if code == 0x3000 then
+        ' '
+
+    else
+        c
+
+
+{-| Words in alphabetic scripts are one token each; Hangul and CJK runs give
+every pair of adjacent characters, or the character itself when alone. Tokens
+are in text order, repeats included.
+-}
+tokenize : String -> List String
+tokenize text =
+    normalize text
+        |> String.toList
+        |> runs
+        |> List.concatMap runTokens
+
+
+{-| Whether a query token says enough to search for: two characters or more,
+or a single Hangul or CJK character, which is a syllable or a word of its own.
+A single letter or digit would match nearly every page.
+-}
+isSignificant : String -> Bool
+isSignificant token =
+    case String.toList token of
+        [] ->
+            False
+
+        [ c ] ->
+            script c == Hangul || script c == Cjk
+
+        _ ->
+            True
+
+
+runs : List Char -> List ( Script, List Char )
+runs chars =
+    let
+        step c acc =
+            let
+                s =
+                    script c
+            in
+            case acc of
+                ( runScript, run ) :: rest ->
+                    if runScript == s then
+                        ( runScript, c :: run ) :: rest
+
+                    else
+                        ( s, [ c ] ) :: acc
+
+                [] ->
+                    [ ( s, [ c ] ) ]
+    in
+    List.foldl step [] chars
+        |> List.reverse
+        |> List.filter (\( s, _ ) -> s /= Separator)
+        |> List.map (\( s, run ) -> ( s, List.reverse run ))
+
+
+runTokens : ( Script, List Char ) -> List String
+runTokens ( s, run ) =
+    case ( s, run ) of
+        ( Word, _ ) ->
+            [ String.fromList run ]
+
+        ( _, [ _ ] ) ->
+            [ String.fromList run ]
+
+        _ ->
+            List.map2 (\a b -> String.fromList [ a, b ]) run (List.drop 1 run)
+
+
+script : Char -> Script
+script c =
+    let
+        code =
+            Char.toCode c
+
+        between low high =
+            code >= low && code <= high
+    in
+    if between 0x1100 0x11FF || between 0x3130 0x318F || between 0xAC00 0xD7A3 then
+        Hangul
+
+    else if
+        between 0x3040 0x30FF
+            || between 0x31F0 0x31FF
+            || between 0x3400 0x4DBF
+            || between 0x4E00 0x9FFF
+            || between 0xF900 0xFAFF
+    then
+        Cjk
+
+    else if
+        (code <= 0x20)
+            || List.member code [ 0x85, 0xA0, 0x1680 ]
+            || between 0x21 0x2F
+            || between 0x3A 0x40
+            || between 0x5B 0x60
+            || between 0x7B 0x7E
+            || between 0x2000 0x206F
+            || between 0x3000 0x303F
+    then
+        Separator
+
+    else
+        Word
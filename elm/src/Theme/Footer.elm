@@ -11,18 +11,18 @@ view : Model -> Html Msg
 view model =
     footer [ class "pyohwa-footer" ]
         [ div [ class "pyohwa-footer-nav" ]
-            [ viewPrev model.prev
-            , viewNext model.next
+            [ viewPrev model.labels.previous model.prev
+            , viewNext model.labels.next model.next
             ]
         ]
 
 
-viewPrev : Maybe PrevNextLink -> Html Msg
-viewPrev maybePrev =
+viewPrev : String -> Maybe PrevNextLink -> Html Msg
+viewPrev label maybePrev =
     case maybePrev of
         Just link ->
             a [ class "pyohwa-footer-link pyohwa-footer-prev", href link.link ]
-                [ span [ class "pyohwa-footer-label" ] [ text "Previous" ]
+                [ span [ class "pyohwa-footer-label" ] [ text label ]
                 , span [ class "pyohwa-footer-title" ] [ text link.title ]
                 ]
 
@@ -30,12 +30,12 @@ viewPrev maybePrev =
             div [] []
 
 
-viewNext : Maybe PrevNextLink -> Html Msg
-viewNext maybeNext =
+viewNext : String -> Maybe PrevNextLink -> Html Msg
+viewNext label maybeNext =
     case maybeNext of
         Just link ->
             a [ class "pyohwa-footer-link pyohwa-footer-next", href link.link ]
-                [ span [ class "pyohwa-footer-label" ] [ text "Next" ]
+                [ span [ class "pyohwa-footer-label" ] [ text label ]
                 , span [ class "pyohwa-footer-title" ] [ text link.title ]
                 ]
 
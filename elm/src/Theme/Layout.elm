@@ -1,26 +1,35 @@
 module Theme.Layout exposing (view)
 
 import Html exposing (Html, div, main_, node, text)
-import Html.Attributes exposing (class, id, property)
+import Html.Attributes exposing (attribute, class, id, property)
+import Html.Events exposing (on)
+import Json.Decode as Decode exposing (Decoder)
 import Json.Encode as Encode
 import Model exposing (Model)
-import Msg exposing (Msg)
+import Msg exposing (Msg(..))
 import Search.Modal as SearchModal
 import Theme.Footer as Footer
 import Theme.Navbar as Navbar
 import Theme.Sidebar as Sidebar
 import Theme.Toc as Toc
 
-
+{-| The build pre-renders this same structure with the default page templates
+(`crates/pyohwa-core/src/render/templates/`), so classes and ids in the Theme
+views need to stay in step with them.
+-}
 view : Model -> Html Msg
 view model =
-    div [ class "pyohwa-layout" ]
+    div [ class "pyohwa-layout", attribute "data-layout" model.pageLayout ]
         [ Navbar.view model
         , viewBody model
         , SearchModal.view model
         ]
 
 
+{-| Custom layouts (`layout: <name>` with a `<name>.html` template) get the doc
+structure here; `data-layout` on the root carries the name, so themes can style
+them or add a case for their own view.
+-}
 viewBody : Model -> Html Msg
 viewBody model =
     case model.pageLayout of
@@ -39,12 +48,7 @@ viewDocLayout model =
     div [ class "pyohwa-main" ]
         [ Sidebar.view model
         , main_ [ class "pyohwa-content" ]
-            [ div
-                [ class "pyohwa-prose"
-                , id "content"
-                , property "innerHTML" (Encode.string model.pageContent)
-                ]
-                []
+            [ viewProse model
             , Footer.view model
             ]
         , Toc.view model
@@ -55,12 +59,7 @@ viewHomeLayout : Model -> Html Msg
 viewHomeLayout model =
     div [ class "pyohwa-layout-home" ]
         [ main_ [ class "pyohwa-content" ]
-            [ div
-                [ class "pyohwa-prose"
-                , id "content"
-                , property "innerHTML" (Encode.string model.pageContent)
-                ]
-                []
+            [ viewProse model
             ]
         ]
 
@@ -69,12 +68,27 @@ viewPageLayout : Model -> Html Msg
 viewPageLayout model =
     div [ class "pyohwa-layout-page" ]
         [ main_ [ class "pyohwa-content pyohwa-content--centered" ]
-            [ div
-                [ class "pyohwa-prose"
-                , id "content"
-                , property "innerHTML" (Encode.string model.pageContent)
-                ]
-                []
+            [ viewProse model
             , Footer.view model
             ]
         ]
+
+
+{-| The rendered Markdown. Its HTML comes from the build, so clicks on code group
+tabs are picked up by their `data-code-group-tab` attribute.
+-}
+viewProse : Model -> Html Msg
+viewProse model =
+    div
+        [ class "pyohwa-prose"
+        , id "content"
+        , property "innerHTML" (Encode.string model.pageContent)
+        , on "click" codeGroupTabDecoder
+        ]
+        []
+
+
+codeGroupTabDecoder : Decoder Msg
+codeGroupTabDecoder =
+    Decode.at [ "target", "dataset", "codeGroupTab" ] Decode.string
+        |> Decode.map SelectCodeGroupTab
@@ -1,6 +1,6 @@
 module Theme.Sidebar exposing (view)
 
-import Html exposing (Html, a, aside, div, text)
+import Html exposing (Html, a, aside, div, span, text)
 import Html.Attributes exposing (class, classList, href, id)
 import Html.Events exposing (onClick)
 import Model exposing (Model)
@@ -49,4 +49,12 @@ viewItem item =
         , classList [ ( "active", item.active ) ]
         , href item.link
         ]
-        [ text item.text ]
+        (case item.badge of
+            Just badge ->
+                [ text (item.text ++ " ")
+                , span [ class "pyohwa-sidebar-badge" ] [ text badge ]
+                ]
+
+            Nothing ->
+                [ text item.text ]
+        )
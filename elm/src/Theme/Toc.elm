@@ -10,7 +10,7 @@ import Msg exposing (Msg(..))
 view : Model -> Html Msg
 view model =
     aside [ class "pyohwa-toc", id "toc" ]
-        [ div [ class "pyohwa-toc-title" ] [ text "On this page" ]
+        [ div [ class "pyohwa-toc-title" ] [ text model.labels.onThisPage ]
         , div [] (List.map (viewItem model.activeTocId) model.pageToc)
         ]
 
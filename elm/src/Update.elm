@@ -4,7 +4,7 @@ import Http
 import Model exposing (Model)
 import Msg exposing (Msg(..))
 import Ports
-import Search.Search as Search exposing (SearchState(..))
+import Search.Search as Search exposing (SearchIndex, SearchState(..), Shard(..))
 
 
 update : Msg -> Model -> ( Model, Cmd Msg )
@@ -36,29 +36,43 @@ update msg model =
             ( { model | searchOpen = False, searchQuery = "", searchResults = [] }, Cmd.none )
 
         SearchInput query ->
-            let
-                results =
-                    case model.searchIndex of
-                        Loaded entries ->
-                            Search.filterResults query entries
+            case model.searchIndex of
+                Loaded index ->
+                    runSearch query index model
 
-                        _ ->
-                            []
-            in
-            ( { model | searchQuery = query, searchResults = results }, Cmd.none )
+                _ ->
+                    ( { model | searchQuery = query, searchResults = [] }, Cmd.none )
 
         GotSearchIndex result ->
             case result of
-                Ok entries ->
-                    let
-                        results =
-                            Search.filterResults model.searchQuery entries
-                    in
-                    ( { model | searchIndex = Loaded entries, searchResults = results }, Cmd.none )
+                Ok index ->
+                    runSearch model.searchQuery index model
 
                 Err _ ->
                     ( { model | searchIndex = Error "Failed to load search index" }, Cmd.none )
 
+        GotTermShard key result ->
+            case ( result, model.searchIndex ) of
+                ( Ok shard, Loaded index ) ->
+                    runSearch model.searchQuery (Search.addTermShard key shard index) model
+
+                ( Err _, _ ) ->
+                    ( { model | searchIndex = Error "Failed to load search index" }, Cmd.none )
+
+                _ ->
+                    ( model, Cmd.none )
+
+        GotPageShard number result ->
+            case ( result, model.searchIndex ) of
+                ( Ok entries, Loaded index ) ->
+                    runSearch model.searchQuery (Search.addPageShard number entries index) model
+
+                ( Err _, _ ) ->
+                    ( { model | searchIndex = Error "Failed to load search index" }, Cmd.none )
+
+                _ ->
+                    ( model, Cmd.none )
+
         OnKeyDown key ->
             if key == "Escape" then
                 ( { model | searchOpen = False, searchQuery = "", searchResults = [] }, Cmd.none )
@@ -66,26 +80,64 @@ update msg model =
             else
                 ( model, Cmd.none )
 
+        SelectCodeGroupTab label ->
+            ( { model | codeGroupTab = Just label }, Ports.selectCodeGroupTab label )
+
         NoOp ->
             ( model, Cmd.none )
 
 
-fetchSearchIndex : String -> Cmd Msg
-fetchSearchIndex base =
+{-| Show the results from the loaded shards, and fetch the ones the query
+still needs; results are recomputed as they arrive.
+-}
+runSearch : String -> SearchIndex -> Model -> ( Model, Cmd Msg )
+runSearch query index model =
     let
-        url =
-            if String.endsWith "/" base then
-                base ++ "search-index.json"
-
-            else
-                base ++ "/search-index.json"
+        shards =
+            Search.missingShards query index
     in
+    ( { model
+        | searchQuery = query
+        , searchIndex = Loaded (Search.requestShards shards index)
+        , searchResults = Search.filterResults query index
+      }
+    , Cmd.batch (List.map (fetchShard model.siteBase) shards)
+    )
+
+
+fetchSearchIndex : String -> Cmd Msg
+fetchSearchIndex base =
     Http.get
-        { url = url
+        { url = siteUrl base "search-index.json"
         , expect = Http.expectJson GotSearchIndex Search.searchIndexDecoder
         }
 
 
+fetchShard : String -> ( Shard, String ) -> Cmd Msg
+fetchShard base ( shard, path ) =
+    case shard of
+        Terms key ->
+            Http.get
+                { url = siteUrl base path
+                , expect = Http.expectJson (GotTermShard key) Search.termShardDecoder
+                }
+
+        Pages number ->
+            Http.get
+                { url = siteUrl base path
+                , expect = Http.expectJson (GotPageShard number) Search.pageShardDecoder
+                }
+
+
+siteUrl : String -> String -> String
+siteUrl base path =
+    if String.endsWith "/" base then
+        base ++ path
+
+    else
+        base ++ "/" ++ path
+
+
 findActiveHeading : Float -> List Model.TocItemModel -> String
 findActiveHeading scrollY tocItems =
     let
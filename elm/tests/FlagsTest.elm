@@ -1,5 +1,6 @@
 module FlagsTest exposing (..)
 
+import Dict
 import Expect
 import Flags
 import Json.Decode as Decode
@@ -180,4 +181,93 @@ suite =
 
                     Err err ->
                         Expect.fail (Decode.errorToString err)
+                , test "decodes stored code group tab preference" <|
+            \_ ->
+                let
+                    json =
+                        """
+                        {
+                            "page": {"title": "T", "description": "", "content": "", "toc": [], "layout": "doc", "frontmatter": {}},
+                            "site": {"title": "S", "description": "", "base": "/", "nav": [], "sidebar": []},
+                            "theme": {"highlightTheme": "x"},
+                            "preferences": {"codeGroupTab": "pnpm"}
+                        }
+                        """
+                in
+                case Decode.decodeString Flags.decoder json of
+                    Ok flags ->
+                        Expect.equal (Just "pnpm") flags.preferences.codeGroupTab
+
+                    Err err ->
+                        Expect.fail (Decode.errorToString err)
+        , test "code group tab preference defaults to nothing" <|
+            \_ ->
+                let
+                    json =
+                        """
+                        {
+                            "page": {"title": "T", "description": "", "content": "", "toc": [], "layout": "doc", "frontmatter": {}},
+                            "site": {"title": "S", "description": "", "base": "/", "nav": [], "sidebar": []},
+                            "theme": {"highlightTheme": "x"},
+                            "preferences": {"codeGroupTab": null}
+                        }
+                        """
+                in
+                case Decode.decodeString Flags.decoder json of
+                    Ok flags ->
+                        Expect.equal Nothing flags.preferences.codeGroupTab
+
+                    Err err ->
+                        Expect.fail (Decode.errorToString err)
+        , test "decodes custom frontmatter and sidebar badges" <|
+            \_ ->
+                let
+                    json =
+                        """
+                        {
+                            "page": {"title": "T", "description": "", "content": "", "toc": [], "layout": "doc", "frontmatter": {"status": "beta", "owners": ["platform"]}},
+                            "site": {"title": "S", "description": "", "base": "/", "nav": [], "sidebar": [{"text": "G", "items": [{"text": "A", "link": "/a", "badge": "beta"}, {"text": "B", "link": "/b"}]}]},
+                            "theme": {"highlightTheme": "x"}
+                        }
+                        """
+                in
+                case Decode.decodeString Flags.decoder json of
+                    Ok flags ->
+                        Expect.all
+                            [ \f -> Expect.equal [ "owners", "status" ] (Dict.keys f.page.frontmatter)
+                            , \f ->
+                                Expect.equal [ Just "beta", Nothing ]
+                                    (List.concatMap (.items >> List.map .badge) f.site.sidebar)
+                            ]
+                            flags
+
+                    Err err ->
+                        Expect.fail (Decode.errorToString err)
+        , test "decodes theme labels and defaults to English" <|
+            \_ ->
+                let
+                    json labels =
+                        """
+                        {
+                            "page": {"title": "T", "description": "", "content": "", "toc": [], "layout": "doc", "frontmatter": {}},
+                            "site": {"title": "S", "description": "", "base": "/", "nav": [], "sidebar": []"""
+                            ++ labels
+                            ++ """},
+                            "theme": {"highlightTheme": "x"}
+                        }
+                        """
+                in
+                Expect.equal
+                    [ Just { onThisPage = "이 페이지의 내용", previous = "이전", next = "다음" }
+                    , Just Flags.defaultLabels
+                    ]
+                    (List.map
+                        (\extra ->
+                            Decode.decodeString (Decode.map (.site >> .labels) Flags.decoder) (json extra)
+                                |> Result.toMaybe
+                        )
+                        [ ", \"labels\": {\"onThisPage\": \"이 페이지의 내용\", \"previous\": \"이전\", \"next\": \"다음\"}"
+                        , ""
+                        ]
+                    )
         ]
@@ -1,32 +1,156 @@
 module SearchTest exposing (..)
 
+import Array
+import Dict exposing (Dict)
 import Expect
-import Search.Search exposing (SearchEntry, filterResults)
+import Search.Search exposing (Posting, SearchEntry, SearchIndex, Shard(..), TermShard, addPageShard, addTermShard, filterResults, missingShards, requestShards, shardKey)
+import Set
+import Search.Tokenizer exposing (isSignificant, tokenize)
 import Test exposing (..)
 
 
+{-| Index entries the way `pyohwa-search` does: the title, the sections above
+the entry's own, tags, and the body (description and content). Every shard is
+loaded.
+-}
+buildIndex : List SearchEntry -> SearchIndex
+buildIndex entries =
+    let
+        fieldsOf entry =
+            [ ( 0, tokenize entry.title )
+            , ( 1, tokenize (String.join " " (List.take (List.length entry.breadcrumb - 1) entry.breadcrumb)) )
+            , ( 2, tokenize (String.join " " entry.tags) )
+            , ( 3, tokenize (entry.description ++ " " ++ entry.content) )
+            ]
+
+        postingsOf page entry =
+            List.concatMap
+                (\( field, tokens ) ->
+                    tokens
+                        |> List.indexedMap Tuple.pair
+                        |> List.foldl
+                            (\( position, token ) acc ->
+                                Dict.update token (\ps -> Just (Maybe.withDefault [] ps ++ [ position ])) acc
+                            )
+                            Dict.empty
+                        |> Dict.toList
+                        |> List.map (\( token, positions ) -> ( token, Posting page field (List.length tokens) positions ))
+                )
+                (fieldsOf entry)
+
+        append key value =
+            Dict.update key (\values -> Just (Maybe.withDefault [] values ++ [ value ]))
+
+        terms : Dict String (List Posting)
+        terms =
+            entries
+                |> List.indexedMap postingsOf
+                |> List.concat
+                |> List.foldl (\( token, posting ) -> append token posting) Dict.empty
+
+        filters : Dict String (List Int)
+        filters =
+            entries
+                |> List.indexedMap
+                    (\page entry ->
+                        Dict.toList entry.fields
+                            |> List.concatMap
+                                (\( key, values ) -> List.map (\value -> ( key ++ ":" ++ String.toLower value, page )) values)
+                    )
+                |> List.concat
+                |> List.foldl (\( filter, page ) -> append filter page) Dict.empty
+
+        average field =
+            toFloat (List.sum (List.map (fieldsOf >> List.drop field >> List.head >> Maybe.map (Tuple.second >> List.length) >> Maybe.withDefault 0) entries))
+                / toFloat (max 1 (List.length entries))
+    in
+    { count = List.length entries
+    , weights = Array.fromList [ 4, 2, 2, 1 ]
+    , averageLengths = Array.fromList (List.map average [ 0, 1, 2, 3 ])
+    , filterKeys = Set.toList (Set.fromList (List.concatMap (.fields >> Dict.keys) entries))
+    , boosts = Dict.empty
+    , stopWords = []
+    , termShards = Dict.empty
+    , pageShardSize = 100
+    , pageShards = Array.empty
+    , pages = Dict.fromList (List.indexedMap Tuple.pair entries)
+    , terms = terms
+    , filters = filters
+    , loaded = Set.empty
+    , requested = Set.empty
+    }
+
+
+{-| The manifest of an index with one term shard and one page shard, none loaded.
+-}
+manifest : SearchIndex
+manifest =
+    { count = 1
+    , weights = Array.fromList [ 4, 2, 2, 1 ]
+    , averageLengths = Array.fromList [ 1, 0, 0, 0 ]
+    , filterKeys = []
+    , boosts = Dict.empty
+    , stopWords = []
+    , termShards = Dict.fromList [ ( "in", "assets/search/terms-in.json" ) ]
+    , pageShardSize = 100
+    , pageShards = Array.fromList [ "assets/search/pages-0.json" ]
+    , pages = Dict.empty
+    , terms = Dict.empty
+    , filters = Dict.empty
+    , loaded = Set.empty
+    , requested = Set.empty
+    }
+
+
+installShard : TermShard
+installShard =
+    { terms = Dict.fromList [ ( "install", [ Posting 0 0 1 [ 0 ] ] ) ]
+    , filters = Dict.empty
+    }
+
+
+sampleIndex : SearchIndex
+sampleIndex =
+    buildIndex sampleEntries
+
+
 sampleEntries : List SearchEntry
 sampleEntries =
     [ { id = "1"
       , url = "/guide/intro"
       , title = "Introduction"
+      , breadcrumb = [ "Introduction" ]
       , description = "Getting started guide"
       , content = "Welcome to the documentation. This guide helps you get started."
       , tags = [ "guide", "intro" ]
+      , fields = Dict.fromList [ ( "status", [ "stable" ] ) ]
       }
     , { id = "2"
       , url = "/api/config"
       , title = "Configuration"
+      , breadcrumb = [ "Configuration" ]
       , description = "How to configure the system"
       , content = "You can configure pyohwa using a toml file."
       , tags = [ "api", "config" ]
+      , fields = Dict.fromList [ ( "status", [ "beta" ] ), ( "owner", [ "platform", "infra" ] ) ]
       }
     , { id = "3"
       , url = "/guide/advanced"
       , title = "Advanced Usage"
+      , breadcrumb = [ "Advanced Usage" ]
       , description = "Advanced features"
       , content = "This section covers advanced topics like custom themes."
       , tags = [ "guide", "advanced" ]
+      , fields = Dict.fromList [ ( "status", [ "beta" ] ) ]
+      }
+    , { id = "4"
+      , url = "/ko/install"
+      , title = "설치 안내"
+      , breadcrumb = [ "설치 안내" ]
+      , description = ""
+      , content = "패키지를 설치하는 방법입니다."
+      , tags = []
+      , fields = Dict.empty
       }
     ]
 
@@ -36,33 +160,33 @@ suite =
     describe "Search.filterResults"
         [ test "short query returns empty" <|
             \_ ->
-                Expect.equal [] (filterResults "a" sampleEntries)
+                Expect.equal [] (filterResults "a" sampleIndex)
         , test "matches by title" <|
             \_ ->
                 let
                     results =
-                        filterResults "Introduction" sampleEntries
+                        filterResults "Introduction" sampleIndex
                 in
                 Expect.equal 1 (List.length results)
         , test "matches by content" <|
             \_ ->
                 let
                     results =
-                        filterResults "toml" sampleEntries
+                        filterResults "toml" sampleIndex
                 in
                 Expect.equal 1 (List.length results)
         , test "matches by tags" <|
             \_ ->
                 let
                     results =
-                        filterResults "advanced" sampleEntries
+                        filterResults "advanced" sampleIndex
                 in
                 Expect.atLeast 1 (List.length results)
         , test "case insensitive matching" <|
             \_ ->
                 let
                     results =
-                        filterResults "CONFIGURATION" sampleEntries
+                        filterResults "CONFIGURATION" sampleIndex
                 in
                 Expect.equal 1 (List.length results)
         , test "limits to 10 results" <|
@@ -73,13 +197,147 @@ suite =
                             { id = "x"
                             , url = "/test"
                             , title = "Test Page"
+                            , breadcrumb = [ "Test Page" ]
                             , description = "test"
                             , content = "matching content"
                             , tags = []
-                            }
+                            , fields = Dict.empty
+                                  }
 
                     results =
-                        filterResults "test" manyEntries
+                        filterResults "test" (buildIndex manyEntries)
                 in
                 Expect.atMost 10 (List.length results)
+        , test "field filters narrow the results" <|
+            \_ ->
+                Expect.equal [ "/api/config", "/guide/advanced" ]
+                    (List.map .url (filterResults "status:Beta" sampleIndex))
+        , test "field filters combine with text" <|
+            \_ ->
+                Expect.equal [ "/api/config" ]
+                    (List.map .url (filterResults "owner:infra toml" sampleIndex))
+        , test "unknown keys are matched as text" <|
+            \_ ->
+                Expect.equal [] (filterResults "team:beta" sampleIndex)
+        , test "korean queries match by tokens regardless of spacing" <|
+            \_ ->
+                Expect.equal [ "/ko/install" ]
+                    (List.map .url (filterResults "설치 방법" sampleIndex))
+        , test "a single hangul syllable is a query" <|
+            \_ ->
+                Expect.equal [ "/ko/install" ]
+                    (List.map .url (filterResults "설" sampleIndex))
+        , test "single letters are not a query" <|
+            \_ ->
+                Expect.equal [] (filterResults "a b" sampleIndex)
+        , test "full-width queries are folded" <|
+            \_ ->
+                Expect.equal [ "/api/config" ]
+                    (List.map .url (filterResults "ｔｏｍｌ" sampleIndex))
+        , test "title matches rank above body matches" <|
+            \_ ->
+                let
+                    page url title content =
+                        { id = url
+                        , url = url
+                        , title = title
+                        , breadcrumb = [ title ]
+                        , description = ""
+                        , content = content
+                        , tags = []
+                        , fields = Dict.empty
+                          }
+
+                    index =
+                        buildIndex
+                            [ page "/usage" "Usage" "Run install after you install it."
+                            , page "/install" "Install" "Getting set up."
+                            ]
+                in
+                Expect.equal [ "/install", "/usage" ]
+                    (List.map .url (filterResults "install" index))
+        , test "a prefix matches longer words" <|
+            \_ ->
+                Expect.equal [ "/api/config" ]
+                    (List.map .url (filterResults "configur" sampleIndex))
+        , test "typos are tolerated" <|
+            \_ ->
+                Expect.equal [ "/guide/intro" ]
+                    (List.map .url (filterResults "documantation" sampleIndex))
+        , test "every query word must match" <|
+            \_ ->
+                Expect.equal [] (filterResults "toml themes" sampleIndex)
+        , test "sections link to their anchor with a breadcrumb" <|
+            \_ ->
+                let
+                    section =
+                        { id = "/guide#linux"
+                        , url = "/guide#linux"
+                        , title = "Linux"
+                        , breadcrumb = [ "Guide", "Install", "Linux" ]
+                        , description = ""
+                        , content = "Use apt to install the package."
+                        , tags = []
+                        , fields = Dict.empty
+                          }
+                in
+                Expect.equal [ ( "/guide#linux", [ "Guide", "Install", "Linux" ] ) ]
+                    (List.map (\result -> ( result.url, result.breadcrumb ))
+                        (filterResults "apt" (buildIndex (section :: sampleEntries)))
+                    )
+        , test "the context shows the matched word" <|
+            \_ ->
+                Expect.equal [ "You can configure pyohwa using a toml file." ]
+                    (List.map .matchContext (filterResults "configure" sampleIndex))
+        , test "a query first needs the term shards of its words" <|
+            \_ ->
+                Expect.equal [ ( Terms "in", "assets/search/terms-in.json" ) ]
+                    (missingShards "install" manifest)
+        , test "then the page shards of its best matches" <|
+            \_ ->
+                Expect.equal [ ( Pages 0, "assets/search/pages-0.json" ) ]
+                    (missingShards "install" (addTermShard "in" installShard manifest))
+        , test "requested shards are not fetched again" <|
+            \_ ->
+                Expect.equal []
+                    (missingShards "install" (requestShards (missingShards "install" manifest) manifest))
+        , test "results appear once their page shard is loaded" <|
+            \_ ->
+                manifest
+                    |> addTermShard "in" installShard
+                    |> addPageShard 0 (List.take 1 sampleEntries)
+                    |> filterResults "install"
+                    |> List.map .url
+                    |> Expect.equal [ "/guide/intro" ]
+        , test "boosted pages rank higher" <|
+            \_ ->
+                Expect.equal [ "/guide/advanced", "/guide/intro" ]
+                    (List.map .url (filterResults "guide" { sampleIndex | boosts = Dict.fromList [ ( 2, 3 ) ] }))
+        , test "stop words are dropped from queries" <|
+            \_ ->
+                Expect.equal [ "/api/config" ]
+                    (List.map .url (filterResults "of toml" { sampleIndex | stopWords = [ "of" ] }))
+        , test "terms are sharded by their first two characters" <|
+            \_ ->
+                Expect.equal [ "in", "20", "a", "x", "u193" ]
+                    (List.map shardKey [ "install", "2024", "a", "x:y", "설치" ])
+        ]
+
+
+tokenizerSuite : Test
+tokenizerSuite =
+    describe "Search.Tokenizer.tokenize"
+        [ test "splits words and lowercases" <|
+            \_ ->
+                Expect.equal [ "hello", "world", "hello" ] (tokenize "Hello, World! hello")
+        , test "hangul runs become bigrams" <|
+            \_ ->
+                Expect.equal [ "설치", "치를", "시작" ] (tokenize "설치를 시작")
+        , test "a single hangul syllable is its own token" <|
+            \_ ->
+                Expect.equal [ "api", "를" ] (tokenize "API를")
+        , test "single letters are too short to search for, single syllables are not" <|
+            \_ ->
+                Expect.equal [ False, True, True, True ]
+                    (List.map isSignificant [ "a", "팁", "漢", "ab" ])
         ]
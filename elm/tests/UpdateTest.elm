@@ -52,6 +52,13 @@ suite =
                         Update.update (ScrollToHeading "section-2") fallbackModel
                 in
                 Expect.equal "section-2" newModel.activeTocId
+        , test "SelectCodeGroupTab remembers the chosen label" <|
+            \_ ->
+                let
+                    ( newModel, _ ) =
+                        Update.update (SelectCodeGroupTab "cargo") fallbackModel
+                in
+                Expect.equal (Just "cargo") newModel.codeGroupTab
         , test "NoOp does nothing" <|
             \_ ->
                 let